@@ -16,15 +16,14 @@ echo ""
 echo "デプロイに必要なファイルは以下の場所にあります:"
 echo "  - フロントエンド (HTML/CSS/JS): ./www/"
 echo "  - WASM/JS グルーコード: ./pkg/"
-echo "  - WebSocket サーバー: ./server/ws_server.js"
-echo "  - サーバー依存関係: ./package.json, ./package-lock.json"
+echo "  - WebSocket サーバー: ./target/release/server (下の 3. でビルド)"
 echo ""
 echo "これらのファイルを本番サーバーの適切な場所に配置してください。"
 echo "サーバーでのセットアップ例:"
 echo "1. プロジェクト全体 (または必要なファイル) をサーバーにアップロード。"
 echo "2. サーバーのプロジェクトルートに移動。"
-echo "3. サーバーの依存関係をインストール: npm install --production"
-echo "4. WebSocket サーバーを起動: node server/ws_server.js &"
+echo "3. WebSocket サーバーをビルド: cargo build --release --no-default-features --features server --bin server"
+echo "4. WebSocket サーバーを起動: ./target/release/server &"
 echo "5. 静的ファイル配信サーバー (例: nginx) を設定し、./www と ./pkg の内容を配信するようにする。"
 echo "   (例: nginx で / を ./www に、/pkg を ./pkg にマッピング)"
 echo "�� デプロイが成功しますように！ 💖" 
\ No newline at end of file
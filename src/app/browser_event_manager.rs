@@ -6,17 +6,19 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{window, Event, MouseEvent, HtmlCanvasElement};
 use crate::ecs::world::World;
-use crate::network::NetworkManager;
 use crate::app::drag_handler; // update_dragged_position, handle_drag_end を呼び出すため
 use crate::log; // log マクロのみをインポート
 use log::error; // ★追加: error! マクロを正しくインポート
 
+/// A shared, swappable slot holding an attached event listener closure.
+/// Kept in an `Arc<Mutex<..>>` so the listener can later be detached from wherever it was stored.
+pub(crate) type ListenerSlot = Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>;
+
 /// Attaches mousemove and mouseup listeners to the window for drag updates and end detection.
 pub(crate) fn attach_drag_listeners(
     world_arc: Arc<Mutex<World>>,
-    network_manager_arc: Arc<Mutex<NetworkManager>>, // handle_drag_end で必要
-    window_mousemove_closure_arc: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    window_mouseup_closure_arc: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
+    window_mousemove_closure_arc: ListenerSlot,
+    window_mouseup_closure_arc: ListenerSlot,
     entity_id: usize, // The entity being dragged
     canvas: &HtmlCanvasElement, // ★ 追加: Canvas 要素への参照 ★
 ) -> Result<(), JsValue> {
@@ -64,7 +66,6 @@ pub(crate) fn attach_drag_listeners(
     {
         // Clone Arcs for the closure
         let world_arc_clone = Arc::clone(&world_arc);
-        let network_manager_arc_clone = Arc::clone(&network_manager_arc);
         let _window_mousemove_closure_arc_clone = Arc::clone(&window_mousemove_closure_arc); // Need to detach this listener
         let _window_mouseup_closure_arc_clone = Arc::clone(&window_mouseup_closure_arc);   // Need to detach this listener
         let canvas_clone = canvas.clone(); // ★ canvas も clone する ★
@@ -82,7 +83,6 @@ pub(crate) fn attach_drag_listeners(
                 // --- Call handle_drag_end logic ---
                 drag_handler::handle_drag_end(
                     &world_arc_clone,
-                    &network_manager_arc_clone,
                     entity_id,
                     end_x, // ★ 変換後の座標を使う！ ★
                     end_y, // ★ 変換後の座標を使う！ ★
@@ -118,8 +118,8 @@ pub(crate) fn attach_drag_listeners(
 
 /// Detaches the mousemove and mouseup listeners from the window.
 pub(crate) fn detach_drag_listeners(
-    window_mousemove_closure_arc: &Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    window_mouseup_closure_arc: &Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
+    window_mousemove_closure_arc: &ListenerSlot,
+    window_mouseup_closure_arc: &ListenerSlot,
 ) -> Result<(), JsValue> {
     log("Detaching drag listeners...");
     let window = window().ok_or("Failed to get window")?;
@@ -154,9 +154,9 @@ pub(crate) fn detach_drag_listeners(
 /// Detaches the canvas-specific event listeners (click, dblclick, mousedown).
 pub(crate) fn detach_canvas_listeners(
     canvas: &HtmlCanvasElement,
-    click_closure_arc: &Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    dblclick_closure_arc: &Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    mousedown_closure_arc: &Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
+    click_closure_arc: &ListenerSlot,
+    dblclick_closure_arc: &ListenerSlot,
+    mousedown_closure_arc: &ListenerSlot,
 ) -> Result<(), JsValue> {
     log("Detaching canvas listeners...");
 
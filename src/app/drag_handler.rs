@@ -16,11 +16,9 @@ use crate::components::{
     Card,
 };
 use crate::app::event_handler::{self, ClickTarget};
-use crate::protocol::{self};
-use crate::logic::rules;
+use crate::events::MoveRequested;
+use crate::logic::rules; // 組札から取り出せるかのチェック
 use crate::{log}; // log マクロを使う (ルートから)
-use super::drag_apply_handler; // ★追加: 新しいモジュールを使う
-use crate::network::NetworkManager; // ★追加★
 
 
 /// ドラッグ開始時の処理 (GameApp::handle_drag_start のロジック)
@@ -40,7 +38,11 @@ pub fn handle_drag_start(
         }
     };
 
-    let clicked_entity = Entity(entity_usize);
+    // JS から来るのは番号だけなので、今生きてるエンティティに戻す
+    let Some(clicked_entity) = world_guard.entity_from_index(entity_usize as u32) else {
+        log(&format!("handle_drag_start: entity {} no longer exists. Drag cancelled.", entity_usize));
+        return;
+    };
 
     // ★★★ Tableau の裏向きカードはドラッグ不可にするチェック ★★★
     if let (Some(stack_info), Some(card)) = (
@@ -51,6 +53,11 @@ pub fn handle_drag_start(
             log(&format!("Attempted to drag face-down card {:?} from Tableau. Drag cancelled.", clicked_entity));
             return; // ドラッグ処理をせずに終了
         }
+        // 組札からは一番上の1枚だけ、ルールで許されてる時だけ場札に戻せる
+        if matches!(stack_info.stack_type, StackType::Foundation(_)) && !rules::can_take_from_foundation(&world_guard, clicked_entity) {
+            log(&format!("Card {:?} cannot be taken off the foundation. Drag cancelled.", clicked_entity));
+            return;
+        }
     }
     // ★★★ チェックここまで ★★★
 
@@ -83,21 +90,12 @@ pub fn handle_drag_start(
         if let StackType::Tableau(tableau_index) = stack_info.stack_type {
             if card.is_face_up {
                 is_group_drag = true;
-                let clicked_pos_in_stack = stack_info.position_in_stack;
-
-                let potential_group_members: Vec<Entity> = world
-                    .get_all_entities_with_component::<StackInfo>()
-                    .into_iter()
-                    .filter(|&e| e != clicked_entity)
-                    .filter(|&e| world.get_component::<Card>(e).is_some())
-                    .filter(|&e| {
-                        world.get_component::<StackInfo>(e)
-                            .map_or(false, |si| {
-                                si.stack_type == StackType::Tableau(tableau_index) &&
-                                si.position_in_stack > clicked_pos_in_stack
-                            })
-                    })
-                    .collect();
+                // 索引で、クリックしたカードより上に積まれてるカードを取る
+                let stacks = world.stacks();
+                let potential_group_members: Vec<Entity> = match stacks.location(clicked_entity) {
+                    Some((_, index)) => stacks.cards(StackType::Tableau(tableau_index))[index + 1..].to_vec(),
+                    None => Vec::new(),
+                };
                 dragged_group.extend(potential_group_members);
                 log(&format!("  Group drag initiated for Tableau {}. Group: {:?}", tableau_index, dragged_group));
             }
@@ -150,7 +148,6 @@ pub fn update_dragged_position(
     mouse_x: f32,
     mouse_y: f32
 ) {
-    let clicked_entity = Entity(clicked_entity_id);
     let mut world_guard = match world_arc.try_lock() {
         Ok(guard) => guard,
         Err(e) => {
@@ -158,6 +155,9 @@ pub fn update_dragged_position(
             return;
         }
     };
+    let Some(clicked_entity) = world_guard.entity_from_index(clicked_entity_id as u32) else {
+        return;
+    };
 
     // DraggingInfo を代表エンティティから取得
     let dragging_info_opt = world_guard.get_component::<DraggingInfo>(clicked_entity).cloned(); // 後で World を可変借用するため clone
@@ -211,13 +211,11 @@ pub fn update_dragged_position(
 /// ドラッグ終了時の処理 (GameApp::handle_drag_end のロジック)
 pub fn handle_drag_end(
     world_arc: &Arc<Mutex<World>>,
-    network_manager_arc: &Arc<Mutex<NetworkManager>>,
     entity_usize: usize,
     end_x: f32,
     end_y: f32,
 ) {
-    let entity = Entity(entity_usize);
-    log(&format!("handle_drag_end logic started for entity: {:?}, end: ({}, {})", entity, end_x, end_y));
+    log(&format!("handle_drag_end logic started for entity: {}, end: ({}, {})", entity_usize, end_x, end_y));
 
     // --- 1. World のロックを取得 ---
     let mut world = match world_arc.lock() {
@@ -231,6 +229,11 @@ pub fn handle_drag_end(
         }
     };
 
+    let Some(entity) = world.entity_from_index(entity_usize as u32) else {
+        error!("  - Error: entity {} no longer exists in handle_drag_end. Aborting.", entity_usize);
+        return;
+    };
+
     // --- 2. DraggingInfo と元のスタック情報を取得 ---
     // ★修正: DraggingInfo がない場合はエラーログを出してリターンする形に変更★
     let dragging_info = match world.remove_component::<DraggingInfo>(entity) {
@@ -250,91 +253,31 @@ pub fn handle_drag_end(
     let target_element = event_handler::find_clicked_element(&world, end_x, end_y, Some(entity));
     log(&format!("  - Found target element: {:?}", target_element));
 
-    // --- 4. ドロップ先に基づいて処理を分岐 ---
-    let mut move_successful = false; // ★移動が成功したかどうかのフラグ★
-
-    if let Some(click_target) = target_element {
-        match click_target {
-            ClickTarget::Stack(target_stack_type) => {
-                log(&format!("  - Target is a stack area: {:?}", target_stack_type));
-                // --- 4a-i. 移動ルールのチェック ---
-                log("    Checking move validity...");
-                let is_valid = check_move_validity(&world, entity, target_stack_type);
-
-                if is_valid {
-                    // --- 4a-ii. 移動ルール OK の場合 ---
-                    log("    Move is valid! Updating world and notifying server...");
-                    let target_stack_type_for_proto: protocol::StackType = target_stack_type.into();
-                    // ★ 修正: 不要な第3引数 entity を削除 ★
-                    drag_apply_handler::update_world_and_notify_server(
-                        &mut world,
-                        network_manager_arc,
-                        target_stack_type,
-                        target_stack_type_for_proto,
-                        &dragging_info,
-                    );
-                    move_successful = true; // ★移動成功フラグを立てる★
-                } else {
-                    log("    Move is invalid.");
-                }
-            }
-            ClickTarget::Card(target_card_entity) => {
-                 log(&format!("  - Target is a card ({:?}). Checking validity...", target_card_entity));
-                 // カードへのドロップもチェックする (Tableau/Foundation)
-                 if let Some(target_stack_info) = world.get_component::<StackInfo>(target_card_entity) {
-                     let target_stack_type = target_stack_info.stack_type;
-                     let is_valid = check_move_validity(&world, entity, target_stack_type);
-                     if is_valid {
-                         log("    Move onto card's stack is valid! Updating world and notifying server...");
-                         let target_stack_type_for_proto: protocol::StackType = target_stack_type.into();
-                         // ★ 修正: 不要な第3引数 entity を削除 ★
-                         drag_apply_handler::update_world_and_notify_server(
-                             &mut world,
-                             network_manager_arc,
-                             target_stack_type,
-                             target_stack_type_for_proto,
-                             &dragging_info,
-                         );
-                         move_successful = true;
-                     } else {
-                         log("    Move onto card's stack is invalid.");
-                     }
-                 } else {
-                     log("    Could not get StackInfo for target card. Move invalid.");
-                 }
-            }
-        }
-    }
-
-    // --- 5. 移動が成功しなかった場合は元の位置に戻す ---
-    if !move_successful {
-        log("  - Move failed or target was invalid. Resetting card position.");
-        reset_card_position(&mut world, &dragging_info);
-    }
-
-    // World のロックはこのスコープを抜けるときに解放される
-
-    // ★削除: リスナーのデタッチは JS 側で行うので、ここでは何もしない
-    // detach_drag_listeners(window_mousemove_closure_arc, window_mouseup_closure_arc).unwrap_or_else(|e| {
-    //     error!("Error detaching listeners in handle_drag_end: {:?}", e);
-    // });
-    log("handle_drag_end logic finished.");
-}
+    // --- 4. ドロップ先のスタックへの移動をリクエスト ---
+    // ルールチェックと World の更新は次の tick で MoveCardSystem がやる。
+    // ダメだった時は LayoutSystem が元の位置に戻してくれるよ
+    let target_stack_type = match target_element {
+        Some(ClickTarget::Stack(target_stack_type)) => Some(target_stack_type),
+        Some(ClickTarget::Card(target_card_entity)) => world
+            .get_component::<StackInfo>(target_card_entity)
+            .map(|target_stack_info| target_stack_info.stack_type),
+        None => None,
+    };
 
-/// ヘルパー関数: 指定された移動が有効かチェックする
-fn check_move_validity(world: &World, moved_entity: Entity, target_stack_type: StackType) -> bool {
     match target_stack_type {
-        StackType::Foundation(index) => {
-            rules::can_move_to_foundation(world, moved_entity, index)
-        }
-        StackType::Tableau(index) => {
-            rules::can_move_to_tableau(world, moved_entity, index)
+        Some(target) => {
+            log(&format!("  - Requesting move of {:?} to {:?}", entity, target));
+            world.send_event(MoveRequested { entity, target });
         }
-        _ => {
-            log(&format!("    Dropping onto {:?} is not a valid target type.", target_stack_type));
-            false
+        None => {
+            // --- 5. ドロップ先が無い場合はすぐ元の位置に戻す ---
+            log("  - No drop target. Resetting card position.");
+            reset_card_position(&mut world, &dragging_info);
         }
     }
+
+    // World のロックはこのスコープを抜けるときに解放される
+    log("handle_drag_end logic finished.");
 }
 
 /// カードの位置をドラッグ開始時の座標に戻す。
@@ -355,7 +298,7 @@ pub fn reset_card_position(
         // ★ World の可変借用ができないため、一旦 clone する (非効率かも) ★
         //    または layout_calculator が &World を取るように修正する
         //    現状の実装 (calculate_card_position が &World を取る) なら clone 不要なはず
-        let original_position = crate::app::layout_calculator::calculate_card_position(
+        let original_position = crate::logic::layout_calculator::calculate_card_position(
             original_stack_type, 
             *original_pos_in_stack, // u8 を渡す
             &*world, // World の不変参照を渡す
@@ -3,17 +3,16 @@
 
 use std::sync::{Arc, Mutex};
 use crate::ecs::world::World;
-use crate::network::NetworkManager;
 use crate::ecs::entity::Entity;
 use crate::components::card::Card;
 use crate::components::stack::StackType; // StackType も使うから use するよ！
 use crate::components::position::Position; // Position も使うから use するよ！
-use crate::config::layout; // レイアウト情報も使う！
+use crate::logic::layout_calculator; // スタックの場所を計算する！
+use crate::logic::variants; // 今の遊び方で使うスタック
 use crate::app::renderer::{RENDER_CARD_WIDTH, RENDER_CARD_HEIGHT}; // カードのサイズを Renderer から持ってくる！
 use crate::logic::auto_move::find_automatic_foundation_move;
-use crate::protocol::{self, ClientMessage}; // protocol モジュールと ClientMessage をインポート
+use crate::events::MoveRequested;
 use crate::{log, error}; // log と error マクロをインポート (lib.rs から)
-use serde_json;
 // use itertools::Itertools; // ★ max_by を使うので不要になった ★
 // use crate::app::AppEvent; // ★ AppEvent が見つからないため一旦コメントアウト
 // use crate::components::dragging_info::DraggingInfo; // 現状未使用
@@ -22,22 +21,25 @@ use serde_json;
 // use web_sys::console; // 現状未使用
 
 /// ダブルクリック時の実際のロジック (lib.rs の GameApp::handle_double_click_logic から移動)
+/// 自動で置ける組札があれば、そこへの移動をリクエストするよ (処理は次の tick で MoveCardSystem がやる)。
 pub fn handle_double_click_logic(
     entity_id: usize,
     world_arc: Arc<Mutex<World>>,
-    network_manager_arc: Arc<Mutex<NetworkManager>>
 ) {
     log(&format!("  Executing double-click logic for entity_id: {}", entity_id));
-    let entity = Entity(entity_id);
-
     // World をロックして、必要な情報を取得
-    let world_guard = match world_arc.lock() {
+    let mut world_guard = match world_arc.lock() {
         Ok(w) => w,
         Err(e) => {
             error(&format!("Error locking world in handle_double_click_logic: {}", e));
             return;
         }
     };
+    // JS から来るのは番号だけなので、今生きてるエンティティに戻す
+    let Some(entity) = world_guard.entity_from_index(entity_id as u32) else {
+        error(&format!("Entity {} no longer exists in handle_double_click_logic", entity_id));
+        return;
+    };
 
     // ダブルクリックされたカードを取得
     let card_to_move = match world_guard.get_component::<Card>(entity) {
@@ -51,36 +53,11 @@ pub fn handle_double_click_logic(
     // 自動移動先を探す！🔍
     // find_automatic_foundation_move 関数を呼び出して、指定されたカードエンティティ (entity) が
     // 自動的に移動できる Foundation があるか探す。
-    // 引数には World の参照 (`&*world_guard`) とカードの Entity ID (`entity`) を渡すよ！
-    let target_stack_opt = find_automatic_foundation_move(&*world_guard, entity);
-    // World のロックを早めに解除！ これ以降 World の状態は読み書きできないけど、
-    // ロック時間が短くなって、他の処理をブロックする可能性が減るんだ。👍
-    drop(world_guard);
-
-    match target_stack_opt {
-        Some(target_stack) => {
-            // 移動先が見つかった！🎉 MakeMove メッセージを送信！🚀
-            log(&format!("  Found automatic move target: {:?} for card {:?}", target_stack, card_to_move));
-            // components::stack::StackType を protocol::StackType に変換
-            let protocol_target_stack: protocol::StackType = target_stack.into(); // From トレイトを実装済みと仮定 (protocol.rsで実装必要かも)
-            let message = ClientMessage::MakeMove { moved_entity: entity, target_stack: protocol_target_stack };
-
-            // メッセージ送信
-            match serde_json::to_string(&message) {
-                Ok(json_message) => {
-                     match network_manager_arc.lock() {
-                         Ok(nm) => {
-                             if let Err(e) = nm.send_message(&json_message) {
-                                 error(&format!("  Failed to send MakeMove message from logic: {}", e));
-                             } else {
-                                 log("  MakeMove message sent successfully from logic.");
-                             }
-                         },
-                         Err(e) => error(&format!("Failed to lock NetworkManager in logic: {}", e))
-                     }
-                }
-                Err(e) => error(&format!("Failed to serialize MakeMove message in logic: {}", e))
-            }
+    match find_automatic_foundation_move(&world_guard, entity) {
+        Some(target) => {
+            // 移動先が見つかった！🎉 MoveRequested を送る！🚀
+            log(&format!("  Found automatic move target: {:?} for card {:?}", target, card_to_move));
+            world_guard.send_event(MoveRequested { entity, target });
         }
         None => {
             // 移動先は見つからなかった...😢
@@ -120,7 +97,7 @@ pub fn find_clicked_element(world: &World, x: f32, y: f32, dragged_entity: Optio
     find_clicked_stack_area(world, x, y)
 }
 
-/// 指定された座標 (x, y) にある、最も手前 (y 座標が最大) のクリック可能な要素 (カード) を探す。
+/// 指定された座標 (x, y) にある、最も手前 (y 座標が最大、同じならスタックの上の方) のクリック可能な要素 (カード) を探す。
 /// 重なりを考慮し、一番上の要素のみを返す。
 /// ★ 修正: ドラッグ中のエンティティを無視するための引数を追加 ★
 pub fn find_topmost_clicked_card(world: &World, x: f32, y: f32, dragged_entity_to_ignore: Option<Entity>) -> Option<ClickTarget> {
@@ -136,6 +113,8 @@ pub fn find_topmost_clicked_card(world: &World, x: f32, y: f32, dragged_entity_t
         return None;
     }
 
+    let stacks = world.stacks();
+
     // 2. Position持ちエンティティをフィルタリング & マッピング
     let clicked_cards_iter = position_entities
         .into_iter()
@@ -159,8 +138,10 @@ pub fn find_topmost_clicked_card(world: &World, x: f32, y: f32, dragged_entity_t
                  let is_inside = x >= card_left && x < card_right && y >= card_top && y < card_bottom;
 
                  if is_inside {
-                     // ヒット！ このカードの Entity と Y 座標 (手前判定用) を返す
-                     Some((entity, pos.y))
+                     // ヒット！ このカードの Entity と Y 座標、スタック内の順番 (手前判定用) を返す
+                     // (Waste や Foundation はカードが同じ座標に重なるので、Y が同じならスタックの上の方が手前)
+                     let index_in_stack = stacks.location(entity).map_or(0, |(_, index)| index);
+                     Some((entity, pos.y, index_in_stack))
                  } else {
                      None // クリック範囲外
                  }
@@ -173,14 +154,14 @@ pub fn find_topmost_clicked_card(world: &World, x: f32, y: f32, dragged_entity_t
     //    max_by はイテレータを消費して Option<(Entity, f32)> を返す
     //    ★ 型アノテーションを追加して E0282 を解消 ★
     let topmost_card = clicked_cards_iter
-        .max_by(|(_entity1, y1): &(Entity, f32), (_entity2, y2): &(Entity, f32)| {
-            // f32 の比較は total_cmp を使うのが Rust では推奨！
-            y1.total_cmp(y2)
+        .max_by(|(_entity1, y1, index1): &(Entity, f32, usize), (_entity2, y2, index2): &(Entity, f32, usize)| {
+            // f32 の比較は total_cmp を使うのが Rust では推奨！ Y が同じならスタック内の順番で比べる
+            y1.total_cmp(y2).then(index1.cmp(index2))
         });
 
     // 4. 結果を Option<ClickTarget> に変換して返す
     match topmost_card {
-        Some((entity, _y_pos)) => {
+        Some((entity, _y_pos, _index)) => {
             log(&format!("  Topmost clicked card found: {:?}", entity));
             Some(ClickTarget::Card(entity)) // 正しく ClickTarget でラップして返す
         }
@@ -206,8 +187,8 @@ pub fn find_topmost_clicked_card(world: &World, x: f32, y: f32, dragged_entity_t
 ///   - `None`: どのスタックエリアにもヒットしなかった場合。
 ///
 /// # 実装詳細
-/// 1. 各スタックタイプ (`Stock`, `Waste`, `Foundation` 0-3, `Tableau` 0-6) の基本的な表示領域（通常はカード1枚分のサイズ）を計算する。
-///    - `src/config/layout.rs` の定数とカードサイズ (`RENDER_CARD_WIDTH`, `RENDER_CARD_HEIGHT`) を使うよ。
+/// 1. 今の遊び方 (`variants::active`) が使う各スタックの基本的な表示領域（カード1枚分のサイズ）を計算する。
+///    - 場所は `layout_calculator::calculate_card_position`、サイズは (`RENDER_CARD_WIDTH`, `RENDER_CARD_HEIGHT`) を使うよ。
 /// 2. 順番に各スタックの領域をチェックし、クリック座標 `(x, y)` が領域内に含まれていれば、
 ///    対応する `StackType` を `ClickTarget::Stack` でラップして `Some` で返す。最初に見つかった時点で終了！
 /// 3. 全てのスタックエリアをチェックしてもヒットしなかった場合は `None` を返す。
@@ -215,87 +196,24 @@ pub fn find_topmost_clicked_card(world: &World, x: f32, y: f32, dragged_entity_t
 /// # 注意点
 /// - この関数は `find_topmost_clicked_card` の後に呼ばれる前提だよ。
 /// - そのため、ここでの判定は「カード以外のスタックのพื้นฐาน的な場所」をクリックしたかどうかのチェックが主になるよ。
-pub fn find_clicked_stack_area(_world: &World, x: f32, y: f32) -> Option<ClickTarget> {
+pub fn find_clicked_stack_area(world: &World, x: f32, y: f32) -> Option<ClickTarget> {
     log("  Checking for clicked stack area...");
 
     // カードのサイズを取得 (定数)
     let card_width = RENDER_CARD_WIDTH as f32;
     let card_height = RENDER_CARD_HEIGHT as f32;
 
-    // 1. Stock エリアの判定
-    let stock_left = layout::STOCK_POS_X;
-    let stock_top = layout::STOCK_POS_Y;
-    let stock_right = stock_left + card_width;
-    let stock_bottom = stock_top + card_height;
-    if x >= stock_left && x < stock_right && y >= stock_top && y < stock_bottom {
-        log("    -> Hit Stock area.");
-        return Some(ClickTarget::Stack(StackType::Stock));
-    }
-
-    // 2. Waste エリアの判定
-    let waste_left = layout::WASTE_POS_X;
-    let waste_top = layout::WASTE_POS_Y;
-    let waste_right = waste_left + card_width;
-    let waste_bottom = waste_top + card_height;
-    if x >= waste_left && x < waste_right && y >= waste_top && y < waste_bottom {
-        // Waste エリアは、上にカードがなくてもクリックターゲットになりうる
-        // (Waste の一番上のカードをクリックする場合は find_topmost_clicked_card で判定される)
-        // ここでは Waste のベースエリアをクリックしたと判断する
-        log("    -> Hit Waste area.");
-        return Some(ClickTarget::Stack(StackType::Waste));
-    }
-
-    // 3. Foundation エリア (0-3) の判定
-    for i in 0..4 {
-        let foundation_left = layout::FOUNDATION_START_X + (layout::FOUNDATION_X_OFFSET * i as f32);
-        let foundation_top = layout::FOUNDATION_START_Y;
-        let foundation_right = foundation_left + card_width;
-        let foundation_bottom = foundation_top + card_height;
-        if x >= foundation_left && x < foundation_right && y >= foundation_top && y < foundation_bottom {
-            // Foundation エリアは、カードがあってもなくてもクリック/ドロップターゲットになりうる
-            let stack_type = StackType::Foundation(i);
-            log(&format!("    -> Hit Foundation {} area.", i));
+    // 今の遊び方で使うスタックの、一番下のカードの場所 (カード1枚分) を順番に見る。
+    // 空の場札に K を置く時や、空の組札・フリーセルに置く時もここで当たるよ。
+    // (カードが積まれた部分は find_topmost_clicked_card で判定済み)
+    for stack_type in variants::active(world).kinds().all() {
+        let base = layout_calculator::calculate_card_position(stack_type, 0, world);
+        if x >= base.x && x < base.x + card_width && y >= base.y && y < base.y + card_height {
+            log(&format!("    -> Hit {:?} area.", stack_type));
             return Some(ClickTarget::Stack(stack_type));
         }
     }
 
-    // 4. Tableau エリア (0-6) の判定
-    for i in 0..7 {
-        let tableau_left = layout::TABLEAU_START_X + (layout::TABLEAU_X_OFFSET * i as f32);
-        let tableau_top = layout::TABLEAU_START_Y;
-        // Tableau の底 (クリック/ドロップ可能なエリアの下限) をどう定義するか？
-        // -> とりあえず、一番上のカード配置場所 (高さ card_height 分) のみを判定対象とする
-        //    (空の Tableau 列に K を置くケースを想定)
-        // -> TODO: より洗練された判定が必要かも (列全体の高さなど)
-        let tableau_right = tableau_left + card_width;
-        let tableau_bottom = tableau_top + card_height; // 最初のカード位置のみ
-
-        if x >= tableau_left && x < tableau_right && y >= tableau_top && y < tableau_bottom {
-            // 空の Tableau エリア (カードがない場所) をクリック/ドロップした場合
-            // (カードがある場合は find_topmost_clicked_card で処理されるはず)
-            let stack_type = StackType::Tableau(i);
-            log(&format!("    -> Hit empty Tableau {} base area.", i));
-            return Some(ClickTarget::Stack(stack_type));
-
-            // TODO: Tableau のカードが積まれた部分の下のエリアも判定するか？
-            // let cards_in_tableau = world.query::<(&Card, &Position, &StackMembership)>()
-            //     .filter(|(_, _, membership)| membership.stack_type == StackType::Tableau(i))
-            //     .collect::<Vec<_>>();
-            // let tableau_stack_height = if cards_in_tableau.is_empty() {
-            //     card_height
-            // } else {
-            //     // 一番下のカードの位置を見つける必要がある
-            //     // この実装は仮
-            //     tableau_top + card_height + (cards_in_tableau.len() as f32 * layout::TABLEAU_Y_OFFSET_FACE_UP)
-            // };
-            // let full_tableau_bottom = tableau_top + tableau_stack_height; // 仮
-            // if x >= tableau_left && x < tableau_right && y >= tableau_top && y < full_tableau_bottom {
-                 // ...
-            // }
-
-        }
-    }
-
     // どのスタックエリアにもヒットしなかった場合
     log("    -> No stack area hit.");
     None
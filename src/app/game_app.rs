@@ -14,18 +14,26 @@ use log::error;
 // use log::{info, error}; // ★★★ 削除: lib.rs のマクロと衝突するため ★★★
 
 use crate::ecs::world::World;
-use crate::network::NetworkManager;
-use crate::protocol::{
-    self, // protocol モジュール自体も使う
-    ServerMessage, PlayerId,
-    ClientMessage // ClientMessage も使う
-};
+use crate::network::{ConnectionStatus, NetworkManager};
+use crate::protocol::ServerMessage;
 use crate::systems::deal_system::DealInitialCardsSystem;
+use crate::systems::{self, network_outbox_system::Outbox, replay_system::ReplaySystem};
+use crate::ecs::schedule::Schedule;
+use crate::ecs::entity_map::EntityMap;
+use crate::events::{AutoCompleteRequested, RedoRequested, StockClicked, UndoRequested};
 use crate::components::stack::StackType;
+use crate::components::game_state::{GameClock, GameState};
+use crate::components::score::{Score, ScoringMode};
+use crate::logic::auto_move;
+use crate::logic::deck;
+use crate::logic::game_record::{self, GameRecord};
+use crate::logic::hints::{self, ActiveHint};
+use crate::logic::variants::Variant;
+use crate::persistence::{self, GameStorage, LocalStorage, MemoryStorage};
+use crate::components::player::LocalPlayer;
+use crate::config::GameRules;
 use crate::app::event_handler::{self, ClickTarget}; // event_handler モジュールと ClickTarget を use する！
 use crate::log; // log と error マクロをインポート (lib.rs から)
-use crate::ecs::entity::Entity; // Entity を使うためにインポート
-use serde_json;
 // --- レイアウト情報とレンダラー定数をインポート --- ★追加★
 
 // ★修正: network_handler ではなく、新しいモジュールを use する★
@@ -43,17 +51,11 @@ use super::drag_handler;
 use crate::app::state_getter;
 
 // ★追加: browser_event_manager モジュールを use する★
-use crate::app::browser_event_manager; // ★ 警告修正: 未使用のため削除 ★ ← 元に戻す！
+use crate::app::browser_event_manager::{self, ListenerSlot}; // ★ 警告修正: 未使用のため削除 ★ ← 元に戻す！
 
 // ★修正: Result を返すように変更 (listener attach のエラーハンドル)
 use wasm_bindgen::JsValue;
 
-// ★ 追加 ★
-// use crate::app::stock_handler; // ★ 削除 ★
-
-// ★ 追加: layout_calculator と components を使うための use 文 ★
-use crate::app::layout_calculator;
-use crate::components::{Card, Position, StackInfo}; // ★ self を削除 ★
 
 // --- ゲーム全体のアプリケーション状態を管理する構造体 ---
 #[wasm_bindgen]
@@ -61,20 +63,27 @@ pub struct GameApp {
     world: Arc<Mutex<World>>,
     network_manager: Arc<Mutex<NetworkManager>>,
     message_queue: Arc<Mutex<VecDeque<ServerMessage>>>,
-    my_player_id: Arc<Mutex<Option<PlayerId>>>,
     // DealInitialCardsSystem のインスタンスを持っておこう！ (状態を持たないので Clone でも Default でもOK)
     deal_system: DealInitialCardsSystem,
+    // 毎フレーム tick() で回すシステムたち (ステージ順に実行される)
+    schedule: Schedule,
+    // 棋譜の再生中なら、その再生器と再生用の World (遊んでる World とは別。再生中はこっちを描画する)
+    replay: Option<ReplaySystem>,
+    replay_world: Arc<Mutex<World>>,
+    // セーブデータの保存先 (localStorage が使えなければメモリ) と、最後にセーブした時点 (シード, 棋譜の手数)
+    storage: Box<dyn GameStorage>,
+    last_saved: Option<(u64, usize)>,
     // ★★★ 削除: 汎用的なリスナー保持 Vec ★★★
     // event_closures: Arc<Mutex<Vec<Closure<dyn FnMut(Event)>>>>,
 
     // ★★★ 追加: Canvas 用の個別リスナー保持フィールド ★★★
-    canvas_click_closure: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    canvas_dblclick_closure: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    canvas_mousedown_closure: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
+    canvas_click_closure: ListenerSlot,
+    canvas_dblclick_closure: ListenerSlot,
+    canvas_mousedown_closure: ListenerSlot,
 
     // ★ Window にアタッチする MouseMove/MouseUp リスナー (これは元々あった)
-    window_mousemove_closure: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
-    window_mouseup_closure: Arc<Mutex<Option<Closure<dyn FnMut(Event)>>>>,
+    window_mousemove_closure: ListenerSlot,
+    window_mouseup_closure: ListenerSlot,
 
     // Canvas 要素と 2D コンテキスト (これも元々あった)
     canvas: HtmlCanvasElement,
@@ -85,6 +94,7 @@ pub struct GameApp {
 #[wasm_bindgen]
 impl GameApp {
     #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default, clippy::arc_with_non_send_sync)] // JS から new GameApp() で作る前提 / Closure は Send じゃないけど Weak 参照で共有したいので Arc のまま
     pub fn new() -> Self {
         // log() は lib.rs で定義されているため、ここでは直接使えない
         // 必要なら crate::log() などで参照するか、GameApp 内で log を呼ぶ関数を用意する
@@ -101,8 +111,7 @@ impl GameApp {
             .expect("Failed to initialize canvas and context");
 
         // --- その他のフィールド初期化 ---
-        let my_player_id_arc = Arc::new(Mutex::new(None));
-        let deal_system = DealInitialCardsSystem::default();
+        let deal_system = DealInitialCardsSystem;
         let canvas_click_closure_arc = Arc::new(Mutex::new(None));
         let canvas_dblclick_closure_arc = Arc::new(Mutex::new(None));
         let canvas_mousedown_closure_arc = Arc::new(Mutex::new(None));
@@ -114,8 +123,18 @@ impl GameApp {
             world: world_arc,
             network_manager: network_manager_arc,
             message_queue: message_queue_arc,
-            my_player_id: my_player_id_arc,
             deal_system,
+            schedule: systems::build_schedule(),
+            replay: None,
+            replay_world: Arc::new(Mutex::new(World::new())),
+            storage: match LocalStorage::new() {
+                Ok(storage) => Box::new(storage),
+                Err(e) => {
+                    log(&format!("GameApp: {}. Saving to memory instead.", e));
+                    Box::new(MemoryStorage::default())
+                }
+            },
+            last_saved: None,
             canvas_click_closure: canvas_click_closure_arc,
             canvas_dblclick_closure: canvas_dblclick_closure_arc,
             canvas_mousedown_closure: canvas_mousedown_closure_arc,
@@ -296,79 +315,335 @@ impl GameApp {
     }
 
     // カード移動メッセージ送信 (引数を JSON 文字列に戻す)
+    // JS から来るのはこっちのエンティティの番号なので、サーバーのエンティティに変換してから送るよ
     #[wasm_bindgen]
     pub fn send_make_move(&self, moved_entity_id: usize, target_stack_json: String) { // 引数を JSON 文字列に戻す
-        let moved_entity = Entity(moved_entity_id); // usize から Entity へ
-
-        // JSON 文字列をデシリアライズ
-        match serde_json::from_str::<protocol::StackType>(&target_stack_json) {
-            Ok(target_stack) => {
-                // デシリアライズ成功
-                let message = ClientMessage::MakeMove { moved_entity, target_stack };
-
-                match serde_json::to_string(&message) {
-                    Ok(json_message) => {
-                         match self.network_manager.lock() {
-                             Ok(nm) => {
-                                 if let Err(_e) = nm.send_message(&json_message) {
-                                     // error!("Failed to send MakeMove message: {}", _e);
-                                 } else {
-                                     // info!("MakeMove message sent: {:?}", message);
-                                 }
-                             },
-                             Err(_e) => {} // error!("Failed to lock NetworkManager to send MakeMove: {}", _e)
-                         }
-                    }
-                    Err(_e) => {} // error!("Failed to serialize MakeMove message: {}", _e)
-                }
-            }
-            Err(_e) => {
-                // JSON デシリアライズ失敗
-                // error!("Failed to deserialize target_stack_json: {}. JSON: {}", _e, target_stack_json);
-            }
+        let remote_entity = {
+            let world = self.world.lock().expect("Failed to lock world for send_make_move");
+            world
+                .entity_from_index(moved_entity_id as u32)
+                .and_then(|local| world.resource::<EntityMap>()?.remote(local))
+        };
+        match remote_entity {
+            Some(moved_entity) => super::network_sender::send_make_move(&self.network_manager, moved_entity, target_stack_json),
+            None => error!("send_make_move: entity {} is not known to the server.", moved_entity_id),
         }
     }
 
     /// JS から呼び出され、受信メッセージキューを処理し、
     /// もしサーバーから移動拒否メッセージがあればそのカードID (usize) を返す。
     /// なければ None (JS側では undefined) を返す。
+    /// サーバーからの差分を取りこぼしてたら、ここでまるごと送り直してもらうよ。
     #[wasm_bindgen]
     pub fn process_received_messages(&mut self) -> Option<usize> { 
         let results = network_receiver::process_received_messages(
             &self.message_queue,
-            &self.my_player_id,
             &self.world,
         );
 
         // 結果の中から MoveRejected を探す
+        let mut rejected = None;
         for result in results {
-            if let ProcessedMessageResult::MoveRejected { entity_id, reason: _ } = result {
-                /* log(&format!(
-                    "GameApp: MoveRejected event found for entity {:?}. Returning Some({}) to JS.", 
-                    entity_id, entity_id.0
-                )); */
-                return Some(entity_id.0);
+            match result {
+                ProcessedMessageResult::MoveRejected { entity_id, reason: _ } => {
+                    rejected.get_or_insert(entity_id.index() as usize);
+                }
+                ProcessedMessageResult::ResyncNeeded => super::network_sender::send_request_game_state(&self.network_manager),
+                // 他のイベントタイプ (StateChanged など) はここでは特に処理しない
+                // (StateChanged などで画面更新が必要な場合は、別途JS側で render を呼ぶなどの連携が必要)
+                ProcessedMessageResult::StateChanged | ProcessedMessageResult::Nothing => {}
+            }
+        }
+        rejected
+    }
+
+    /// 1フレーム分ゲームを進めるよ！JS の gameLoop から毎フレーム呼ばれる。⏱️
+    ///
+    /// 1. 受信メッセージを World に反映 (`process_received_messages` と同じ)
+    /// 2. スケジュールのシステムを全部実行 (`dt` は前のフレームからの経過秒数)
+    /// 3. システムが `Outbox` に積んだメッセージをサーバーに送る
+    ///
+    /// 戻り値は `process_received_messages` と同じで、サーバーに移動を拒否されたカードの ID。
+    #[wasm_bindgen]
+    pub fn tick(&mut self, dt: f64) -> Option<usize> {
+        let rejected = self.process_received_messages();
+
+        let outgoing = {
+            let world_arc = Arc::clone(&self.world);
+            let mut world = world_arc.lock().expect("Failed to lock world for tick");
+            self.schedule.tick(&mut world, dt);
+            // 手が進んだ (か配り直した) らセーブ
+            if save_point(&world) != self.last_saved {
+                self.save_world(&world);
+            }
+            world.resource_mut::<Outbox>().map(|outbox| std::mem::take(&mut outbox.0)).unwrap_or_default()
+        };
+        if let Some(replay) = self.replay.as_mut() {
+            let mut world = self.replay_world.lock().expect("Failed to lock replay world");
+            if let Err(e) = replay.advance(&mut world, dt) {
+                error!("Replay stopped: {}", e);
+            }
+        }
+
+        // World のロックは送信前に外しておく
+        for message in outgoing {
+            if let Err(e) = super::network_sender::send_serialized_message(&self.network_manager, message.clone()) {
+                error!("Failed to send {:?}: {}", message, e);
             }
-            // 他のイベントタイプ (StateChanged など) はここでは特に処理しない
-            // (StateChanged などで画面更新が必要な場合は、別途JS側で render を呼ぶなどの連携が必要)
         }
 
-        // MoveRejected が見つからなかった場合
-        // log("GameApp: No MoveRejected event found in processed messages. Returning None to JS."); // ★ コメントアウト ★
-        None // None を返す
+        rejected
     }
 
-    // JSから初期カード配置を実行するためのメソッド
+    // JSから初期カード配置を実行するためのメソッド (シードはランダム)
     #[wasm_bindgen]
     pub fn deal_initial_cards(&self) {
+        self.deal_with_seed(deck::random_seed());
+    }
+
+    /// 決まったシードでカードを配る。同じシードなら、どのブラウザでも同じ盤面になるよ🎲
+    /// (バグ報告の再現やデイリーチャレンジ用。JS からは BigInt で渡してね)
+    #[wasm_bindgen]
+    pub fn deal_with_seed(&self, seed: u64) {
         // ★修正: app::init_handler の関数を呼び出す！★
         super::init_handler::deal_initial_cards( // app:: -> super::
             &self.world,
             &self.network_manager,
-            &self.deal_system
+            &self.deal_system,
+            seed,
         );
     }
 
+    /// 今のゲームを配った時のシード (分からなければ `None`)。
+    #[wasm_bindgen]
+    pub fn current_seed(&self) -> Option<u64> {
+        let world = self.world.lock().expect("Failed to lock world");
+        world.resource::<GameState>().and_then(|state| state.seed)
+    }
+
+    /// 遊び方を選ぶ (`"klondike"`, `"spider-1"`, `"spider-2"`, `"spider-4"`, `"freecell"`, `"yukon"`)。
+    /// 今の盤面はそのままで、次に配った時から新しい遊び方になるよ。知らない名前ならエラー。
+    #[wasm_bindgen]
+    pub fn set_variant(&self, name: String) -> Result<(), JsValue> {
+        let variant = Variant::from_name(&name).map_err(|e| JsValue::from_str(&e))?;
+        let mut world = self.world.lock().expect("Failed to lock world");
+        world.insert_resource(variant);
+        log(&format!("Game variant set to {}", variant.name()));
+        Ok(())
+    }
+
+    /// 山札のルールを選ぶ: めくる枚数 (1 か 3)、何周までできるか (`undefined` なら無制限)、
+    /// 捨て札を何枚ずらして見せるか、組札から場札に戻せるか。配る直前に呼んでね (棋譜には配った時のルールが残る)。
+    /// おかしな値ならエラー。マルチプレイ中はサーバーのルールで上書きされる。
+    #[wasm_bindgen]
+    pub fn set_rules(&self, draw_count: u8, max_passes: Option<u32>, waste_fan: u8, foundation_to_tableau: bool) -> Result<(), JsValue> {
+        let rules = GameRules::new(draw_count, max_passes, waste_fan)
+            .map_err(|e| JsValue::from_str(&e))?
+            .with_foundation_to_tableau(foundation_to_tableau);
+        let mut world = self.world.lock().expect("Failed to lock world");
+        world.insert_resource(rules);
+        log(&format!("Game rules set to {:?}", rules));
+        Ok(())
+    }
+
+    /// 点数の数え方を選ぶ (`"standard"` / `"vegas"`)。点数は0からやり直しで、次に配った時から数えるよ。
+    /// 知らない名前ならエラー。マルチプレイ中はサーバーの点数で上書きされる。
+    #[wasm_bindgen]
+    pub fn set_scoring_mode(&self, name: String) -> Result<(), JsValue> {
+        let mode = ScoringMode::from_name(&name).map_err(|e| JsValue::from_str(&e))?;
+        let mut world = self.world.lock().expect("Failed to lock world");
+        world.insert_resource(Score::new(mode));
+        Ok(())
+    }
+
+    /// 今の点数 (ベガスなら持ち越し込みの所持金)。
+    #[wasm_bindgen]
+    pub fn current_score(&self) -> i32 {
+        let world = self.world.lock().expect("Failed to lock world");
+        Score::from_world(&world).points
+    }
+
+    /// 最初の1手からの経過秒数 (一時停止中と勝った後は止まってる)。
+    #[wasm_bindgen]
+    pub fn elapsed_seconds(&self) -> f64 {
+        let world = self.world.lock().expect("Failed to lock world");
+        GameClock::from_world(&world).elapsed_seconds
+    }
+
+    /// このゲームの手数。
+    #[wasm_bindgen]
+    pub fn move_count(&self) -> u32 {
+        let world = self.world.lock().expect("Failed to lock world");
+        GameClock::from_world(&world).moves
+    }
+
+    /// タブが見えてる / 隠れた。隠れてる間は時計を止めるよ (JS の `visibilitychange` から呼んでね)。
+    /// 隠れる時は経過時間も込みでセーブしておく (そのままタブを閉じられても大丈夫なように)。
+    #[wasm_bindgen]
+    pub fn set_page_visible(&mut self, visible: bool) {
+        let world_arc = Arc::clone(&self.world);
+        let mut world = world_arc.lock().expect("Failed to lock world");
+        if let Some(clock) = world.resource_mut::<GameClock>() {
+            clock.paused = !visible;
+        }
+        if !visible && save_point(&world).is_some() {
+            self.save_world(&world);
+        }
+    }
+
+    /// 今のゲームをセーブする (普段は手が進むたびに勝手にセーブしてるよ)。
+    #[wasm_bindgen]
+    pub fn save_game(&mut self) -> Result<(), JsValue> {
+        let world_arc = Arc::clone(&self.world);
+        let world = world_arc.lock().expect("Failed to lock world");
+        persistence::save(&world, self.storage.as_mut()).map_err(|e| JsValue::from_str(&e))?;
+        self.last_saved = save_point(&world);
+        Ok(())
+    }
+
+    /// セーブしてあったゲームを続きから遊ぶ (ページを読み込んだ直後に JS が呼ぶ)。
+    /// セーブが無ければ `false`。壊れてたり新しすぎる形式だったらエラー。
+    #[wasm_bindgen]
+    pub fn resume_saved_game(&mut self) -> Result<bool, JsValue> {
+        let Some(saved) = persistence::load(self.storage.as_ref()).map_err(|e| JsValue::from_str(&e))? else {
+            return Ok(false);
+        };
+        super::state_handler::restore_saved_game(&self.world, &saved);
+        self.last_saved = save_point(&self.world.lock().expect("Failed to lock world"));
+        Ok(true)
+    }
+
+    /// セーブデータを消す。
+    #[wasm_bindgen]
+    pub fn clear_saved_game(&mut self) -> Result<(), JsValue> {
+        persistence::clear(self.storage.as_mut()).map_err(|e| JsValue::from_str(&e))?;
+        self.last_saved = None;
+        Ok(())
+    }
+
+    /// 今の遊び方の名前 (`set_variant` に渡すのと同じ形)。
+    #[wasm_bindgen]
+    pub fn current_variant(&self) -> String {
+        let world = self.world.lock().expect("Failed to lock world");
+        Variant::from_world(&world).name().to_string()
+    }
+
+    /// 今の山札のルール (`GameRules` の JSON)。
+    #[wasm_bindgen]
+    pub fn current_rules_json(&self) -> String {
+        let world = self.world.lock().expect("Failed to lock world");
+        serde_json::to_string(&GameRules::from_world(&world)).unwrap_or_default()
+    }
+
+    /// 棋譜を再生する。遊んでる盤面はそのままで、再生をやめる (`stop_replay`) まで再生用の盤面を描くよ📼
+    /// 最初は配った直後。読めない棋譜ならエラー。
+    #[wasm_bindgen]
+    pub fn start_replay(&mut self, json: String) -> Result<(), JsValue> {
+        let record = GameRecord::from_json(&json).map_err(|e| JsValue::from_str(&e))?;
+        let mut replay = ReplaySystem::new(record);
+        replay.rebuild(&mut self.replay_world.lock().expect("Failed to lock replay world"));
+        self.replay = Some(replay);
+        Ok(())
+    }
+
+    /// 再生をやめて、遊んでる盤面に戻る。
+    #[wasm_bindgen]
+    pub fn stop_replay(&mut self) {
+        self.replay = None;
+    }
+
+    /// 再生中の棋譜を1手進める。最後まで行ってたら `false`。
+    #[wasm_bindgen]
+    pub fn replay_step_forward(&mut self) -> Result<bool, JsValue> {
+        self.with_replay(|replay, world| replay.step_forward(world))
+    }
+
+    /// 再生中の棋譜を1手戻す。最初なら `false`。
+    #[wasm_bindgen]
+    pub fn replay_step_back(&mut self) -> Result<bool, JsValue> {
+        self.with_replay(|replay, world| replay.step_back(world))
+    }
+
+    /// `index` 手目まで指した盤面に飛ぶ (0 なら配った直後)。
+    #[wasm_bindgen]
+    pub fn replay_seek(&mut self, index: usize) -> Result<(), JsValue> {
+        self.with_replay(|replay, world| replay.seek(world, index))
+    }
+
+    /// 記録された時間どおりに `speed` 倍速で自動再生する (0 以下なら止める)。
+    #[wasm_bindgen]
+    pub fn replay_play(&mut self, speed: f64) -> Result<(), JsValue> {
+        self.with_replay(|replay, _| {
+            replay.play(speed);
+            Ok(())
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn replay_pause(&mut self) -> Result<(), JsValue> {
+        self.with_replay(|replay, _| {
+            replay.pause();
+            Ok(())
+        })
+    }
+
+    /// 再生中なら今何手目か (再生してなければ `None`)。
+    #[wasm_bindgen]
+    pub fn replay_position(&self) -> Option<usize> {
+        self.replay.as_ref().map(ReplaySystem::position)
+    }
+
+    /// 自動再生中？ (最後まで行ったら勝手に止まるよ)
+    #[wasm_bindgen]
+    pub fn replay_is_playing(&self) -> bool {
+        self.replay.as_ref().is_some_and(ReplaySystem::is_playing)
+    }
+
+    /// 再生中の棋譜の手の数。
+    #[wasm_bindgen]
+    pub fn replay_length(&self) -> Option<usize> {
+        self.replay.as_ref().map(ReplaySystem::len)
+    }
+
+    /// 今のゲームの棋譜 (シード・遊び方・ルール・手の列) を JSON で返す。バグ報告に貼ってね📜
+    /// まだ配ってなければエラー。
+    #[wasm_bindgen]
+    pub fn export_game(&self) -> Result<String, JsValue> {
+        let world = self.world.lock().expect("Failed to lock world");
+        let record = world.resource::<GameRecord>().ok_or_else(|| JsValue::from_str("No game has been dealt yet"))?;
+        record.to_json().map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// `export_game` の棋譜を読み込んで、同じシードとルールで配り直してから、記録された手を1手ずつ再生する。
+    /// 読めない棋譜ならエラー。途中の手が却下されたら (盤面がずれたら) そこで止めてエラーを返すよ。
+    /// サーバーにつながってる間はエラー (みんなの盤面を勝手に配り直しちゃうので)。
+    #[wasm_bindgen]
+    pub fn import_game(&mut self, json: String) -> Result<(), JsValue> {
+        let status = self.network_manager.lock().expect("Failed to lock NetworkManager for status").get_status();
+        if status == ConnectionStatus::Connected {
+            return Err(JsValue::from_str("Cannot import a game record while connected to the server"));
+        }
+        let record = GameRecord::from_json(&json).map_err(|e| JsValue::from_str(&e))?;
+        {
+            let mut world = self.world.lock().expect("Failed to lock world");
+            world.insert_resource(record.variant);
+            world.insert_resource(record.rules);
+            world.insert_resource(Score::new(record.scoring));
+        }
+        self.deal_with_seed(record.seed);
+
+        // いつものスケジュールで再生する (イベントの読み位置がずれないように) けど、
+        // 再生した手はサーバーに送るものじゃないので、Outbox に積まれた分は捨てる
+        let mut world = self.world.lock().expect("Failed to lock world");
+        let replayed = game_record::replay(&mut world, &mut self.schedule, &record.moves);
+        if let Some(outbox) = world.resource_mut::<Outbox>() {
+            outbox.0.clear();
+        }
+        replayed.map_err(|e| JsValue::from_str(&e))?;
+        log(&format!("Imported a game record with {} move(s) (seed {})", record.moves.len(), record.seed));
+        Ok(())
+    }
+
     /// WASM 側 (`GameApp`) が保持しているゲームの世界 (`World`) の現在の状態を、
     /// JSON 文字列形式で取得するためのメソッドだよ！ JavaScript 側から呼び出して、
     /// デバッグ目的でコンソールに表示したり、画面描画に使ったりすることを想定してるよ！ ✨
@@ -400,26 +675,32 @@ impl GameApp {
     // 自分の Player ID を返す (デバッグ用)
     #[wasm_bindgen]
     pub fn get_my_player_id_debug(&self) -> Option<u32> {
-        self.my_player_id.lock().expect("Failed to lock my_player_id").map(|id| id)
+        // 自分の ID は World の LocalPlayer リソースにある (GameJoined を受け取るまでは無い)
+        let world = self.world.lock().expect("Failed to lock world");
+        world.resource::<LocalPlayer>().map(|local_player| local_player.id)
     }
 
     /// カードがダブルクリックされた時の処理 (JSから呼び出される元のメソッド)
     #[wasm_bindgen]
     pub fn handle_double_click(&self, entity_id: usize) {
+        if self.replay.is_some() {
+            return; // 再生中は盤面を触れない
+        }
         log(&format!("GameApp: handle_double_click called for entity_id: {}", entity_id));
         // event_handler のロジック関数を呼び出す
         event_handler::handle_double_click_logic(
             entity_id,
             Arc::clone(&self.world), // Arc をクローンして渡す
-            Arc::clone(&self.network_manager) // Arc をクローンして渡す
         );
     }
 
     /// Rust側で Canvas にゲーム画面を描画する関数
     #[wasm_bindgen]
     pub fn render_game_rust(&self) -> Result<(), JsValue> {
+        // 棋譜の再生中は再生用の World を描く
+        let world = if self.replay.is_some() { &self.replay_world } else { &self.world };
         super::renderer::render_game_rust( // app:: -> super::
-            &self.world,
+            world,
             &self.canvas,
             &self.context
         // JsValue に変換する必要があるので .map_err を追加
@@ -439,6 +720,9 @@ impl GameApp {
     ///    スタッククリック時のアクション (例: 山札クリックでカードをめくる) などを実装していくよ！
     #[wasm_bindgen]
     pub fn handle_click(&mut self, x: f32, y: f32) {
+        if self.replay.is_some() {
+            return; // 再生中は盤面を触れない
+        }
         // ★ 早期リターンを追加 (デバッグ用、または不要なら削除) ★
         // log(&format!("GameApp::handle_click received: ({}, {})", x, y));
         // return; // ここで一旦止めてみる
@@ -492,131 +776,94 @@ impl GameApp {
         }
     }
 
+    /// `world` をセーブする。失敗してもゲームは続けたいので、ログに出すだけ。
+    fn save_world(&mut self, world: &World) {
+        match persistence::save(world, self.storage.as_mut()) {
+            Ok(()) => self.last_saved = save_point(world),
+            Err(e) => error!("Failed to save the game: {}", e),
+        }
+    }
+
+    /// 再生中の `ReplaySystem` と再生用の World で `f` を呼ぶ。再生してなければエラー。
+    fn with_replay<T>(&mut self, f: impl FnOnce(&mut ReplaySystem, &mut World) -> Result<T, String>) -> Result<T, JsValue> {
+        let replay = self.replay.as_mut().ok_or_else(|| JsValue::from_str("No replay is loaded"))?;
+        let mut world = self.replay_world.lock().expect("Failed to lock replay world");
+        f(replay, &mut world).map_err(|e| JsValue::from_str(&e))
+    }
+
     /// 山札 (Stock) がクリックされたときの処理
+    /// Stock にカードがあれば1枚 Waste にめくる。空なら Waste を全部 Stock に戻す。
+    /// 実際の操作は logic::stock_handler に任せて (サーバーと同じ処理)、結果をサーバーに通知するよ。
     fn handle_stock_click(&mut self) {
         log("handle_stock_click called.");
-        let mut world = match self.world.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => {
-                error!("World mutex poisoned in handle_stock_click: {:?}. Aborting.", poisoned);
-                return;
-            }
-        };
-        // NetworkManager は現時点では使わないが、将来のために残しておく
-        // let network_manager = self.network_manager.clone();
-
-        // --- 1. Stock の一番上のカードを探す ---
-        // position_in_stack が最も大きいものを探す
-        let top_stock_card_entity = world
-            .get_all_entities_with_component::<StackInfo>()
-            .into_iter()
-            .filter(|e| world.get_component::<StackInfo>(*e).map_or(false, |si| si.stack_type == StackType::Stock))
-            .max_by_key(|e| world.get_component::<StackInfo>(*e).unwrap().position_in_stack); // unwrap はフィルタリング後なので安全なはず
-
-        if let Some(top_card_entity) = top_stock_card_entity {
-            // --- 2. Stock にカードがある場合: Waste に移動 ---
-            log(&format!("Found top card in Stock: {:?}", top_card_entity));
-
-            // Waste の現在のカード数を取得 (次の position_in_stack のため)
-            let waste_card_count = world
-                .get_all_entities_with_component::<StackInfo>()
-                .into_iter()
-                .filter(|e| world.get_component::<StackInfo>(*e).map_or(false, |si| si.stack_type == StackType::Waste))
-                .count();
-            let next_waste_pos = waste_card_count as u8;
-
-            // ★ 修正: 位置計算を borrow_mut の前に移動 ★
-            let new_pos = layout_calculator::calculate_card_position(StackType::Waste, next_waste_pos, &*world);
-
-            // カードのコンポーネントを更新
-            let mut card_moved = false;
-            if let Some(stack_info) = world.get_component_mut::<StackInfo>(top_card_entity) {
-                stack_info.stack_type = StackType::Waste;
-                stack_info.position_in_stack = next_waste_pos;
-                card_moved = true; // StackInfo 変更成功
-            }
-            if let Some(card) = world.get_component_mut::<Card>(top_card_entity) {
-                card.is_face_up = true;
-            }
-            if let Some(position) = world.get_component_mut::<Position>(top_card_entity) {
-                // ★ 修正: 事前に計算した位置を使用 ★
-                // ★ 修正: calculate_card_position の結果を一旦変数に入れる ★ // ← このコメントは古くなったので削除
-                // let new_pos = layout_calculator::calculate_card_position(StackType::Waste, next_waste_pos, &*world); // ← 移動済み
-                *position = new_pos;
-            }
+        // めくる / 戻す の判断とサーバーへの通知は、次の tick で StockSystem と NetworkOutboxSystem がやる
+        match self.world.lock() {
+            Ok(mut world) => world.send_event(StockClicked),
+            Err(poisoned) => error!("World mutex poisoned in handle_stock_click: {:?}. Aborting.", poisoned),
+        }
+    }
 
-            if card_moved {
-                log(&format!("Moved card {:?} from Stock to Waste (pos: {})", top_card_entity, next_waste_pos));
-                // ★ サーバーに通知 (必要なら) ★
-                // let mut nm = network_manager.lock().unwrap();
-                // nm.send_message(ClientMessage::DrawFromStock); // 例
-            }
+    /// 1つ前の操作を元に戻す (Ctrl+Z とかから JS が呼ぶ)。棋譜の再生中は何もしない。
+    /// 次の tick で HistorySystem が戻して、NetworkOutboxSystem がサーバーにも伝える。
+    /// サーバーが断ったら、サーバーの状態が届いて元に戻るよ。
+    #[wasm_bindgen]
+    pub fn undo(&mut self) {
+        if self.replay.is_some() {
+            return; // 再生中は盤面を触れない
+        }
+        match self.world.lock() {
+            Ok(mut world) => world.send_event(UndoRequested),
+            Err(poisoned) => error!("World mutex poisoned in undo: {:?}. Aborting.", poisoned),
+        }
+    }
 
-        } else {
-            // --- 3. Stock が空の場合: Waste から Stock に戻す ---
-            log("Stock is empty. Checking Waste...");
-
-            // Waste にあるカードの Entity を収集
-            let waste_cards: Vec<Entity> = world
-                .get_all_entities_with_component::<StackInfo>()
-                .into_iter()
-                .filter(|e| world.get_component::<StackInfo>(*e).map_or(false, |si| si.stack_type == StackType::Waste))
-                .collect();
-
-            if !waste_cards.is_empty() {
-                log(&format!("Found {} cards in Waste. Moving them back to Stock.", waste_cards.len()));
-                let mut cards_reset = 0;
-                // Waste のカードを position_in_stack の昇順でソートして Vec<(Entity, u8)> を作成
-                let mut sorted_waste_cards_with_pos: Vec<(Entity, u8)> = waste_cards
-                    .iter()
-                    .map(|e| (*e, world.get_component::<StackInfo>(*e).unwrap().position_in_stack))
-                    .collect();
-                sorted_waste_cards_with_pos.sort_by_key(|&(_, pos)| pos);
-
-                // ★ 修正: Vec を作ってイテレートする (borrow checker のため) ★
-                let entities_to_update: Vec<(Entity, u8)> = sorted_waste_cards_with_pos
-                    .iter()
-                    .enumerate()
-                    .map(|(index, (entity, _))| (*entity, index as u8))
-                    .collect();
-
-                // ★ 修正: 位置計算と更新を分離 ★
-                //    事前に新しい位置を計算して Vec に格納
-                let mut new_positions = Vec::new();
-                for (entity, new_stock_pos) in &entities_to_update {
-                     let new_pos = layout_calculator::calculate_card_position(StackType::Stock, *new_stock_pos, &*world);
-                     new_positions.push((*entity, new_pos)); // タプル (Entity, Position) を格納
-                }
+    /// 元に戻した操作をやり直す。流れは `undo` と同じ。
+    #[wasm_bindgen]
+    pub fn redo(&mut self) {
+        if self.replay.is_some() {
+            return; // 再生中は盤面を触れない
+        }
+        match self.world.lock() {
+            Ok(mut world) => world.send_event(RedoRequested),
+            Err(poisoned) => error!("World mutex poisoned in redo: {:?}. Aborting.", poisoned),
+        }
+    }
 
-                // ★ 修正: コンポーネント更新ループ ★
-                for (index, (entity, _original_waste_pos)) in sorted_waste_cards_with_pos.iter().enumerate() { // sorted_waste_cards_with_pos を再度使うか、entities_to_update を使うか注意
-                    let new_stock_pos = index as u8; // entities_to_update を使わない場合は index を使う
-                    if let Some(stack_info) = world.get_component_mut::<StackInfo>(*entity) {
-                        stack_info.stack_type = StackType::Stock;
-                        stack_info.position_in_stack = new_stock_pos;
-                    }
-                    if let Some(card) = world.get_component_mut::<Card>(*entity) {
-                        card.is_face_up = false; // Stock に戻すときは裏向き
-                    }
-                    // 事前に計算した位置を探して適用
-                    if let Some((_, new_pos)) = new_positions.iter().find(|(e, _)| *e == *entity) {
-                         if let Some(position) = world.get_component_mut::<Position>(*entity) {
-                            // ★ 修正: calculate_card_position の結果を一旦変数に入れる ★ // ← このコメントは古くなったので削除
-                            // let new_pos = layout_calculator::calculate_card_position(StackType::Stock, new_stock_pos, &*world); // ← 移動済み
-                            *position = new_pos.clone(); // ★ 修正: clone() を呼ぶ ★
-                        }
-                    }
-                    cards_reset += 1;
-                }
-                log(&format!("Reset {} cards from Waste to Stock.", cards_reset));
-                 // ★ サーバーに通知 (必要なら) ★
-                // let mut nm = network_manager.lock().unwrap();
-                // nm.send_message(ClientMessage::ResetStockFromWaste); // 例
-            } else {
-                log("Waste is also empty. Nothing to do.");
-            }
+    /// あとは組札に上げるだけの盤面 (山札・捨て札が空で、場札が全部表向き) ？
+    #[wasm_bindgen]
+    pub fn can_auto_complete(&self) -> bool {
+        let world = self.world.lock().expect("Failed to lock world");
+        auto_move::can_auto_complete(&world)
+    }
+
+    /// 残りのカードを自動で組札に上げる。次の tick から AutoCompleteSystem が1枚ずつ動かして、
+    /// 1手ずつサーバーにも送るよ。まだオートコンプリートできない盤面なら何もせず `false`。
+    #[wasm_bindgen]
+    pub fn auto_complete(&mut self) -> bool {
+        if self.replay.is_some() {
+            return false; // 再生中は盤面を触れない
+        }
+        let mut world = self.world.lock().expect("Failed to lock world");
+        if !auto_move::can_auto_complete(&world) {
+            return false;
         }
-        // World のロックはこのスコープを抜けるときに解放される
+        world.send_event(AutoCompleteRequested);
+        true
+    }
+
+    /// 次の一手のヒントを JSON で返す (指せる手が無ければ `None`)。
+    /// 出したヒントは `ActiveHint` として World に置いておくので、次の描画で移動元と移動先が光るよ💡
+    /// カードが動いたら古いヒントとして消える。
+    #[wasm_bindgen]
+    pub fn get_hint(&self) -> Option<String> {
+        let mut world = self.world.lock().expect("Failed to lock world");
+        let Some(hint) = hints::best_hint(&world) else {
+            world.remove_resource::<ActiveHint>();
+            return None;
+        };
+        let since = world.increment_change_tick();
+        world.insert_resource(ActiveHint { hint, since });
+        serde_json::to_string(&hint).ok()
     }
 
     /// JSから呼び出され、ドラッグ中のカード位置を更新する。
@@ -649,7 +896,7 @@ impl GameApp {
         match event_handler::find_topmost_clicked_card(&world, x, y, None) {
             Some(ClickTarget::Card(entity)) => {
                 // log(&format!("get_entity_id_at: 座標 ({}, {}) でカードエンティティ {:?} を発見。", x, y, entity));
-                Some(entity.0)
+                Some(entity.index() as usize) // JS には番号だけ渡す (戻ってきたら entity_from_index で戻す)
             }
             _ => {
                 // log(&format!("get_entity_id_at: 座標 ({}, {}) にカードは見つかりませんでした。", x, y));
@@ -660,6 +907,9 @@ impl GameApp {
 
     /// ドラッグ開始時に JS から呼ばれる
     pub fn handle_drag_start(&mut self, entity_usize: usize, start_x: f32, start_y: f32) {
+        if self.replay.is_some() {
+            return; // 再生中は盤面を触れない
+        }
         // log(&format!("GameApp::handle_drag_start: Entity {}, Start: ({}, {})", entity_usize, start_x, start_y));
 
         // 1. drag_handler を呼び出して DraggingInfo を追加
@@ -669,7 +919,6 @@ impl GameApp {
         // --- 復活！ ---
         if let Err(e) = browser_event_manager::attach_drag_listeners(
             Arc::clone(&self.world),
-            Arc::clone(&self.network_manager), // network_manager も渡す
             // ★ 修正: attach_drag_listeners の引数に合わせる ★
             // Entity(entity_usize), // entity_usize を Entity に変換して渡す
             Arc::clone(&self.window_mousemove_closure),
@@ -691,7 +940,6 @@ impl GameApp {
         // log(&format!("GameApp::handle_drag_end: JS called for entity: {}, end: ({}, {})", entity_usize, end_x, end_y));
         drag_handler::handle_drag_end(
             &self.world,
-            &self.network_manager,
             entity_usize,
             end_x,
             end_y
@@ -747,4 +995,9 @@ impl Drop for GameApp {
 
         println!("Listeners detached.");
     }
-} 
\ No newline at end of file
+} 
+
+/// セーブした時点の目印 (シード, 棋譜の手数)。配る前なら `None` (セーブしない)。
+fn save_point(world: &World) -> Option<(u64, usize)> {
+    world.resource::<GameRecord>().map(|record| (record.seed, record.moves.len()))
+}
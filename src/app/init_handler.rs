@@ -7,8 +7,8 @@ use std::collections::VecDeque;
 use crate::ecs::world::World;
 use crate::network::{NetworkManager, ConnectionStatus};
 use crate::systems::deal_system::DealInitialCardsSystem;
-use crate::protocol::{GameStateData, ClientMessage, CardData, PositionData, ServerMessage};
-use crate::components::{self, Card, StackInfo, Position, /*StackType*/}; // StackType は直接使われていない
+use crate::protocol::{GameStateData, ClientMessage, ServerMessage};
+use crate::components;
 use crate::log; // ★修正: log マクロのみ★
 use log::error; // ★追加: error! マクロ★
 use crate::app::network_sender; // ★修正: network_handler -> network_sender ★
@@ -22,18 +22,27 @@ use web_sys::{window, HtmlCanvasElement, CanvasRenderingContext2d};
 pub(crate) fn initialize_world() -> Arc<Mutex<World>> {
     log("App::Init: Initializing world...");
     let mut world = World::new();
-    // コンポーネント登録
-    world.register_component::<components::card::Card>();
-    world.register_component::<components::position::Position>();
-    world.register_component::<components::stack::StackInfo>();
-    world.register_component::<components::game_state::GameState>();
+    // コンポーネント登録 (カードの中身と場所はサーバー同期やセーブに使うスナップショットにも入れる📸)
+    world.register_serializable_component::<components::card::Card>();
+    world.register_serializable_component::<components::position::Position>();
+    world.register_serializable_component::<components::stack::StackInfo>();
     world.register_component::<components::player::Player>();
     // ★ DraggingInfo も登録 ★
     world.register_component::<components::dragging_info::DraggingInfo>();
+    // ゲーム状態はリソースとして置いておく (カードを配った時にも Playing にリセットされる)
+    world.insert_resource(components::game_state::GameState::playing());
+    // システム同士のイベントと、サーバーへの送信キュー
+    crate::events::register_game_events(&mut world);
+    world.insert_resource(crate::systems::network_outbox_system::Outbox::default());
+    // サーバーのエンティティ ⇔ こっちのエンティティ の対応表
+    world.insert_resource(crate::ecs::entity_map::EntityMap::default());
+    // undo / redo 用の操作履歴
+    world.insert_resource(crate::logic::history::History::default());
     Arc::new(Mutex::new(world))
 }
 
 /// NetworkManager の初期化を行う。
+#[allow(clippy::arc_with_non_send_sync)] // WebSocket (JsValue) は Send じゃないけど、wasm はシングルスレッドなのでOK
 pub(crate) fn initialize_network(message_queue_arc: Arc<Mutex<VecDeque<ServerMessage>>>) -> Arc<Mutex<NetworkManager>> {
     log("App::Init: Initializing network...");
     let server_url = format!("ws://{}:{}", "localhost", 8101);
@@ -72,36 +81,11 @@ pub(crate) fn initialize_canvas() -> Result<(HtmlCanvasElement, CanvasRenderingC
 /// 現在の World の状態から GameStateData を作成する。
 pub(crate) fn get_initial_state_data(world: &World) -> GameStateData {
     log("App::Init: Generating initial game state data...");
-    let players = Vec::new(); // TODO: プレイヤー情報も取得・含めるべき？
-
-    let card_entities = world.get_all_entities_with_component::<Card>();
-    let mut cards = Vec::with_capacity(card_entities.len());
-
-    for &entity in &card_entities {
-        let card = world.get_component::<Card>(entity)
-            .expect(&format!("Card component not found for entity {:?}", entity));
-        let stack_info = world.get_component::<StackInfo>(entity)
-            .expect(&format!("StackInfo component not found for entity {:?}", entity));
-        let position = world.get_component::<Position>(entity)
-            .expect(&format!("Position component not found for entity {:?}", entity));
-
-        cards.push(CardData {
-            entity,
-            suit: card.suit.into(),
-            rank: card.rank.into(),
-            is_face_up: card.is_face_up,
-            stack_type: stack_info.stack_type.into(), // From トレイト実装前提
-            position_in_stack: stack_info.position_in_stack,
-            position: PositionData {
-                x: position.x,
-                y: position.y,
-            },
-        });
-    }
-
+    // TODO: プレイヤー情報も含めるべき？
     GameStateData {
-        players,
-        cards,
+        // ルールはサーバーが決めるので送らない
+        rules: None,
+        ..GameStateData::from_world(world)
     }
 }
 
@@ -129,7 +113,8 @@ pub(crate) fn send_initial_state(
 pub fn deal_initial_cards(
     world_arc: &Arc<Mutex<World>>,
     network_manager_arc: &Arc<Mutex<NetworkManager>>,
-    deal_system: &DealInitialCardsSystem // DealSystem への参照を受け取る
+    deal_system: &DealInitialCardsSystem, // DealSystem への参照を受け取る
+    seed: u64, // シャッフルのシード (同じシードなら同じ盤面)
 ) {
     log("App::Init: deal_initial_cards() called.");
 
@@ -144,7 +129,7 @@ pub fn deal_initial_cards(
             }
         };
         log("  Executing DealInitialCardsSystem...");
-        deal_system.execute(&mut mutable_world_guard);
+        deal_system.execute(&mut mutable_world_guard, seed);
         log("  DealInitialCardsSystem executed successfully.");
         log("  Released mutable lock.");
     } // <-- 書き込みロック解放
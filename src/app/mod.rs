@@ -9,12 +9,10 @@ pub mod renderer;    // 描画処理
 pub mod state_handler;   // ★追加
 // pub mod init_handler;    // 今後追加予定 
 pub mod drag_handler;
-pub mod layout_calculator;
 pub mod state_getter;
 pub mod browser_event_manager; // ★追加済★
-pub mod drag_apply_handler; // ★追加 
 // ★追加: 新しいネットワークモジュール★
 pub mod network_connector;
 pub mod network_sender;
 pub mod network_receiver;
-pub mod stock_handler; // ★ 追加 ★ 
\ No newline at end of file
+// layout_calculator / stock_handler は web 無しでも使えるように logic/ に移動したよ
@@ -3,10 +3,12 @@
 
 use std::sync::{Arc, Mutex};
 use std::collections::VecDeque;
-use crate::protocol::{ServerMessage, PlayerId};
+use crate::protocol::ServerMessage;
+use crate::components::player::LocalPlayer;
 use crate::ecs::entity::Entity;
+use crate::ecs::entity_map::EntityMap;
 use crate::ecs::world::World;
-use crate::app::state_handler; 
+use crate::app::state_handler::{self, DeltaOutcome};
 use crate::log;
 use log::error;
 
@@ -19,12 +21,13 @@ pub enum ProcessedMessageResult {
         entity_id: Entity,
         reason: String,
     },
+    /// サーバーからの差分を取りこぼした。`RequestGameState` を送って、まるごと送り直してもらう。
+    ResyncNeeded,
 }
 
 /// 受信メッセージキューを処理して、発生した重要イベントのリストを返すよ！
 pub fn process_received_messages(
     message_queue_arc: &Arc<Mutex<VecDeque<ServerMessage>>>,
-    my_player_id_arc: &Arc<Mutex<Option<PlayerId>>>,
     world_arc: &Arc<Mutex<World>>
 ) -> Vec<ProcessedMessageResult> { 
     let mut results: Vec<ProcessedMessageResult> = Vec::new();
@@ -43,22 +46,39 @@ pub fn process_received_messages(
     for message in messages_to_process {
         log(&format!("  Processing: {:?}", message));
         match message {
-            ServerMessage::GameJoined { your_player_id, initial_game_state } => {
-                *my_player_id_arc.lock().expect("Failed to lock my_player_id") = Some(your_player_id);
+            ServerMessage::GameJoined { your_player_id, initial_game_state, sequence } => {
+                // 自分のプレイヤーID は World の LocalPlayer リソースに置く
+                world_arc.lock().expect("Failed to lock world").insert_resource(LocalPlayer { id: your_player_id });
                 log(&format!("App::NetworkReceiver: Game joined! My Player ID: {}", your_player_id));
-                if state_handler::apply_game_state(world_arc, initial_game_state) {
+                if state_handler::apply_game_state(world_arc, initial_game_state, Some(sequence)) {
                     results.push(ProcessedMessageResult::StateChanged);
                 }
             }
-            ServerMessage::GameStateUpdate { current_game_state } => {
-                log("App::NetworkReceiver: Received GameStateUpdate.");
-                if state_handler::apply_game_state(world_arc, current_game_state) {
+            ServerMessage::GameStateUpdate { current_game_state, sequence } => {
+                log(&format!("App::NetworkReceiver: Received GameStateUpdate #{}.", sequence));
+                if state_handler::apply_game_state(world_arc, current_game_state, Some(sequence)) {
                     results.push(ProcessedMessageResult::StateChanged);
                 }
             }
+            ServerMessage::GameStateDelta { delta } => {
+                match state_handler::apply_game_delta(world_arc, delta) {
+                    DeltaOutcome::Applied(true) => results.push(ProcessedMessageResult::StateChanged),
+                    DeltaOutcome::Applied(false) | DeltaOutcome::Ignored => {}
+                    DeltaOutcome::ResyncNeeded => results.push(ProcessedMessageResult::ResyncNeeded),
+                }
+            }
             ServerMessage::MoveRejected { entity_id, reason } => {
                 log(&format!("App::NetworkReceiver: Move rejected by server for entity {:?}: {}", entity_id, reason));
-                results.push(ProcessedMessageResult::MoveRejected { entity_id, reason });
+                // サーバーのエンティティをこっちのエンティティに戻してから伝える
+                let local = world_arc
+                    .lock()
+                    .expect("Failed to lock world")
+                    .resource::<EntityMap>()
+                    .and_then(|map| map.local(entity_id));
+                match local {
+                    Some(entity_id) => results.push(ProcessedMessageResult::MoveRejected { entity_id, reason }),
+                    None => error!("App::NetworkReceiver: Rejected entity {:?} is unknown locally.", entity_id),
+                }
             }
             ServerMessage::PlayerJoined { player_id, player_name } => {
                 log(&format!("App::NetworkReceiver: Player {} ({}) joined.", player_name, player_id));
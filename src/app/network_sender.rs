@@ -41,20 +41,19 @@ pub fn send_join_game(
     }
 }
 
-/// カード移動メッセージを送信する。
+/// カード移動メッセージを送信する。`moved_entity` はサーバー側のエンティティだよ。
 pub fn send_make_move(
     network_manager_arc: &Arc<Mutex<NetworkManager>>,
-    moved_entity_id: usize,
+    moved_entity: Entity,
     target_stack_json: String // Keep accepting JSON for now
 ) {
-    log(&format!("App::NetworkSender: send_make_move called with entity: {}, target: {}", moved_entity_id, target_stack_json));
-    let moved_entity = Entity(moved_entity_id);
+    log(&format!("App::NetworkSender: send_make_move called with entity: {:?}, target: {}", moved_entity, target_stack_json));
 
     // Deserialize the JSON string into the component's StackType first
     match serde_json::from_str::<ComponentStackType>(&target_stack_json) {
         Ok(target_stack_component) => {
             // Convert component's StackType to protocol's StackType
-            let target_stack_proto: ProtocolStackType = target_stack_component.into();
+            let target_stack_proto: ProtocolStackType = target_stack_component;
             let message = ClientMessage::MakeMove { moved_entity, target_stack: target_stack_proto };
             if let Err(e) = send_serialized_message(network_manager_arc, message) {
                 error!("App::NetworkSender: Failed to send MakeMove message: {}", e);
@@ -64,4 +63,12 @@ pub fn send_make_move(
             error!("App::NetworkSender: Failed to deserialize target_stack JSON: {}. Input: {}", e, target_stack_json);
         }
     }
-} 
\ No newline at end of file
+} 
+
+/// 今の状態をまるごと送り直してもらう (差分を取りこぼした時)。
+pub fn send_request_game_state(network_manager_arc: &Arc<Mutex<NetworkManager>>) {
+    log("App::NetworkSender: Requesting a full game state.");
+    if let Err(e) = send_serialized_message(network_manager_arc, ClientMessage::RequestGameState) {
+        error!("App::NetworkSender: Failed to send RequestGameState message: {}", e);
+    }
+}
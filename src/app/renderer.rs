@@ -3,8 +3,11 @@
 
 use std::sync::{Arc, Mutex};
 use crate::ecs::world::World;
-use crate::components::{Position, Card, DraggingInfo, StackInfo, Suit, Rank, StackType};
+use crate::components::{Position, Card, DraggingInfo, Score, StackInfo, Suit, Rank, StackType};
 use crate::ecs::entity::Entity;
+use crate::logic::hints::{ActiveHint, Hint, HintMove};
+use crate::logic::layout_calculator;
+use crate::logic::variants;
 use log::warn;
 use wasm_bindgen::JsValue;
 use web_sys::{HtmlCanvasElement, CanvasRenderingContext2d};
@@ -35,6 +38,8 @@ const COLOR_TEXT_RED: &str = "#ff7b7b"; // ★ Dark: 明るい赤色の文字 
 const COLOR_TEXT_BLACK: &str = "#f8f9fa"; // ★ Dark: 明るいグレー/オフホワイトの文字 ★
 // const COLOR_PLACEHOLDER_BORDER: &str = "#adb5bd"; // Previous Gray
 const COLOR_PLACEHOLDER_BORDER: &str = "#6c757d"; // ★ Dark: 中間のグレーの枠線 ★
+const COLOR_HINT: &str = "#ffd43b"; // ヒントの移動元・移動先を囲む色 (黄色)
+const HINT_LINE_WIDTH: f64 = 3.0;
 
 // カードの文字 (ランクとスート)
 const FONT_FAMILY: &str = "sans-serif";
@@ -42,6 +47,11 @@ const FONT_SIZE_RANK: f64 = 18.0; // ランク (A, 2-10, J, Q, K) のフォン
 const RANK_OFFSET_X: f64 = 5.0; // カード左上からのランク文字のXオフセット
 const RANK_OFFSET_Y: f64 = 20.0; // カード左上からのランク文字のYオフセット
 
+// 点数 (右上)
+const FONT_SIZE_SCORE: f64 = 18.0;
+const SCORE_MARGIN: f64 = 20.0; // Canvas の右端からの余白
+const SCORE_BASELINE_Y: f64 = 30.0; // 山札や組札より上に出す
+
 // --- 公開関数 (GameApp から呼び出される) ---
 
 /// Rust側で Canvas にゲーム画面を描画する関数。
@@ -77,19 +87,36 @@ pub fn render_game_rust(
     // context.set_line_width(1.0);
     // context.stroke();
     // context.set_line_dash(&JsValue::from(js_sys::Array::new())).unwrap();
+    // 今の遊び方で使うスタックと、フリーセル・組札の置き場所 (ロックはすぐ離す)
+    let (kinds, slot_positions) = {
+        let world = world_arc.lock().map_err(|e| JsValue::from_str(&format!("Failed to lock world mutex: {}", e)))?;
+        let kinds = variants::active(&world).kinds();
+        let slots: Vec<(f64, f64)> = kinds
+            .free_cells()
+            .chain(kinds.foundations())
+            .map(|stack| {
+                let position = layout_calculator::calculate_card_position(stack, 0, &world);
+                (position.x as f64, position.y as f64)
+            })
+            .collect();
+        (kinds, slots)
+    };
+
     // ★★★ 修正: Stock も draw_rounded_rect を使う ★★★
-    draw_rounded_rect(
-        context, 
-        STOCK_POS_X as f64, 
-        STOCK_POS_Y as f64, 
-        RENDER_CARD_WIDTH, 
-        RENDER_CARD_HEIGHT, 
-        RENDER_CARD_CORNER_RADIUS
-    )?;
-    context.set_stroke_style_str(COLOR_PLACEHOLDER_BORDER);
-    context.set_line_width(1.0); // 線幅は描画前に設定
-    context.stroke();
-    context.set_line_dash(&JsValue::from(js_sys::Array::new())).unwrap(); // 点線解除 (必要なら)
+    if kinds.stock {
+        draw_rounded_rect(
+            context, 
+            STOCK_POS_X as f64, 
+            STOCK_POS_Y as f64, 
+            RENDER_CARD_WIDTH, 
+            RENDER_CARD_HEIGHT, 
+            RENDER_CARD_CORNER_RADIUS
+        )?;
+        context.set_stroke_style_str(COLOR_PLACEHOLDER_BORDER);
+        context.set_line_width(1.0); // 線幅は描画前に設定
+        context.stroke();
+        context.set_line_dash(&JsValue::from(js_sys::Array::new())).unwrap(); // 点線解除 (必要なら)
+    }
     // ★削除★ ログ不要
     // log(&format!("    Drew Stock placeholder at ({}, {})"), STOCK_POS_X, STOCK_POS_Y);
 
@@ -99,12 +126,16 @@ pub fn render_game_rust(
     // ★★★ デバッグ: draw_rounded_rect + stroke の代わりに stroke_rect を試す！ ★★★
     // ★削除★ ログ不要
     // log("      Calling context.stroke_rect() for Waste...");
-    context.stroke_rect(
-        WASTE_POS_X as f64,
-        WASTE_POS_Y as f64,
-        RENDER_CARD_WIDTH,
-        RENDER_CARD_HEIGHT
-    );
+    if kinds.waste {
+        context.set_stroke_style_str(COLOR_PLACEHOLDER_BORDER);
+        context.set_line_width(1.0);
+        context.stroke_rect(
+            WASTE_POS_X as f64,
+            WASTE_POS_Y as f64,
+            RENDER_CARD_WIDTH,
+            RENDER_CARD_HEIGHT
+        );
+    }
     // ★削除★ ログ不要
     // log("      context.stroke_rect() for Waste called (assuming success).");
     /* --- 元のコード (コメントアウト) ---
@@ -129,22 +160,19 @@ pub fn render_game_rust(
     // ★削除★ ログ不要
     // log(&format!("    Finished drawing Waste placeholder at ({}, {})", WASTE_POS_X, WASTE_POS_Y));
 
-    // 2.5.3: 上がり札 (Foundation) のプレースホルダーを描画 (4つあるからループ！)
-    // ★削除★ 念のためログ不要
-    // log("    Attempting to draw Foundation placeholders...");
-    for i in 0..4 { 
-        let foundation_x = FOUNDATION_START_X as f64 + i as f64 * FOUNDATION_X_OFFSET as f64;
-        let foundation_y = FOUNDATION_START_Y as f64;
+    // 2.5.3: フリーセルと上がり札 (Foundation) のプレースホルダーを描画 (数は遊び方しだいなのでループ！)
+    context.set_stroke_style_str(COLOR_PLACEHOLDER_BORDER);
+    context.set_line_width(1.0);
+    for (slot_x, slot_y) in slot_positions {
         draw_rounded_rect(
             context,
-            foundation_x,
-            foundation_y,
+            slot_x,
+            slot_y,
             RENDER_CARD_WIDTH,
             RENDER_CARD_HEIGHT,
             RENDER_CARD_CORNER_RADIUS,
         )?;
         context.stroke();
-        // log(&format!("    Drew Foundation {} placeholder at ({}, {})", i, foundation_x, foundation_y)); // 毎回のログはうるさいのでコメントアウト
     }
     // ★削除★ 念のためログ不要
     // log("    Finished drawing all 4 Foundation placeholders.");
@@ -254,6 +282,23 @@ pub fn render_game_rust(
         }
     }
 
+    // --- 4.5 ヒントが出てたら、移動元と移動先を光らせる 💡 ---
+    if let Some(active) = world.resource::<ActiveHint>() {
+        if !active.is_stale(&world) {
+            draw_hint(context, &world, &active.hint)?;
+        }
+    }
+
+    // --- 4.6 点数を右上に出す 💯 (点数を数えてる時だけ) ---
+    if let Some(score) = world.resource::<Score>() {
+        context.save();
+        context.set_fill_style_str(COLOR_TEXT_BLACK);
+        context.set_font(&format!("bold {}px {}", FONT_SIZE_SCORE, FONT_FAMILY));
+        context.set_text_align("right");
+        context.fill_text(&score.label(), canvas_width - SCORE_MARGIN, SCORE_BASELINE_Y)?;
+        context.restore();
+    }
+
     // --- 5. Draw the dragged card LAST (if any) ---
     // ★ 削除: ドラッグ中のカードを最後に描画する処理を削除 ★
     // if let Some((pos, card)) = dragged_card_data {
@@ -273,6 +318,28 @@ pub fn render_game_rust(
 
 // --- ヘルパー関数 (Helper Functions) ---
 
+/// ヒントの移動元 (動かすカード、山札・捨て札ならその場所) と、移動先 (カードが置かれる場所) を枠で囲む。
+fn draw_hint(context: &CanvasRenderingContext2d, world: &World, hint: &Hint) -> Result<(), JsValue> {
+    let source = match hint.action {
+        HintMove::Move { entity, .. } => world.get_component::<Position>(entity).cloned(),
+        HintMove::DrawFromStock | HintMove::RecycleWaste => {
+            Some(layout_calculator::calculate_card_position(hint.source(), 0, world))
+        }
+    };
+    let target_stack = hint.target();
+    let target = layout_calculator::calculate_card_position(target_stack, world.stacks().len(target_stack) as u8, world);
+
+    context.save();
+    context.set_stroke_style_str(COLOR_HINT);
+    context.set_line_width(HINT_LINE_WIDTH);
+    for pos in source.iter().chain(std::iter::once(&target)) {
+        draw_rounded_rect(context, pos.x as f64, pos.y as f64, RENDER_CARD_WIDTH, RENDER_CARD_HEIGHT, RENDER_CARD_CORNER_RADIUS)?;
+        context.stroke();
+    }
+    context.restore();
+    Ok(())
+}
+
 /// 角丸の四角形のパスを作成するヘルパー関数。
 /// これ自体は描画せず、パスを作るだけだよ。
 /// 呼び出し側で `context.fill()` や `context.stroke()` をする必要がある。
@@ -332,6 +399,7 @@ fn stack_type_draw_order(stack_type: StackType) -> u8 {
     match stack_type {
         StackType::Stock => 0,
         StackType::Waste => 1,
+        StackType::FreeCell(_) => 2,   // フリーセルは組札と同じ段
         StackType::Foundation(_) => 2, // Foundation は Tableau より先に描画
         StackType::Tableau(_) => 3,    // Tableau は Foundation の後
         StackType::Hand => 4,         // Hand は最後 (もし使うなら)
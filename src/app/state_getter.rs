@@ -1,13 +1,13 @@
 //! Gets the current game state from the World and converts it to JSON.
 
 use std::sync::{Arc, Mutex};
-use log::{info, error, warn};
+use log::{info, error};
 use serde_json;
 use wasm_bindgen::JsValue;
 
+use crate::components::Card;
 use crate::ecs::world::World;
-use crate::components::{Position, Card, StackInfo};
-use crate::protocol::{CardData, GameStateData, PositionData};
+use crate::protocol::GameStateData;
 
 /// ワールドの状態を取得し、JSON 文字列として返します。
 /// (GameApp::get_world_state_json のロジック)
@@ -23,33 +23,8 @@ pub fn get_world_state_json(world_arc: &Arc<Mutex<World>>) -> Result<JsValue, Js
     };
 
     info!("Getting world state..."); // info! マクロを使用
-    let mut cards_data = Vec::new();
-    let entities_with_card = world.get_all_entities_with_component::<Card>();
-    info!("Found {} entities with Card component.", entities_with_card.len()); // info! マクロを使用
-
-    for entity in entities_with_card {
-        let pos_opt = world.get_component::<Position>(entity);
-        let card_opt = world.get_component::<Card>(entity);
-        let stack_info_opt = world.get_component::<StackInfo>(entity);
-
-        if let (Some(pos), Some(card), Some(stack_info)) = (pos_opt, card_opt, stack_info_opt) {
-            let card_data = CardData {
-                entity,
-                suit: card.suit.into(),
-                rank: card.rank.into(),
-                is_face_up: card.is_face_up,
-                stack_type: stack_info.stack_type.into(),
-                position_in_stack: stack_info.position_in_stack,
-                position: PositionData { x: pos.x, y: pos.y },
-            };
-            cards_data.push(card_data);
-        } else {
-            warn!("Entity {:?} is missing Position, Card, or StackInfo component. Skipping.", entity);
-        }
-    }
-    info!("Collected data for {} cards.", cards_data.len()); // info! マクロを使用
-
-    let game_state_data = GameStateData { players: Vec::new(), cards: cards_data };
+    let game_state_data = GameStateData::from_world(&world);
+    info!("Collected data for {} cards.", game_state_data.world.count::<Card>()); // info! マクロを使用
 
     match serde_json::to_string(&game_state_data) {
         Ok(json_string) => {
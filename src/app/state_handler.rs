@@ -1,109 +1,234 @@
 // src/app/state_handler.rs
 //! GameApp の状態更新（主にサーバーからの情報反映）に関するロジック。
 
-use std::sync::{Arc, Mutex}; // Arc と Mutex を使う
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard}; // Arc と Mutex を使う
 // use std::collections::VecDeque; // 現状未使用
 use crate::ecs::world::World;
 use crate::ecs::entity::Entity;
+use crate::ecs::entity_map::EntityMap;
+use crate::ecs::resource::Resource;
+use crate::ecs::snapshot::{SnapshotDelta, WorldSnapshot};
+use crate::logic::history::History;
+use crate::persistence::SavedGame;
+use crate::components::game_state::{GameClock, GameState};
 // use crate::network::{NetworkManager, ConnectionStatus}; // 現状未使用
-use crate::protocol::{/*self,*/ GameStateData}; // protocol モジュールと GameStateData をインポート (selfは不要)
+use crate::protocol::{GameStateData, GameStateDelta, PlayerData};
 use crate::components::{
     card::{Card, /*Rank, Suit*/}, // Rank, Suit は未使用
-    position::Position,
-    stack::{StackInfo, /*StackType*/}, // StackTypeは apply_card_data 内で直接は使わない
+    stack::{StackInfo, StackType},
     player::Player, // Player コンポーネントも使う
     dragging_info::DraggingInfo, // DraggingInfo もクリア対象
 };
-// use crate::protocol::{ServerMessage, ClientMessage, PlayerId, PlayerData, CardData, PositionData}; // GameStateData 以外は未使用
 use crate::{log, /*error*/}; // error は未使用
 
+/// サーバーから最後に受け取った状態の通し番号 (リソース)。差分 (`GameStateDelta`) を重ねていいか決めるのに使う。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerSequence {
+    /// 最後に反映した状態の番号。まだまるごとの状態を受け取ってなければ `None`。
+    pub last: Option<u64>,
+    /// 差分を取りこぼしたので、まるごと送り直してもらうのを待ってる。
+    pub resync_requested: bool,
+}
+
+impl Resource for ServerSequence {}
+
+/// `apply_game_delta` の結果。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaOutcome {
+    /// 重ねた。中身は状態が変わったかどうか。
+    Applied(bool),
+    /// もう反映済みの古い差分 (か、送り直しを待ってる間の差分) なので捨てた。
+    Ignored,
+    /// 通し番号が飛んでた。`RequestGameState` でまるごと送り直してもらってね。
+    ResyncNeeded,
+}
+
+/// カードをどう反映するか。
+enum CardUpdate {
+    /// スナップショットをまるごと (`World::restore_mapped`)。
+    Whole(WorldSnapshot),
+    /// 差分だけ (`World::apply_delta_mapped`)。
+    Delta(SnapshotDelta),
+}
+
 /// サーバーから受け取った GameStateData を World に反映させる内部関数。
 /// (lib.rs の GameApp::apply_game_state から移動)
 /// 状態が更新された場合は true を返すように変更！
+///
+/// カードはサーバーのスナップショットを `World::restore_mapped` で戻す。サーバーのエンティティIDはそのまま使わずに、
+/// `EntityMap` でこっちのエンティティに対応付けるよ。初めて見るカードはこっちで新しく作って、サーバーの状態に無いカードは消す。
+///
+/// カードの中身や場所がこっちの World と食い違ってたら (他の人が動かした、こっちの操作が断られた、など)、
+/// こっちの undo 履歴はもう World と合わないので捨てるよ。
+/// `sequence` はサーバーが付けた通し番号 (セーブデータから戻す時みたいにサーバーと関係ない時は `None`)。
+/// カードを反映できた時だけ、その番号まで受け取ったことにするよ。
 pub fn apply_game_state(
     world_arc: &Arc<Mutex<World>>, // World への参照を受け取る
-    game_state: GameStateData
+    mut game_state: GameStateData,
+    sequence: Option<u64>,
 ) -> bool {
     log("App::State: Applying game state update...");
-    let mut world = match world_arc.lock() { // poison 対応
-        Ok(guard) => guard,
-        Err(poisoned) => {
-            log(&format!("World mutex poisoned in apply_game_state: {:?}. Recovering...", poisoned));
-            poisoned.into_inner()
+    let mut world = lock_world(world_arc);
+    let players = std::mem::take(&mut game_state.players);
+    let cards = CardUpdate::Whole(std::mem::take(&mut game_state.world));
+    match apply_server_state(&mut world, &game_state, Some(players), cards) {
+        Ok(changed) => {
+            if let Some(sequence) = sequence {
+                world.insert_resource(ServerSequence { last: Some(sequence), resync_requested: false });
+            }
+            changed
         }
-    };
+        Err(e) => {
+            log(&format!("App::State: Failed to apply the server's cards: {}", e));
+            // 何も受け取ってないことにする (次の差分で、もう一回まるごと送ってもらう)
+            if sequence.is_some() {
+                world.insert_resource(ServerSequence::default());
+            }
+            true // カード以外 (プレイヤーとか) は変わってるかもしれないので描き直してもらう
+        }
+    }
+}
 
-    // ★状態変更があったかどうかのフラグ (クリア処理や追加処理があれば true)
+/// サーバーから受け取った差分を World に重ねる。変わったカードだけ付け直すよ。
+/// 前に反映した状態の次の番号じゃなければ重ねない (取りこぼしてたら `ResyncNeeded`)。
+/// 重ねるのに失敗した時も、もう World がサーバーとずれてるので `ResyncNeeded`。
+pub fn apply_game_delta(world_arc: &Arc<Mutex<World>>, delta: GameStateDelta) -> DeltaOutcome {
+    let mut world = lock_world(world_arc);
+    let sync = world.resource::<ServerSequence>().copied().unwrap_or_default();
+    match sync.last {
+        Some(last) if delta.follows(last) => {}
+        Some(last) if delta.sequence <= last => return DeltaOutcome::Ignored,
+        _ if sync.resync_requested => return DeltaOutcome::Ignored,
+        _ => {
+            log(&format!("App::State: Missed a state update (have {:?}, got {}); asking for a resync.", sync.last, delta.sequence));
+            world.insert_resource(ServerSequence { resync_requested: true, ..sync });
+            return DeltaOutcome::ResyncNeeded;
+        }
+    }
+    log(&format!("App::State: Applying state delta {} ({} changed, {} removed)...", delta.sequence, delta.cards.changed.len(), delta.cards.removed.len()));
+    let sequence = delta.sequence;
+    let session = delta.session_state();
+    let GameStateDelta { players, cards, .. } = delta;
+    match apply_server_state(&mut world, &session, players, CardUpdate::Delta(cards)) {
+        Ok(changed) => {
+            world.insert_resource(ServerSequence { last: Some(sequence), resync_requested: false });
+            DeltaOutcome::Applied(changed)
+        }
+        Err(e) => {
+            log(&format!("App::State: Failed to apply state delta {}: {}; asking for a resync.", sequence, e));
+            world.insert_resource(ServerSequence { resync_requested: true, ..sync });
+            DeltaOutcome::ResyncNeeded
+        }
+    }
+}
+
+/// `apply_game_state` と `apply_game_delta` の中身。
+/// `session` からはカードとプレイヤー以外 (シード、ルール、点数など) だけ使う。`players` が `None` ならプレイヤーはそのまま。
+/// 状態が変わったかどうかを返す。カードを反映できなかったら `Err` (それより前に反映した分はそのまま)。
+fn apply_server_state(world: &mut World, session: &GameStateData, players: Option<Vec<PlayerData>>, cards: CardUpdate) -> Result<bool, String> {
+    // ★状態変更があったかどうかのフラグ (削除や追加があれば true)
     let mut state_changed = false;
 
-    // --- 1. 既存のプレイヤーとカード情報をクリア ---
-    log("  Clearing existing player and card entities...");
-    let existing_player_entities: Vec<Entity> =
-        world.get_all_entities_with_component::<Player>()
-            .into_iter()
-            .collect();
-    if !existing_player_entities.is_empty() { state_changed = true; }
-    for entity in existing_player_entities {
-        world.remove_component::<Player>(entity);
+    // --- 0. シードと捨て札を戻した回数、ルール、遊び方、点数はサーバーのものに合わせる (勝ち負けの状態はこっちのまま) ---
+    if let Some(state) = world.resource_mut::<GameState>() {
+        state.seed = session.seed;
+        state.stock_recycles = session.stock_recycles;
+    }
+    if let Some(rules) = session.rules {
+        world.insert_resource(rules);
+    }
+    if let Some(variant) = session.variant {
+        world.insert_resource(variant);
     }
-    let existing_card_entities: Vec<Entity> = world
-        .get_all_entities_with_component::<Card>()
-        .into_iter()
-        .collect();
-    if !existing_card_entities.is_empty() { state_changed = true; }
-    for entity in existing_card_entities {
-        world.remove_component::<Card>(entity);
-        world.remove_component::<Position>(entity);
-        world.remove_component::<StackInfo>(entity);
-        world.remove_component::<DraggingInfo>(entity); // ドラッグ情報もクリア
-        // TODO: エンティティ自体を destroy するべきか？
-        //       現状はコンポーネントを削除するだけ。
-        //       サーバーからの GameStateData が常に全カード情報を含むならこれで良い。
-        //       差分更新の場合は destroy が必要になる。
+    if let Some(score) = session.score {
+        world.insert_resource(score);
+    }
+    // 手数はサーバーのもの、経過時間はこっちの時計のまま (最初の1手がサーバー経由で来たら時計を動かす)
+    if let Some(server_clock) = session.clock {
+        let mut clock = world.resource::<GameClock>().copied().unwrap_or_default();
+        clock.moves = server_clock.moves;
+        clock.started |= server_clock.started;
+        world.insert_resource(clock);
     }
 
-    // --- 2. 新しいプレイヤー情報を反映 --- 
-    if !game_state.players.is_empty() { state_changed = true; }
-    log(&format!("  Applying {} players...", game_state.players.len()));
-    for player_data in game_state.players {
-        log(&format!("    Player ID: {}, Name: {}", player_data.id, player_data.name));
-        // プレイヤーエンティティを ID で作成または取得
-        let player_entity = Entity(player_data.id as usize); // PlayerId(u32) を usize にキャスト
-        world.create_entity_with_id(player_entity); // 存在しなければ作成
-        // Player コンポーネントを追加/更新
-        world.add_component(player_entity, Player { name: player_data.name, is_current_turn: false });
+    // --- 1. プレイヤーは来たら作り直す (プレイヤーIDは Player コンポーネントに入ってる) ---
+    if let Some(players) = players {
+        log("  Replacing player entities...");
+        for entity in world.get_all_entities_with_component::<Player>() {
+            world.destroy_entity(entity);
+            state_changed = true;
+        }
+        if !players.is_empty() { state_changed = true; }
+        log(&format!("  Applying {} players...", players.len()));
+        for player_data in players {
+            log(&format!("    Player ID: {}, Name: {}", player_data.id, player_data.name));
+            let player_entity = world.create_entity();
+            world.add_component(player_entity, Player { id: player_data.id, name: player_data.name, is_current_turn: false });
+        }
     }
 
-    // --- 3. 新しいカード情報を反映 --- 
-    if !game_state.cards.is_empty() { state_changed = true; }
-    log(&format!("  Applying {} cards...", game_state.cards.len()));
-    for card_data in game_state.cards {
-        let entity = card_data.entity;
-        world.create_entity_with_id(entity); // 存在保証
-
-        // Card コンポーネント
-        let card_component = Card {
-            suit: card_data.suit.into(), // protocol::Suit -> components::card::Suit
-            rank: card_data.rank.into(), // protocol::Rank -> components::card::Rank
-            is_face_up: card_data.is_face_up,
-        };
-        world.add_component(entity, card_component);
-
-        // StackInfo コンポーネント
-        let stack_info_component = StackInfo {
-            stack_type: card_data.stack_type.into(), // protocol::StackType -> components::stack::StackType
-            position_in_stack: card_data.position_in_stack,
-        };
-        world.add_component(entity, stack_info_component);
-
-        // Position コンポーネント
-        let position_component = Position {
-            x: card_data.position.x,
-            y: card_data.position.y,
-        };
-        world.add_component(entity, position_component);
+    // --- 2. カードを反映 (サーバーのエンティティを、EntityMap でこっちのエンティティに付け替えて) ---
+    // こっちの World とサーバーの状態が食い違ってたか (undo 履歴を捨てるかどうか)。位置はレイアウト次第なので比べない
+    let cards_before = card_states(world);
+    let mut entity_map = world.remove_resource::<EntityMap>().unwrap_or_default();
+    let (applied, touched) = match &cards {
+        CardUpdate::Whole(snapshot) => {
+            log(&format!("  Applying {} cards...", snapshot.count::<Card>()));
+            (world.restore_mapped(snapshot, &mut entity_map), !snapshot.is_empty())
+        }
+        CardUpdate::Delta(delta) => (world.apply_delta_mapped(delta, &mut entity_map), !delta.is_empty()),
+    };
+    world.insert_resource(entity_map);
+    applied?;
+    state_changed |= touched;
+    let cards_after = card_states(world);
+    let diverged = cards_before != cards_after;
+    if cards_before.len() != cards_after.len() { state_changed = true; }
+    for (entity, _) in cards_after {
+        world.remove_component::<DraggingInfo>(entity); // ドラッグ中だったとしてもサーバーの状態が優先
+    }
+
+    if diverged {
+        if let Some(history) = world.resource_mut::<History>() {
+            log("  Local state diverged from the server; clearing undo history.");
+            history.clear();
+        }
     }
 
     log("App::State: Game state update applied.");
-    state_changed // 変更があったかどうかを返す
-} 
\ No newline at end of file
+    Ok(state_changed) // 変更があったかどうかを返す
+}
+
+/// World をロックする (poison してても中身を使う)。
+fn lock_world(world_arc: &Arc<Mutex<World>>) -> MutexGuard<'_, World> {
+    match world_arc.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            log(&format!("World mutex poisoned: {:?}. Recovering...", poisoned));
+            poisoned.into_inner()
+        }
+    }
+}
+
+/// カードごとの (中身, スタック, 何番目か)。サーバーの状態を反映する前後で比べる用。
+fn card_states(world: &World) -> BTreeMap<Entity, (Card, StackType, u8)> {
+    world
+        .query::<(Entity, &Card, &StackInfo)>()
+        .map(|(entity, card, stack_info)| (entity, (card.clone(), stack_info.stack_type, stack_info.position_in_stack)))
+        .collect()
+}
+
+/// セーブデータ (`persistence::SavedGame`) から World を元に戻す。
+/// カードはサーバーの状態と同じく `apply_game_state` で反映して、履歴とかはその後で戻すよ。
+/// セーブデータのエンティティはサーバーのとは関係ないので、対応表 (`EntityMap`) は一時的に使って、終わったら空にする。
+pub fn restore_saved_game(world_arc: &Arc<Mutex<World>>, saved: &SavedGame) {
+    log("App::State: Restoring a saved game...");
+    lock_world(world_arc).insert_resource(EntityMap::default());
+    apply_game_state(world_arc, saved.state.clone(), None);
+
+    let mut world = lock_world(world_arc);
+    let entity_map = world.insert_resource(EntityMap::default()).unwrap_or_default();
+    saved.restore_resources(&mut world, |entity| entity_map.local(entity));
+    log(&format!("App::State: Restored {} cards from the saved game.", saved.state.world.count::<Card>()));
+}
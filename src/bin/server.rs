@@ -0,0 +1,227 @@
+// src/bin/server.rs
+//! ネイティブ版の WebSocket ゲームサーバーだよ！🦀🌐
+//!
+//! ルールチェック付きの権威サーバー (`npm run dev` もこれを起動するよ)。
+//! ゲームの中身は全部 `ecs_wasm_game5::server::GameServer` がやってて、
+//! このファイルは WebSocket の接続管理とメッセージの配達だけを担当するよ。
+//!
+//! 起動: `cargo run --no-default-features --features server --bin server -- [listen_addr] [options]`
+//! (listen_addr を省略すると `0.0.0.0:8101`、クライアントがつなぎに行くポート)
+//!
+//! 山札のルール (`GameRules`) はオプションで決めるよ:
+//! - `--draw <1|3>`: 何枚ずつめくるか (デフォルト 1)
+//! - `--max-passes <n>`: 山札を何周までできるか (デフォルト無制限)
+//! - `--waste-fan <n>`: 捨て札を何枚ずらして見せるか (デフォルトはめくる枚数と同じ)
+//! - `--no-foundation-to-tableau`: 組札から場札に戻せなくする
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ecs_wasm_game5::config::GameRules;
+use ecs_wasm_game5::protocol::{ClientMessage, PlayerId, ServerMessage};
+use ecs_wasm_game5::server::{GameServer, Outgoing, Recipient};
+use log::{error, info, warn, LevelFilter, Metadata, Record};
+use tungstenite::{Error as WsError, Message, WebSocket};
+
+const DEFAULT_LISTEN_ADDR: &str = "0.0.0.0:8101";
+/// 受信待ちをこの時間で切り上げて、送信キューを確認しに行く。
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// ゲームセッションと、各接続への送信チャンネルをまとめたもの。
+struct Hub {
+    game: GameServer,
+    clients: HashMap<PlayerId, Sender<String>>,
+}
+
+impl Hub {
+    /// `GameServer` が返した返信を、宛先ごとにそれぞれの接続スレッドへ配る。
+    fn dispatch(&self, outgoing: Vec<Outgoing>) {
+        for Outgoing { recipient, message } in outgoing {
+            let json = match serde_json::to_string(&message) {
+                Ok(json) => json,
+                Err(e) => {
+                    error!("Failed to serialize {:?}: {}", message, e);
+                    continue;
+                }
+            };
+            for (&player_id, sender) in &self.clients {
+                let wanted = match recipient {
+                    Recipient::Player(id) => id == player_id,
+                    Recipient::AllExcept(id) => id != player_id,
+                    Recipient::All => true,
+                };
+                if wanted {
+                    // 受け取り側のスレッドがもう終わってても気にしない (切断処理は向こうでやる)
+                    let _ = sender.send(json.clone());
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    log::set_logger(&STDERR_LOGGER).expect("logger should only be installed once");
+    log::set_max_level(LevelFilter::Info);
+
+    let Options { listen_addr, rules } = parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("Usage: server [listen_addr] [--draw <1|3>] [--max-passes <n>] [--waste-fan <n>] [--no-foundation-to-tableau]");
+        process::exit(2);
+    });
+    let listener = TcpListener::bind(&listen_addr)
+        .unwrap_or_else(|e| panic!("Failed to bind {}: {}", listen_addr, e));
+    info!("Authoritative game server listening on ws://{} 🚀 ({:?})", listen_addr, rules);
+
+    let mut game = GameServer::new();
+    game.set_game_rules(rules);
+    let hub = Arc::new(Mutex::new(Hub { game, clients: HashMap::new() }));
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let hub = Arc::clone(&hub);
+                thread::spawn(move || handle_connection(stream, hub));
+            }
+            Err(e) => warn!("Failed to accept connection: {}", e),
+        }
+    }
+}
+
+/// コマンドラインで決めた設定。
+struct Options {
+    listen_addr: String,
+    rules: GameRules,
+}
+
+/// `[listen_addr] [options]` を読む。ルールは `GameRules::new` でチェックするよ。
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut listen_addr = None;
+    let mut draw_count = 1;
+    let mut max_passes = None;
+    let mut waste_fan = None;
+    let mut foundation_to_tableau = true;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--draw" => draw_count = option_value(&arg, args.next())?,
+            "--max-passes" => max_passes = Some(option_value(&arg, args.next())?),
+            "--waste-fan" => waste_fan = Some(option_value(&arg, args.next())?),
+            "--no-foundation-to-tableau" => foundation_to_tableau = false,
+            option if option.starts_with("--") => return Err(format!("Unknown option {}", option)),
+            _ if listen_addr.is_none() => listen_addr = Some(arg),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    let rules = GameRules::new(draw_count, max_passes, waste_fan.unwrap_or(draw_count))?
+        .with_foundation_to_tableau(foundation_to_tableau);
+    Ok(Options { listen_addr: listen_addr.unwrap_or_else(|| DEFAULT_LISTEN_ADDR.to_string()), rules })
+}
+
+/// `--option <value>` の値を読む。
+fn option_value<T: FromStr>(option: &str, value: Option<String>) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    let value = value.ok_or_else(|| format!("{} needs a value", option))?;
+    value.parse().map_err(|e| format!("Invalid value {:?} for {}: {}", value, option, e))
+}
+
+/// 1つの WebSocket 接続を最後まで面倒見るよ。接続ごとに1スレッド。
+fn handle_connection(stream: TcpStream, hub: Arc<Mutex<Hub>>) {
+    let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_else(|_| "unknown".to_string());
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("WebSocket handshake with {} failed: {}", peer, e);
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        warn!("Failed to set read timeout for {}: {}", peer, e);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let player_id = {
+        let mut hub = hub.lock().expect("hub mutex poisoned");
+        let player_id = hub.game.connect();
+        hub.clients.insert(player_id, sender);
+        player_id
+    };
+    info!("{} connected as player {}.", peer, player_id);
+
+    run_connection(&mut socket, player_id, &receiver, &hub);
+
+    let mut hub = hub.lock().expect("hub mutex poisoned");
+    hub.clients.remove(&player_id);
+    let outgoing = hub.game.disconnect(player_id);
+    hub.dispatch(outgoing);
+    info!("{} (player {}) disconnected.", peer, player_id);
+}
+
+/// 受信 → GameServer で処理 → 送信キューを流す、を接続が切れるまで繰り返す。
+fn run_connection(
+    socket: &mut WebSocket<TcpStream>,
+    player_id: PlayerId,
+    receiver: &Receiver<String>,
+    hub: &Arc<Mutex<Hub>>,
+) {
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let mut hub = hub.lock().expect("hub mutex poisoned");
+                let outgoing = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(message) => hub.game.handle_message(player_id, message),
+                    Err(e) => {
+                        warn!("Invalid message from player {}: {} ({})", player_id, e, text);
+                        vec![Outgoing {
+                            recipient: Recipient::Player(player_id),
+                            message: ServerMessage::Error { message: format!("Invalid message: {}", e) },
+                        }]
+                    }
+                };
+                hub.dispatch(outgoing);
+            }
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {} // Binary / Ping / Pong は使わない (Pong の返信は tungstenite がやってくれる)
+            Err(WsError::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(WsError::ConnectionClosed) | Err(WsError::AlreadyClosed) => return,
+            Err(e) => {
+                warn!("Connection error for player {}: {}", player_id, e);
+                return;
+            }
+        }
+
+        while let Ok(json) = receiver.try_recv() {
+            if let Err(e) = socket.send(Message::Text(json)) {
+                warn!("Failed to send to player {}: {}", player_id, e);
+                return;
+            }
+        }
+    }
+}
+
+/// `log` クレートの出力を標準エラーに流すだけの小さなロガー。
+struct StderrLogger;
+
+static STDERR_LOGGER: StderrLogger = StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
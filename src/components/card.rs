@@ -4,6 +4,8 @@
 use serde::{Serialize, Deserialize};
 // Component トレイトを使う宣言！このファイルで作る構造体がコンポーネントであることを示すため！
 use crate::ecs::component::Component; // `crate::` はプロジェクトのルートから、って意味ね！
+use crate::ecs::snapshot::SerializableComponent;
+#[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 // use rand::{seq::SliceRandom, thread_rng}; // logic/deck.rs に移動したため不要
 
@@ -15,7 +17,7 @@ use wasm_bindgen::prelude::*;
 /// - PartialEq, Eq: 等しいか比較できるように (`==`)
 /// - Hash: HashMap のキーとかで使えるように
 /// - Serialize, Deserialize: JSON などに変換できるように
-#[wasm_bindgen]
+#[cfg_attr(feature = "web", wasm_bindgen)] // JS に公開するのはブラウザ版の時だけ
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
     Heart,   // ❤️
@@ -28,7 +30,7 @@ pub enum Suit {
 ///
 /// スートと同じように #[derive(...)] を付けておくよ！
 /// PartialOrd, Ord も追加して、ランクの大小比較 (`<`, `>`) もできるようにしておこう！ソリティアで使いそう！👍
-#[wasm_bindgen]
+#[cfg_attr(feature = "web", wasm_bindgen)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
 pub enum Rank {
     Ace = 1, // A は 1 として扱うよ (ソリティアのルールによるかもだけど、一旦こうしておく！)
@@ -63,9 +65,22 @@ pub struct Card {
     pub is_face_up: bool, // カードが表向きかどうか
 }
 
+impl Card {
+    /// 新しいカードを裏向きで作るよ！🂠
+    /// 配る前のデッキのカードはみんな裏向きだからね。
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        Self { suit, rank, is_face_up: false }
+    }
+}
+
 // Card 構造体が Component であることを示すよ！
 impl Component for Card {}
 
+// スナップショット (セーブデータ・サーバーとの同期) に入る📸
+impl SerializableComponent for Card {
+    const NAME: &'static str = "card";
+}
+
 // Suit の全種類を配列として定義しておくと、後でループ処理とかで便利だよ！
 pub const ALL_SUITS: [Suit; 4] = [Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade];
 
@@ -94,7 +109,7 @@ mod tests {
         };
         assert_eq!(card.suit, Suit::Heart);
         assert_eq!(card.rank, Rank::Ace);
-        assert_eq!(card.is_face_up, true);
+        assert!(card.is_face_up);
         println!("Card 作成テスト: {:?} - 成功", card);
     }
 
@@ -4,15 +4,15 @@
 use serde::{Serialize, Deserialize};
 // Component トレイトを使うからインポートするよ
 use crate::ecs::component::Component;
+use crate::ecs::resource::Resource;
 
 /// ゲーム全体の現在の状態を表す列挙型だよ！
 ///
 /// ゲームがまだプレイ中なのか、それとも誰かが勝って終わったのか、
 /// みたいな状況を示すのに使うよ！🏆🏁
 ///
-/// このコンポーネントは、普通はゲーム全体で一つだけ存在する特別なエンティティ
-/// （例えば、エンティティIDが0とか、特別な名前をつけたエンティティとか）に
-/// アタッチされることが多いよ。（リソースとかシングルトンって呼ばれたりもする）
+/// ゲーム全体で一つだけあればいいので、`GameState` は World のリソースとして置くよ
+/// (`world.insert_resource(GameState { .. })` / `world.resource::<GameState>()`)。
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GameStatus {
     /// ゲームが進行中の状態
@@ -28,30 +28,100 @@ pub enum GameStatus {
     //       ポーズ中 (Paused) とか、他の状態も追加できるね！
 }
 
-/// ゲーム状態を保持するコンポーネント。
+/// ゲーム状態を保持するリソース。
 ///
-/// 中身はシンプルに GameStatus enum を持つだけ！
-/// これを World に登録して、一つのエンティティに持たせることで、
-/// どこからでも現在のゲーム状態を参照・更新できるようにするんだ。便利！💡
+/// 中身は GameStatus enum と、このゲームを配った時のシード！
+/// World にリソースとして置いておけば、どのシステムからでも
+/// `world.resource::<GameState>()` で現在のゲーム状態を参照・更新できるんだ。便利！💡
+/// (昔はエンティティに持たせてたので Component も実装したままにしてるよ)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameState {
     pub status: GameStatus,
+    /// カードを配った時のシャッフルのシード。同じシードで配れば同じ盤面になるよ🎲
+    /// (分からない時、例えば古いセーブデータなどは `None`)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// このゲームで捨て札を山札に戻した回数。`GameRules::max_passes` の判定に使うよ (undo すると減る)。
+    #[serde(default)]
+    pub stock_recycles: u32,
 }
 
 // GameState 構造体が Component であることを示すマーカー！✅
 impl Component for GameState {}
+// World に1個だけ置くリソースとしても使えるよ！🌏
+impl Resource for GameState {}
+
+impl GameState {
+    /// プレイ中の状態で作るよ。新しいゲームを始める時に使う。
+    pub fn playing() -> Self {
+        Self { status: GameStatus::Playing, seed: None, stock_recycles: 0 }
+    }
+
+    /// `seed` で配った新しいゲームの状態を作るよ。
+    pub fn playing_with_seed(seed: u64) -> Self {
+        Self { status: GameStatus::Playing, seed: Some(seed), stock_recycles: 0 }
+    }
+}
+
+/// このゲームの経過時間と手数だよ！⏱️ (World のリソース。配り直すたびにまっさらになる)
+///
+/// 時計は最初の1手で動き出して、タブが隠れてる間 (`paused`) と勝った後 (`finished`) は止まる。
+/// 進めるのは `GameClockSystem` (`Time` の dt を足していく)。
+/// マルチプレイでは手数はサーバーが数えて、経過時間はそれぞれのブラウザで数えるよ。
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct GameClock {
+    /// 最初の1手からの経過秒数 (止まってた間は数えない)。
+    pub elapsed_seconds: f64,
+    /// 手数 (カードの移動と山札の操作。undo は数えない)。
+    pub moves: u32,
+    /// もう最初の1手を指した？
+    pub started: bool,
+    /// タブが隠れてて一時停止中？
+    pub paused: bool,
+    /// 勝って止まった？
+    pub finished: bool,
+}
+
+impl Resource for GameClock {}
+
+impl GameClock {
+    /// World に置いてある時計 (無ければまだ始まってない時計)。
+    pub fn from_world(world: &crate::ecs::world::World) -> Self {
+        world.resource::<GameClock>().copied().unwrap_or_default()
+    }
+
+    /// 時計が進んでる？
+    pub fn is_running(&self) -> bool {
+        self.started && !self.paused && !self.finished
+    }
+
+    /// 1手指した (最初の1手なら時計が動き出す)。
+    pub fn record_move(&mut self) {
+        self.moves += 1;
+        self.started = true;
+    }
+
+    /// 動いてれば `dt` 秒進める。
+    pub fn advance(&mut self, dt: f64) {
+        if self.is_running() {
+            self.elapsed_seconds += dt;
+        }
+    }
+}
 
 // --- テスト ---
 #[cfg(test)]
 mod tests {
     use super::*; // 上で定義した GameStatus, GameState を使う
-    use crate::component::Component; // Component トレイトもテストで使う
+    use crate::ecs::component::Component; // Component トレイトもテストで使う
 
     #[test]
     fn create_game_state_component() {
         // 最初はプレイ中の状態
         let initial_state = GameState {
             status: GameStatus::Playing,
+            seed: None,
+            stock_recycles: 0,
         };
 
         assert_eq!(initial_state.status, GameStatus::Playing);
@@ -60,6 +130,8 @@ mod tests {
         // ゲームオーバーの状態も作ってみる (プレイヤー1が勝利！)
         let game_over_state = GameState {
             status: GameStatus::GameOver { winner_id: Some(1) },
+            seed: Some(7),
+            stock_recycles: 2,
         };
 
         assert_eq!(game_over_state.status, GameStatus::GameOver { winner_id: Some(1) });
@@ -73,6 +145,25 @@ mod tests {
         println!("GameState コンポーネント作成テスト、成功！🎉");
     }
 
+    #[test]
+    fn clock_runs_only_between_the_first_move_and_the_win() {
+        let mut clock = GameClock::default();
+        clock.advance(5.0);
+        assert_eq!(clock.elapsed_seconds, 0.0, "最初の1手までは止まってるはず");
+
+        clock.record_move();
+        clock.advance(2.0);
+        clock.paused = true;
+        clock.advance(60.0);
+        clock.paused = false;
+        clock.record_move();
+        clock.advance(1.5);
+        clock.finished = true;
+        clock.advance(10.0);
+        assert_eq!((clock.elapsed_seconds, clock.moves), (3.5, 2));
+        println!("GameClock のテスト、成功！⏱️");
+    }
+
     #[test]
     fn game_status_comparison() {
         let playing = GameStatus::Playing;
@@ -4,6 +4,8 @@
 use serde::{Serialize, Deserialize};
 // Component トレイトを使う宣言！Player がコンポーネントであることを示す！
 use crate::ecs::component::Component;
+use crate::ecs::resource::Resource;
+use crate::protocol::PlayerId;
 
 /// プレイヤーを表すコンポーネントだよ！👤
 ///
@@ -11,7 +13,7 @@ use crate::ecs::component::Component;
 /// そして今誰のターンなのか、といった情報を管理する必要があるね！
 ///
 /// - `id`: プレイヤーを識別するための一意なID。ここでは単純に数値 (`u32`) にしてみるね！
-///         ネットワーク接続とかと紐づけることも考えられるけど、まずはシンプルに！
+///   ネットワーク接続とかと紐づけることも考えられるけど、まずはシンプルに！
 /// - `is_current_turn`: このプレイヤーが現在操作可能かどうかを示すフラグ。
 ///
 /// #[derive(...)] のおまじない！
@@ -21,11 +23,8 @@ use crate::ecs::component::Component;
 /// - Serialize, Deserialize: JSON などに変換できるように
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
-    // ★修正: id フィールドは PlayerId 型 (u32) を使う想定だったかも？
-    //         一旦 state_handler の実装に合わせて usize でEntity IDと紐づける？
-    //         あるいは state_handler 側で id を使う？
-    //         PlayerId は protocol.rs で定義されてる u32。
-    // pub id: u32, // ← コメントアウト (サーバーからの PlayerData.id を直接コンポーネントに持たせるかは要検討)
+    // サーバーが振ったプレイヤーID。エンティティIDとは別物 (エンティティは World が自分で振る) なので、ここに持たせる
+    pub id: PlayerId,
     pub name: String, // ★追加: プレイヤー名！★
     pub is_current_turn: bool, // 現在このプレイヤーのターンか？
     // TODO: 必要なら持ち点とか他の情報も追加
@@ -34,6 +33,15 @@ pub struct Player {
 // Player 構造体が Component であることを示すマーカー！✅
 impl Component for Player {}
 
+/// このクライアントを操作してる「自分」のプレイヤーID を持つリソースだよ！🙋
+/// サーバーから GameJoined を受け取った時に World に置かれる。まだ参加してなければリソース自体が無い。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalPlayer {
+    pub id: PlayerId,
+}
+
+impl Resource for LocalPlayer {}
+
 // --- テスト ---
 #[cfg(test)]
 mod tests {
@@ -43,23 +51,23 @@ mod tests {
     #[test]
     fn create_player_component() {
         let player1 = Player {
-            // id: 0, // id フィールド削除に伴いコメントアウト
+            id: 0,
             name: "Player 1".to_string(), // ★追加
             is_current_turn: true,
         };
         let player2 = Player {
-            // id: 1,
+            id: 1,
             name: "Player 2".to_string(), // ★追加
             is_current_turn: false,
         };
 
         // 値の確認
-        // assert_eq!(player1.id, 0);
+        assert_eq!(player1.id, 0);
         assert_eq!(player1.name, "Player 1"); // ★追加
-        assert_eq!(player1.is_current_turn, true);
-        // assert_eq!(player2.id, 1);
+        assert!(player1.is_current_turn);
+        assert_eq!(player2.id, 1);
         assert_eq!(player2.name, "Player 2"); // ★追加
-        assert_eq!(player2.is_current_turn, false);
+        assert!(!player2.is_current_turn);
 
         println!("作成したプレイヤー1: {:?}", player1);
         println!("作成したプレイヤー2: {:?}", player2);
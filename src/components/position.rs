@@ -4,6 +4,7 @@
 use serde::{Serialize, Deserialize};
 // Component トレイトを使う宣言！Position がコンポーネントであることを示す！
 use crate::ecs::component::Component;
+use crate::ecs::snapshot::SerializableComponent;
 
 /// 2D空間での位置を表すコンポーネントだよ！ (x, y) 座標を持つよ。📍
 ///
@@ -28,6 +29,11 @@ pub struct Position {
 // Position 構造体が Component であることを示すマーカー！ これ大事！✅
 impl Component for Position {}
 
+// スナップショット (セーブデータ・サーバーとの同期) に入る📸
+impl SerializableComponent for Position {
+    const NAME: &'static str = "position";
+}
+
 // --- テスト ---
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,89 @@
+// src/components/score.rs
+//! 点数を持つリソース `Score` だよ！💯
+//!
+//! 点数のつけ方 (何をしたら何点か) は `logic::scoring` にまとめてある。
+//! ここは「今何点か」と「どのモードで数えてるか」を持つだけ。
+
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+
+/// 点数の数え方。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScoringMode {
+    /// いつものやつ (Windows のソリティアと同じ)。0点から始まって、マイナスにはならない。
+    #[default]
+    Standard,
+    /// ベガス。1ゲーム $52 で買って、組札に上げたカード1枚につき $5 もらえる。
+    /// 点数 (所持金) は次のゲームに持ち越すよ。
+    Vegas,
+}
+
+impl ScoringMode {
+    /// JS とのやり取りに使う名前 (`"standard"` / `"vegas"`)。
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoringMode::Standard => "standard",
+            ScoringMode::Vegas => "vegas",
+        }
+    }
+
+    /// `name` の逆。知らない名前なら `Err`。
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "standard" => Ok(ScoringMode::Standard),
+            "vegas" => Ok(ScoringMode::Vegas),
+            _ => Err(format!("Unknown scoring mode {:?}", name)),
+        }
+    }
+}
+
+/// 今の点数。World にリソースとして置くよ (置いてなければ、スタンダードの0点として扱う)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Score {
+    pub mode: ScoringMode,
+    /// 点数。ベガスなら、前のゲームからの持ち越しも込みの所持金 (ドル)。
+    pub points: i32,
+}
+
+impl Resource for Score {}
+
+impl Score {
+    /// `mode` で数え始める (まだ配ってない状態)。
+    pub fn new(mode: ScoringMode) -> Self {
+        Self { mode, ..Self::default() }
+    }
+
+    /// World に置いてある点数 (無ければスタンダードの0点)。
+    pub fn from_world(world: &World) -> Self {
+        world.resource::<Score>().copied().unwrap_or_default()
+    }
+
+    /// 新しいゲームを配った時の点数。
+    /// スタンダードは0点から、ベガスは持ち越した所持金から $52 払ってスタート。
+    pub fn new_game(self) -> Self {
+        let points = match self.mode {
+            ScoringMode::Standard => 0,
+            ScoringMode::Vegas => self.points - crate::logic::scoring::VEGAS_BUY_IN,
+        };
+        Self { points, ..self }
+    }
+
+    /// 点数を足す (引く)。スタンダードは0点より下がらないよ。
+    pub fn add(&mut self, points: i32) {
+        self.points += points;
+        if self.mode == ScoringMode::Standard {
+            self.points = self.points.max(0);
+        }
+    }
+
+    /// 画面に出す文字 (ベガスはドル表記)。
+    pub fn label(&self) -> String {
+        match self.mode {
+            ScoringMode::Standard => format!("Score: {}", self.points),
+            ScoringMode::Vegas if self.points < 0 => format!("Vegas: -${}", -self.points),
+            ScoringMode::Vegas => format!("Vegas: ${}", self.points),
+        }
+    }
+}
@@ -23,6 +23,9 @@ pub enum StackType {
     /// 山札からめくったカードを置く場所 (Waste) だよ。
     /// クロンダイクでは通常1つだけど、ゲームによっては複数あるかも？
     Waste,
+    /// フリーセル (FreeCell) の空きマスだよ。カードを1枚だけ一時的に置ける。番号 (0-3) を持つ。
+    /// クロンダイクでは使わない (遊び方ごとのスタックは `logic::variants` を見てね)。
+    FreeCell(u8),
     // 将来的には： Hand(PlayerId), DiscardPile など他のゲーム用に拡張できる
     Hand,
 }
@@ -32,8 +35,11 @@ pub enum StackType {
 /// そのスタックの中で何番目か、などを管理するよ。
 /// Component トレイトを実装して、ECS で使えるようにする。
 use crate::ecs::component::Component;
+use crate::ecs::snapshot::SerializableComponent;
+use crate::ecs::entity::Entity;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone)] // デバッグ表示とクローンができるように
+#[derive(Debug, Clone, Serialize, Deserialize)] // デバッグ表示とクローン、スナップショット用に serde も
 pub struct StackInfo {
     /// カードが属しているスタックの種類。
     pub stack_type: StackType,
@@ -52,6 +58,96 @@ impl StackInfo {
 // 中身は空でOK！マーカーとして機能するよ。
 impl Component for StackInfo {} // これで World に登録できるようになる
 
+// スナップショット (セーブデータ・サーバーとの同期) に入る📸
+impl SerializableComponent for StackInfo {
+    const NAME: &'static str = "stack_info";
+}
+
+/// スタックごとのカードの並びをまとめた索引 (インデックス) だよ！📚
+/// 「Tableau 3 の一番上は？」「Waste に何枚ある？」を、全 StackInfo をスキャンせずに答えられる。
+///
+/// 中身は StackInfo から作られるキャッシュで、World が持ってる (`World::stacks()` で取れる)。
+/// StackInfo が書き換えられる可能性がある操作をすると World が自動で作り直すので、
+/// 自分で更新する必要はないよ👍
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stacks {
+    /// スタックごとのエンティティ。position_in_stack の昇順 (先頭が一番下、最後が一番上)。
+    piles: HashMap<StackType, Vec<Entity>>,
+    /// エンティティ → (どのスタックか, そのスタックの何番目か)。
+    locations: HashMap<Entity, (StackType, usize)>,
+}
+
+impl Stacks {
+    /// `(エンティティ, StackInfo)` の一覧から索引を作る。
+    /// 同じ position_in_stack のカードがあったら Entity の小さい方を下にするよ (順番を決めるため)。
+    pub fn build<'a>(entries: impl IntoIterator<Item = (Entity, &'a StackInfo)>) -> Self {
+        let mut sorted: HashMap<StackType, Vec<(u8, Entity)>> = HashMap::new();
+        for (entity, stack_info) in entries {
+            sorted
+                .entry(stack_info.stack_type)
+                .or_default()
+                .push((stack_info.position_in_stack, entity));
+        }
+
+        let mut stacks = Stacks::default();
+        for (stack_type, mut cards) in sorted {
+            cards.sort();
+            let pile: Vec<Entity> = cards.into_iter().map(|(_, entity)| entity).collect();
+            stacks.reindex_from(stack_type, &pile, 0);
+            stacks.piles.insert(stack_type, pile);
+        }
+        stacks
+    }
+
+    /// スタックのカードを下から順に返す。空なら空スライス。
+    pub fn cards(&self, stack: StackType) -> &[Entity] {
+        self.piles.get(&stack).map_or(&[], Vec::as_slice)
+    }
+
+    /// スタックの一番上のカード。
+    pub fn top(&self, stack: StackType) -> Option<Entity> {
+        self.cards(stack).last().copied()
+    }
+
+    /// スタックの枚数。
+    pub fn len(&self, stack: StackType) -> usize {
+        self.cards(stack).len()
+    }
+
+    /// スタックが空なら `true`。
+    pub fn is_empty(&self, stack: StackType) -> bool {
+        self.cards(stack).is_empty()
+    }
+
+    /// エンティティがどのスタックの何番目 (下から 0 始まり) にいるか。
+    pub fn location(&self, entity: Entity) -> Option<(StackType, usize)> {
+        self.locations.get(&entity).copied()
+    }
+
+    /// `from` の `index` 番目から上を全部 (ひとかたまりのまま) `to` の一番上に載せる。
+    /// 動いたエンティティを下から順に返すよ。`index` が範囲外なら何もせず空の Vec。
+    ///
+    /// これは索引だけの操作。StackInfo も一緒に書き換えたいときは `World::move_run` を使ってね。
+    pub fn move_run(&mut self, from: StackType, index: usize, to: StackType) -> Vec<Entity> {
+        let run = match self.piles.get_mut(&from) {
+            Some(pile) if index < pile.len() => pile.split_off(index),
+            _ => return Vec::new(),
+        };
+        let target = self.piles.entry(to).or_default();
+        let start = target.len();
+        target.extend_from_slice(&run);
+        self.reindex_from(to, &run, start);
+        run
+    }
+
+    /// `entities` が `stack` の `start` 番目から並んでるものとして locations を更新する。
+    fn reindex_from(&mut self, stack: StackType, entities: &[Entity], start: usize) {
+        for (offset, &entity) in entities.iter().enumerate() {
+            self.locations.insert(entity, (stack, start + offset));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +164,33 @@ mod tests {
 
         println!("StackInfo 作成テスト、成功！👍");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_stacks_index_orders_and_moves_runs() {
+        let infos = [
+            (Entity::new(10, 0), StackInfo::new(StackType::Tableau(0), 2)),
+            (Entity::new(11, 0), StackInfo::new(StackType::Tableau(0), 0)),
+            (Entity::new(12, 0), StackInfo::new(StackType::Tableau(0), 1)),
+            (Entity::new(13, 0), StackInfo::new(StackType::Waste, 0)),
+        ];
+        let mut stacks = Stacks::build(infos.iter().map(|(e, si)| (*e, si)));
+
+        assert_eq!(stacks.cards(StackType::Tableau(0)), &[Entity::new(11, 0), Entity::new(12, 0), Entity::new(10, 0)]);
+        assert_eq!(stacks.top(StackType::Tableau(0)), Some(Entity::new(10, 0)));
+        assert_eq!(stacks.len(StackType::Waste), 1);
+        assert!(stacks.is_empty(StackType::Stock));
+        assert_eq!(stacks.location(Entity::new(12, 0)), Some((StackType::Tableau(0), 1)));
+
+        // 真ん中から上をまとめて Waste の上へ
+        let moved = stacks.move_run(StackType::Tableau(0), 1, StackType::Waste);
+        assert_eq!(moved, vec![Entity::new(12, 0), Entity::new(10, 0)]);
+        assert_eq!(stacks.cards(StackType::Tableau(0)), &[Entity::new(11, 0)]);
+        assert_eq!(stacks.cards(StackType::Waste), &[Entity::new(13, 0), Entity::new(12, 0), Entity::new(10, 0)]);
+        assert_eq!(stacks.location(Entity::new(10, 0)), Some((StackType::Waste, 2)));
+
+        // 範囲外なら何もしない
+        assert!(stacks.move_run(StackType::Stock, 0, StackType::Waste).is_empty());
+
+        println!("Stacks 索引テスト、成功！👍");
+    }
+}
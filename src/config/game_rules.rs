@@ -0,0 +1,112 @@
+// src/config/game_rules.rs
+//! 遊び方の設定 (ルールのパラメータ) だよ！⚙️
+//!
+//! 山札を何枚ずつめくるか、何周までできるか、捨て札を何枚ずらして見せるか、組札から場札に戻せるか。
+//! World に `GameRules` リソースとして置いておくと、山札の処理 (`logic::stock_handler`)、
+//! 捨て札のレイアウト (`logic::layout_calculator`)、ヒント、ソルバー、サーバーがみんなこれを見るよ。
+//! 置いてなければ `GameRules::default()` (1枚めくり・何周でもOK) として扱う。
+
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRules {
+    /// 山札を1回クリックした時にめくる枚数 (1 か 3)。
+    pub draw_count: u8,
+    /// 山札を何周までできるか。`None` なら無制限。
+    /// 例えば `Some(3)` なら、捨て札を山札に戻せるのは2回まで。
+    pub max_passes: Option<u32>,
+    /// 捨て札の上から何枚を横にずらして見せるか (1 ならずらさずに重ねる)。
+    pub waste_fan: u8,
+    /// 組札の一番上のカードを場札に戻してもいい？ (普通のクロンダイクはOK。古いデータに無ければOK扱い)
+    #[serde(default = "allowed")]
+    pub foundation_to_tableau: bool,
+}
+
+fn allowed() -> bool {
+    true
+}
+
+impl Resource for GameRules {}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self::draw_one()
+    }
+}
+
+impl GameRules {
+    /// 1枚めくり、何周でもOK (今までのルール)。
+    pub fn draw_one() -> Self {
+        Self { draw_count: 1, max_passes: None, waste_fan: 1, foundation_to_tableau: true }
+    }
+
+    /// 3枚めくり、何周でもOK。めくった3枚が見えるように捨て札もずらすよ。
+    pub fn draw_three() -> Self {
+        Self { draw_count: 3, max_passes: None, waste_fan: 3, foundation_to_tableau: true }
+    }
+
+    /// 周回の上限を変えたコピーを返す。
+    pub fn with_max_passes(self, max_passes: Option<u32>) -> Self {
+        Self { max_passes, ..self }
+    }
+
+    /// 組札から場札に戻せるかを変えたコピーを返す。
+    pub fn with_foundation_to_tableau(self, foundation_to_tableau: bool) -> Self {
+        Self { foundation_to_tableau, ..self }
+    }
+
+    /// 値をチェックして作る。JS から来た設定とか、信用できない値はこれを通してね。
+    pub fn new(draw_count: u8, max_passes: Option<u32>, waste_fan: u8) -> Result<Self, String> {
+        if draw_count != 1 && draw_count != 3 {
+            return Err(format!("draw_count must be 1 or 3, got {}", draw_count));
+        }
+        if max_passes == Some(0) {
+            return Err("max_passes must be at least 1".to_string());
+        }
+        if waste_fan == 0 {
+            return Err("waste_fan must be at least 1".to_string());
+        }
+        Ok(Self { draw_count, max_passes, waste_fan, foundation_to_tableau: true })
+    }
+
+    /// World に置いてあるルール (無ければデフォルト)。
+    pub fn from_world(world: &World) -> Self {
+        world.resource::<GameRules>().copied().unwrap_or_default()
+    }
+
+    /// もう `recycles_done` 回捨て札を戻した後で、まだ戻していい？
+    pub fn allows_recycle(&self, recycles_done: u32) -> bool {
+        self.max_passes.is_none_or(|max_passes| recycles_done + 1 < max_passes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pass_limit_and_validation() {
+        let unlimited = GameRules::draw_three();
+        assert!(unlimited.allows_recycle(100));
+
+        // 3周まで = 戻せるのは2回
+        let three_passes = GameRules::draw_one().with_max_passes(Some(3));
+        assert!(three_passes.allows_recycle(0));
+        assert!(three_passes.allows_recycle(1));
+        assert!(!three_passes.allows_recycle(2));
+
+        assert_eq!(GameRules::new(3, None, 3), Ok(GameRules::draw_three()));
+        assert!(GameRules::new(2, None, 1).is_err());
+        assert!(GameRules::new(1, Some(0), 1).is_err());
+        assert!(GameRules::new(1, None, 0).is_err());
+
+        // 古いデータ (組札から戻せるかが無い) は戻せる扱い
+        let old: GameRules = serde_json::from_str(r#"{"draw_count":1,"max_passes":null,"waste_fan":1}"#).unwrap();
+        assert_eq!(old, GameRules::draw_one());
+        assert!(!GameRules::draw_one().with_foundation_to_tableau(false).foundation_to_tableau);
+        println!("GameRules の周回上限とチェックのテスト、成功！⚙️");
+    }
+}
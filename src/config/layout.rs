@@ -22,6 +22,7 @@ pub const STACK_X_OFFSET: f32 = 100.0; // 各スタック間の X 方向の間
 
 pub const WASTE_POS_X: f32 = STOCK_POS_X + STACK_X_OFFSET; // Stock の右隣
 pub const WASTE_POS_Y: f32 = STOCK_POS_Y; // Stock と同じ高さ
+pub const WASTE_FAN_X_OFFSET: f32 = 20.0; // 捨て札をずらして見せる時 (GameRules::waste_fan) の1枚ごとの X 方向のずれ
 
 // pub const FOUNDATION_START_X: f32 = WASTE_POS_X + STACK_X_OFFSET; // Waste の右隣から Foundation 開始 (★古い定義★)
 pub const FOUNDATION_START_X: f32 = 400.0; // ★ 再修正: 左にずらして見切れなくする ★
@@ -29,6 +30,11 @@ pub const FOUNDATION_START_Y: f32 = STOCK_POS_Y; // Stock/Waste と同じ高さ
 // pub const FOUNDATION_X_OFFSET: f32 = STACK_X_OFFSET; // 上で定義した共通の間隔を使う (★古い定義★)
 pub const FOUNDATION_X_OFFSET: f32 = 90.0; // ★ 修正: 少し狭める ★
 
+// フリーセル (遊び方がフリーセルの時だけ)。山札と捨て札が無いので、その場所から組札と同じ間隔で並べる
+pub const FREECELL_START_X: f32 = STOCK_POS_X;
+pub const FREECELL_START_Y: f32 = STOCK_POS_Y;
+pub const FREECELL_X_OFFSET: f32 = FOUNDATION_X_OFFSET;
+
 // ★修正: Tableau の開始位置も調整 (Stock/Waste/Foundation とのバランス)★
 pub const TABLEAU_START_X: f32 = STOCK_POS_X; // Stock と同じ X 座標から開始 (7列配置する)
 pub const TABLEAU_START_Y: f32 = STOCK_POS_Y + 100.0 + 50.0; // Stock/Waste/Foundation の下に配置 (カード高さ約100 + 余白50)
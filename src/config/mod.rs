@@ -1,4 +1,7 @@
 // src/config/mod.rs
 //! ゲーム全体の設定値（レイアウト、ルールパラメータなど）を管理するモジュールだよ！
 
-pub mod layout; 
\ No newline at end of file
+pub mod game_rules; // 山札のめくり枚数や周回上限など
+pub mod layout; 
+
+pub use game_rules::GameRules;
@@ -0,0 +1,52 @@
+// src/console_logger.rs
+//! `log` クレートの出力をブラウザの console に流すロガーだよ！🪵 (web feature の時だけ)
+//!
+//! ルールやシステムのコードは `debug!` / `warn!` / `error!` で書いてあるので、
+//! これを入れておかないとブラウザでは何も出ない。Wasm の起動時 (`set_panic_hook`) に入れるよ。
+//! どこまで出すかは `set_log_level("debug")` みたいに JS から変えられる (最初は `info`)。
+
+use log::{Level, LevelFilter, Metadata, Record};
+use web_sys::console;
+
+/// 最初に出すレベル。`debug` はカード1枚動かすだけでもたくさん出るので、見たい時だけ上げてね。
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// レベルに合わせて `console.error` / `warn` / `info` / `debug` に振り分けるロガー。
+struct ConsoleLogger;
+
+static CONSOLE_LOGGER: ConsoleLogger = ConsoleLogger;
+
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = format!("[{}] {}", record.level(), record.args());
+        match record.level() {
+            Level::Error => console::error_1(&message.into()),
+            Level::Warn => console::warn_1(&message.into()),
+            Level::Info => console::info_1(&message.into()),
+            Level::Debug | Level::Trace => console::debug_1(&message.into()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// ロガーを入れる。2回目以降は何もしない (レベルもそのまま)。
+pub fn init() {
+    if log::set_logger(&CONSOLE_LOGGER).is_ok() {
+        log::set_max_level(DEFAULT_LEVEL);
+    }
+}
+
+/// どこまで出すか変える (`"off"`, `"error"`, `"warn"`, `"info"`, `"debug"`, `"trace"`)。知らない名前ならエラー。
+pub fn set_level(name: &str) -> Result<(), String> {
+    let level = name.parse::<LevelFilter>().map_err(|_| format!("Unknown log level {:?}", name))?;
+    log::set_max_level(level);
+    Ok(())
+}
@@ -0,0 +1,113 @@
+// src/ecs/change_detection.rs
+//! コンポーネントの「追加された / 書き換えられた / 消された」を覚えておく仕組みだよ！👀✨
+//!
+//! World は `change_tick` っていうカウンターを持ってて、コンポーネントを追加したり
+//! `&mut` で触ったりするたびに、その時の tick をエンティティごとに記録する。
+//! あとは「前回見た tick より新しいもの」を探せば、変わったものだけ取り出せるってワケ！
+//!
+//! - `World::added::<T>()` / `changed::<T>()` / `removed::<T>()`: 今のシステムが前回実行されてから変わったもの
+//! - `World::added_since::<T>(tick)` など: 好きな tick からの変化 (描画側が自分で前回の tick を覚えておく時用)
+//!
+//! 「変わった」は「`&mut` で借りられた」って意味なので、同じ値を書き戻しても changed 扱いになるよ。
+//! 本当に値が変わった時だけ通知したいなら、書く前に比べてね (LayoutSystem みたいに)。
+
+use std::collections::HashMap;
+
+use crate::ecs::entity::Entity;
+
+/// 1つのコンポーネントがいつ追加されて、いつ最後に書き換えられたか。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    /// 追加された時の tick。
+    pub added: u64,
+    /// 最後に書き換えられた (`&mut` で借りられた) 時の tick。追加も書き換えに含むよ。
+    pub changed: u64,
+}
+
+impl ComponentTicks {
+    fn new(tick: u64) -> Self {
+        Self { added: tick, changed: tick }
+    }
+
+    /// `since` より後に追加された？
+    pub fn is_added(&self, since: u64) -> bool {
+        self.added > since
+    }
+
+    /// `since` より後に追加 or 書き換えされた？
+    pub fn is_changed(&self, since: u64) -> bool {
+        self.changed > since
+    }
+}
+
+/// コンポーネント型1つ分の変更記録。World の `ComponentStoreEntry` が型ごとに1個持ってる。
+/// 中身の型 `T` を知らなくていいので、型消去したストレージの横に置いておけるよ。
+#[derive(Debug, Default)]
+pub(crate) struct ChangeTrackers {
+    /// 今コンポーネントを持ってるエンティティごとの tick。
+    ticks: HashMap<Entity, ComponentTicks>,
+    /// 消されたコンポーネント (エンティティと消された時の tick)。古いものは `clear_removed_until` で捨てる。
+    removed: Vec<(Entity, u64)>,
+}
+
+impl ChangeTrackers {
+    /// コンポーネントが入った (新規 or 上書き) 時に呼ぶ。上書きなら `added` はそのまま。
+    pub(crate) fn on_insert(&mut self, entity: Entity, tick: u64) {
+        self.ticks
+            .entry(entity)
+            .and_modify(|ticks| ticks.changed = tick)
+            .or_insert_with(|| ComponentTicks::new(tick));
+    }
+
+    /// `&mut` で貸し出した時に呼ぶ。
+    pub(crate) fn on_change(&mut self, entity: Entity, tick: u64) {
+        if let Some(ticks) = self.ticks.get_mut(&entity) {
+            ticks.changed = tick;
+        }
+    }
+
+    /// コンポーネントが消された時に呼ぶ。
+    pub(crate) fn on_remove(&mut self, entity: Entity, tick: u64) {
+        if self.ticks.remove(&entity).is_some() {
+            self.removed.push((entity, tick));
+        }
+    }
+
+    pub(crate) fn get(&self, entity: Entity) -> Option<ComponentTicks> {
+        self.ticks.get(&entity).copied()
+    }
+
+    /// 今コンポーネントを持ってるエンティティ (順不同)。
+    pub(crate) fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks.keys().copied()
+    }
+
+    /// `since` より後に追加されたエンティティ。
+    pub(crate) fn added_since(&self, since: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks
+            .iter()
+            .filter(move |(_, ticks)| ticks.is_added(since))
+            .map(|(&entity, _)| entity)
+    }
+
+    /// `since` より後に追加 or 書き換えされたエンティティ。
+    pub(crate) fn changed_since(&self, since: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks
+            .iter()
+            .filter(move |(_, ticks)| ticks.is_changed(since))
+            .map(|(&entity, _)| entity)
+    }
+
+    /// `since` より後にコンポーネントを消されたエンティティ。
+    pub(crate) fn removed_since(&self, since: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.removed
+            .iter()
+            .filter(move |(_, tick)| *tick > since)
+            .map(|&(entity, _)| entity)
+    }
+
+    /// `tick` までに消された記録を捨てる (放っておくと溜まり続けるので)。
+    pub(crate) fn clear_removed_until(&mut self, tick: u64) {
+        self.removed.retain(|&(_, removed_at)| removed_at > tick);
+    }
+}
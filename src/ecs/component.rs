@@ -54,13 +54,13 @@ use std::any::Any;
 ///    - `: Debug`: さっきも出てきたね！デバッグプリント (`{:?}`) ができるようにするため。問題解決の強い味方！💪
 ///    - `: Any`: これもさっき説明したやつ！`World` が内部でいろんな型のコンポーネントを統一的に管理するために必要不可欠なんだ！🪄
 ///    - `: Send + Sync`: これはちょっと高度な話！🚀 Rustはマルチスレッド (複数の作業を同時に進めること) が得意なんだけど、
-///                  これを安全に行うための目印なんだ。`Send` は「他のスレッドにデータを送っても安全だよ！」って意味で、
-///                  `Sync` は「複数のスレッドから同時にデータにアクセスしても安全だよ！」って意味。
-///                  WASM (ブラウザで動くRust) は基本シングルスレッドだけど、将来的にマルチスレッド対応したり、
-///                  他のライブラリが要求してきたりする可能性もあるから、付けとくのがイケてるRustacean (Rust使い) の作法なんだ！😎
+///      これを安全に行うための目印なんだ。`Send` は「他のスレッドにデータを送っても安全だよ！」って意味で、
+///      `Sync` は「複数のスレッドから同時にデータにアクセスしても安全だよ！」って意味。
+///      WASM (ブラウザで動くRust) は基本シングルスレッドだけど、将来的にマルチスレッド対応したり、
+///      他のライブラリが要求してきたりする可能性もあるから、付けとくのがイケてるRustacean (Rust使い) の作法なんだ！😎
 ///    - `: 'static`: これはライフタイムっていう、データがどれくらいの間有効かを示す情報の一つ。`'static` は「プログラムが動いてる間、ずっと有効だよ！」
-///                 って意味で、参照 (データを直接持たずに場所だけ指してるやつ) を含まない型であることを示すんだ。
-///                 ジェネリクスとか `Any` トレイトを使うときに、この `Component` 型自体が変なタイミングで消えたりしないことを保証するために、よく必要になるよ！🕰️
+///      って意味で、参照 (データを直接持たずに場所だけ指してるやつ) を含まない型であることを示すんだ。
+///      ジェネリクスとか `Any` トレイトを使うときに、この `Component` 型自体が変なタイミングで消えたりしないことを保証するために、よく必要になるよ！🕰️
 ///
 /// **まとめ！** 📝
 /// ゲームで使う「データ部品」 (構造体) を作ったら、忘れずに `impl Component for YourStruct {}` って書いて、
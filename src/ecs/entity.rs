@@ -3,103 +3,223 @@
 // まずは serde っていうライブラリを使う宣言だよ！
 // これはデータをJSON形式とかに変換するのに使うんだけど、
 // EntityのIDをネットワークで送受信したり、セーブデータにしたりする時に役立つかも！
-use serde::{Serialize, Deserialize};
-// std::sync::atomic は、複数の処理が同時にIDを使おうとしても安全に番号を増やせるようにするためのものだよ！
-// AtomicUsize は、一度に一つの処理しかアクセスできない特殊な整数型。これでIDがかぶらないようにするんだ！
-// Ordering::Relaxed は、ちょっと難しいけど、パフォーマンスを良くするための設定だよ。今は気にしなくてOK！😉
-use std::sync::atomic::{AtomicUsize, Ordering};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Entity（エンティティ）とは、ゲームに登場する「モノ」を表すただの識別子（ID）だよ！
 /// 例えば、カード1枚1枚、プレイヤー、ゲームボード自体なんかもエンティティになる。
 ///
-/// この ID は、単なる数字（ここでは usize 型、符号なし整数）で、
-/// これだけだと意味はないんだけど、後で作る「コンポーネント」と組み合わせることで、
-/// 「IDが 5 のエンティティは、スペードのAのカードで、座標 (10, 20) にある」
-/// みたいに意味を持たせることができるんだ！便利でしょ？ ✨
+/// ID は「番号 (index)」と「世代 (generation)」の2つでできてるよ。
+/// エンティティを消すとその番号は空きになって、次に作るエンティティで使い回される。
+/// その時に世代が1つ増えるので、消えたエンティティを指してた古い Entity (stale handle) は
+/// 番号が同じでも別物として扱われる！うっかり別のカードを触っちゃう事故が起きないんだ✨
 ///
 /// #[derive(...)] っていうのは、Rustが自動的に便利な機能を追加してくれるおまじないみたいなものだよ！
 /// - PartialEq, Eq: ID同士が同じかどうか比較できるようにする (`==` とか)
-/// - PartialOrd, Ord: IDの大小を比較できるようにする (`<` とか `>`)
+/// - PartialOrd, Ord: IDの大小を比較できるようにする (番号 → 世代 の順で比べる)
 /// - Hash: IDを高速に検索できるデータ構造（HashMapとか）で使えるようにする
 /// - Clone, Copy: IDを簡単に複製できるようにする
 /// - Debug: IDをデバッグ出力 (`println!("{:?}", entity_id);` みたいに) できるようにする
-/// - Serialize, Deserialize: serde でJSONなどに変換できるようにする
-#[derive(
-    PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Serialize, Deserialize,
-)]
-pub struct Entity(pub usize); // usize 型の数値を保持するタプル構造体。これがエンティティIDの実体！
-
-/// EntityManager（エンティティマネージャー）は、エンティティIDを作成したり管理したりする役割を持つよ！
-/// ゲーム中に新しいエンティティ（例えば新しいカードとか）が必要になったら、
-/// このマネージャーに「新しいIDちょうだい！」ってお願いするんだ🙏
 ///
-/// Default トレイトを実装すると、`EntityManager::default()` で簡単に初期化できるようになるよ！
-#[derive(Default)]
-pub struct EntityManager {
-    // next_id は、次に割り振るエンティティIDを保持するよ。
-    // AtomicUsize を使ってるのは、マルチスレッド環境（複数の処理が同時に動く状況）でも
-    // 安全に一意なIDを発行できるようにするためだよ！💪
-    // ゲームが複雑になってきても安心！😌
-    next_id: AtomicUsize,
-    // TODO: 将来的には、削除されたエンティティIDを再利用する仕組みも入れたいね！♻️
-    // 今は単純にIDを増やし続けるだけだから、メモリ効率はちょっと悪いかも？🤔
-    // でも、まずはシンプルに作るのが一番！👍
+/// serde では `to_bits` の数値1個として読み書きするよ (世代 0 なら番号そのまま)。
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
 }
 
-impl EntityManager {
-    /// 新しい一意なエンティティIDを作成して返すよ！
-    ///
-    /// # 戻り値
-    /// - 新しく作成された `Entity`
-    ///
-    /// この関数は、内部カウンター `next_id` の値を1増やして、その値を新しいIDとして使うよ。
-    /// `fetch_add(1, Ordering::Relaxed)` は、アトミックに（安全に）カウンターを増やして、
-    /// 増やす前の値を返すんだ。だから、IDは0から始まる連番になるよ！0, 1, 2, 3... って感じ！🔢
-    pub fn create_entity(&self) -> Entity {
-        // Relaxed はパフォーマンスのため。今は深く考えなくて大丈夫！😉
-        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
-        Entity(id) // 新しいIDを Entity 構造体で包んで返す！
-    }
-
-    // TODO: エンティティを削除する機能もあとで追加しよう！🗑️
-    // pub fn destroy_entity(&mut self, entity: Entity) {
-    //     // 削除されたIDを管理するリストに追加する処理とかを書く予定！
-    // }
+impl Entity {
+    /// 番号と世代から Entity を作る。普通は `World::create_entity` を使ってね (これはテストや復元用)。
+    pub const fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    /// 番号。生きてるエンティティ同士なら番号は被らない。
+    pub const fn index(self) -> u32 {
+        self.index
+    }
+
+    /// 世代。同じ番号が使い回されるたびに1つ増える。
+    pub const fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// 64bit の数値1個にまとめる (下位32bit が番号、上位32bit が世代)。
+    pub const fn to_bits(self) -> u64 {
+        ((self.generation as u64) << 32) | self.index as u64
+    }
+
+    /// `to_bits` の逆。
+    pub const fn from_bits(bits: u64) -> Self {
+        Self { index: bits as u32, generation: (bits >> 32) as u32 }
+    }
+}
+
+impl Serialize for Entity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.to_bits())
+    }
+}
+
+impl<'de> Deserialize<'de> for Entity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Entity::from_bits)
+    }
+}
+
+/// EntityAllocator（エンティティアロケーター）は、エンティティIDを作ったり消したりする係だよ！
+/// 消された番号は空きリスト (free list) に入れておいて、次に作る時に世代を上げて使い回す♻️
+#[derive(Debug, Default)]
+pub struct EntityAllocator {
+    /// 番号ごとの今の世代と、生きてるかどうか。
+    slots: Vec<Slot>,
+    /// 空いてる番号 (後から空いたものほど後ろ)。
+    free_list: Vec<u32>,
+    /// 生きてるエンティティの数。
+    alive_count: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: u32,
+    alive: bool,
+}
+
+impl EntityAllocator {
+    /// 新しいエンティティを作る。空き番号があればそれを (世代を上げて) 使う。
+    pub fn allocate(&mut self) -> Entity {
+        self.alive_count += 1;
+        if let Some(index) = self.free_list.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.alive = true;
+            return Entity::new(index, slot.generation);
+        }
+        let index = u32::try_from(self.slots.len()).expect("ran out of entity indices");
+        self.slots.push(Slot { generation: 0, alive: true });
+        Entity::new(index, 0)
+    }
+
+    /// エンティティを消す。生きてなかった (古い世代も含む) なら false。
+    pub fn free(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let slot = &mut self.slots[entity.index as usize];
+        slot.alive = false;
+        // 世代が一周しちゃったら、その番号はもう使わない (古いハンドルと区別できなくなるので)
+        match slot.generation.checked_add(1) {
+            Some(next) => {
+                slot.generation = next;
+                self.free_list.push(entity.index);
+            }
+            None => slot.generation = u32::MAX,
+        }
+        self.alive_count -= 1;
+        true
+    }
+
+    /// 決まった Entity (番号と世代) をそのまま生き返らせる (`World::restore` 用)。
+    /// その番号で別のエンティティが生きてたら何もせずに false。
+    pub fn spawn_at(&mut self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        while self.slots.len() <= index {
+            self.free_list.push(self.slots.len() as u32);
+            self.slots.push(Slot { generation: 0, alive: false });
+        }
+        let slot = &mut self.slots[index];
+        if slot.alive {
+            return false;
+        }
+        slot.generation = entity.generation;
+        slot.alive = true;
+        self.free_list.retain(|&free| free != entity.index);
+        self.alive_count += 1;
+        true
+    }
+
+    /// そのエンティティが今も生きてるか。世代が違う古いハンドルは false。
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.slots
+            .get(entity.index as usize)
+            .is_some_and(|slot| slot.alive && slot.generation == entity.generation)
+    }
+
+    /// その番号で今生きてるエンティティ (世代込み)。
+    pub fn alive_at(&self, index: u32) -> Option<Entity> {
+        self.slots
+            .get(index as usize)
+            .filter(|slot| slot.alive)
+            .map(|slot| Entity::new(index, slot.generation))
+    }
+
+    /// 生きてるエンティティの数。
+    pub fn len(&self) -> usize {
+        self.alive_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive_count == 0
+    }
+
+    /// 生きてるエンティティを番号の小さい順に。
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.alive)
+            .map(|(index, slot)| Entity::new(index as u32, slot.generation))
+    }
 }
 
-// --- EntityManager のテスト ---
+// --- EntityAllocator のテスト ---
 // Rustにはテストを書く機能が組み込まれてるんだ！便利！🧪
 // `cargo test` ってコマンドを打つと、この中のコードが実行されるよ。
 #[cfg(test)]
 mod tests {
-    // `super::*` は、このモジュール（tests）の親モジュール（entity.rsの一番上の階層）にある
-    // Entity とか EntityManager を使うよ、っていう意味だよ。
     use super::*;
 
-    // `#[test]` って書くと、これがテスト関数だとRustに伝わるよ！
     #[test]
-    fn create_entities_gives_unique_ids() {
-        // EntityManager を作る
-        let manager = EntityManager::default();
-
-        // いくつかエンティティを作ってみる
-        let entity1 = manager.create_entity();
-        let entity2 = manager.create_entity();
-        let entity3 = manager.create_entity();
-
-        // IDがちゃんとユニーク（全部違う）になってるかチェック！
-        // `assert_ne!` は、2つの値が等しくないことを確認するマクロだよ。
-        // もし等しかったら、テストは失敗する！😱
+    fn allocate_gives_unique_sequential_ids() {
+        let mut allocator = EntityAllocator::default();
+
+        let entity1 = allocator.allocate();
+        let entity2 = allocator.allocate();
+        let entity3 = allocator.allocate();
+
         assert_ne!(entity1, entity2, "エンティティ1と2のIDが同じになっちゃった！😱");
-        assert_ne!(entity1, entity3, "エンティティ1と3のIDが同じになっちゃった！😱");
         assert_ne!(entity2, entity3, "エンティティ2と3のIDが同じになっちゃった！😱");
-
-        // IDがちゃんと0から連番になってるかも確認してみよう！
-        // `assert_eq!` は、2つの値が等しいことを確認するマクロだよ。
-        assert_eq!(entity1.0, 0, "最初のIDは0のはず！🤔");
-        assert_eq!(entity2.0, 1, "2番目のIDは1のはず！🤔");
-        assert_eq!(entity3.0, 2, "3番目のIDは2のはず！🤔");
+        assert_eq!(entity1, Entity::new(0, 0), "最初のIDは0のはず！🤔");
+        assert_eq!(entity3, Entity::new(2, 0), "3番目のIDは2のはず！🤔");
+        assert_eq!(allocator.len(), 3);
 
         println!("エンティティIDのユニーク性テスト、成功！🎉");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn freed_index_is_reused_with_a_new_generation() {
+        let mut allocator = EntityAllocator::default();
+        let old = allocator.allocate();
+        let other = allocator.allocate();
+
+        assert!(allocator.free(old));
+        assert!(!allocator.free(old), "2回目の free は失敗するはず");
+        let reused = allocator.allocate();
+
+        assert_eq!(reused, Entity::new(0, 1));
+        assert!(!allocator.is_alive(old), "古いハンドルは生きてない扱い！");
+        assert!(allocator.is_alive(reused));
+        assert_eq!(allocator.alive_at(0), Some(reused));
+        assert_eq!(allocator.iter().collect::<Vec<_>>(), vec![reused, other]);
+        println!("世代付きの ID 再利用テスト、成功！♻️");
+    }
+
+    #[test]
+    fn serializes_as_a_single_number() {
+        let entity = Entity::new(7, 2);
+        let json = serde_json::to_string(&entity).unwrap();
+        assert_eq!(json, ((2u64 << 32) | 7).to_string());
+        assert_eq!(serde_json::from_str::<Entity>(&json).unwrap(), entity);
+        // 世代 0 なら今までの番号だけの JSON と同じ
+        assert_eq!(serde_json::from_str::<Entity>("5").unwrap(), Entity::new(5, 0));
+        println!("Entity のシリアライズテスト、成功！📦");
+    }
+}
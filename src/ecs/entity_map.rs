@@ -0,0 +1,86 @@
+// src/ecs/entity_map.rs
+//! よその World (サーバーとか) のエンティティと、自分の World のエンティティの対応表だよ！🗺️
+//!
+//! サーバーが振った ID をそのまま自分の World に押し込むと、
+//! 自分で作ったエンティティと番号がぶつかったり、世代がズレたりして大事故になる。
+//! なので「サーバーの Entity ⇔ こっちの Entity」をこの表で変換して使うよ。
+
+use std::collections::HashMap;
+
+use crate::ecs::entity::Entity;
+use crate::ecs::resource::Resource;
+
+/// リモート (サーバー) の Entity とローカルの Entity の1対1の対応表。World のリソースとして置く。
+#[derive(Debug, Default)]
+pub struct EntityMap {
+    remote_to_local: HashMap<Entity, Entity>,
+    local_to_remote: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    /// 対応を登録する。どちらかが既に別の相手と対応してたら、その古い対応は消すよ。
+    pub fn insert(&mut self, remote: Entity, local: Entity) {
+        if let Some(old_local) = self.remote_to_local.insert(remote, local) {
+            self.local_to_remote.remove(&old_local);
+        }
+        if let Some(old_remote) = self.local_to_remote.insert(local, remote) {
+            if old_remote != remote {
+                self.remote_to_local.remove(&old_remote);
+            }
+        }
+    }
+
+    /// リモートの Entity に対応するローカルの Entity。
+    pub fn local(&self, remote: Entity) -> Option<Entity> {
+        self.remote_to_local.get(&remote).copied()
+    }
+
+    /// ローカルの Entity に対応するリモートの Entity。
+    pub fn remote(&self, local: Entity) -> Option<Entity> {
+        self.local_to_remote.get(&local).copied()
+    }
+
+    /// ローカルの Entity の対応を消す (エンティティを destroy した時とか)。消えたリモート側を返す。
+    pub fn remove_local(&mut self, local: Entity) -> Option<Entity> {
+        let remote = self.local_to_remote.remove(&local)?;
+        self.remote_to_local.remove(&remote);
+        Some(remote)
+    }
+
+    pub fn len(&self) -> usize {
+        self.local_to_remote.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.local_to_remote.is_empty()
+    }
+}
+
+impl Resource for EntityMap {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_stays_one_to_one() {
+        let mut map = EntityMap::default();
+        let remote = Entity::new(40, 3);
+        let first = Entity::new(0, 0);
+        let second = Entity::new(1, 0);
+
+        map.insert(remote, first);
+        assert_eq!(map.local(remote), Some(first));
+        assert_eq!(map.remote(first), Some(remote));
+
+        // 同じリモートを別のローカルに付け替えると、古いローカルの対応は消える
+        map.insert(remote, second);
+        assert_eq!(map.remote(first), None);
+        assert_eq!(map.local(remote), Some(second));
+        assert_eq!(map.len(), 1);
+
+        assert_eq!(map.remove_local(second), Some(remote));
+        assert!(map.is_empty());
+        println!("EntityMap の対応表テスト、成功！🗺️");
+    }
+}
@@ -0,0 +1,118 @@
+// src/ecs/event.rs
+//! システム同士でメッセージをやり取りするための「イベント」だよ！📨
+//!
+//! `Events<T>` は型ごとのイベント置き場 (World のリソース)。
+//! 送る側は `world.send_event(...)`、読む側は自分の `EventCursor<T>` で
+//! 「まだ読んでないイベント」だけを受け取るよ。
+//!
+//! 中身はダブルバッファになってて、`World::update_events` (= `Schedule::tick` の最初) のたびに
+//! 「今のフレーム」→「前のフレーム」→ 破棄 と流れていく。
+//! なので tick ごとに1回読むシステムなら、どのタイミングで送られたイベントも1回だけちゃんと届く👍
+
+use std::marker::PhantomData;
+
+use crate::ecs::resource::Resource;
+
+/// イベントとして送れる型の目印トレイト。
+pub trait Event: Send + Sync + 'static {}
+
+/// 型 `T` のイベントを溜めておくリソース。`World::add_event::<T>()` で用意する。
+pub struct Events<T: Event> {
+    /// 前のフレームに送られたイベント。
+    previous: Vec<T>,
+    /// このフレームに送られたイベント。
+    current: Vec<T>,
+    /// `previous[0]` の通し番号 (今までに送られたイベントの何個目か)。
+    previous_start: usize,
+}
+
+impl<T: Event> Default for Events<T> {
+    fn default() -> Self {
+        Self { previous: Vec::new(), current: Vec::new(), previous_start: 0 }
+    }
+}
+
+impl<T: Event> Events<T> {
+    /// イベントを送る。
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    /// 今までに送られたイベントの総数 (= 次に送られるイベントの通し番号)。
+    fn event_count(&self) -> usize {
+        self.previous_start + self.previous.len() + self.current.len()
+    }
+
+    /// バッファを1つ進める。前のフレームのイベントは捨てられるよ。
+    pub fn update(&mut self) {
+        self.previous_start += self.previous.len();
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// まだ残ってるイベントが1個も無いか。
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty() && self.current.is_empty()
+    }
+
+    /// 残ってるイベントを全部、古い順に見る (カーソルは進まない)。
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.previous.iter().chain(self.current.iter())
+    }
+}
+
+impl<T: Event> Resource for Events<T> {}
+
+/// 「どこまで読んだか」を覚えておく読み取り用のカーソル。システムのフィールドに持たせて使うよ。
+pub struct EventCursor<T: Event> {
+    /// 次に読むイベントの通し番号。
+    next: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Event> Default for EventCursor<T> {
+    fn default() -> Self {
+        Self { next: 0, _marker: PhantomData }
+    }
+}
+
+impl<T: Event> EventCursor<T> {
+    /// まだ読んでないイベントを古い順に返して、カーソルを最後まで進める。
+    /// (読む前に捨てられちゃったイベントは飛ばされるよ)
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T> + 'a {
+        let skip = self.next.saturating_sub(events.previous_start);
+        self.next = events.event_count();
+        events.iter().skip(skip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Ping(u32);
+    impl Event for Ping {}
+
+    #[test]
+    fn cursor_reads_each_event_once_and_old_events_expire() {
+        let mut events = Events::<Ping>::default();
+        let mut early = EventCursor::<Ping>::default();
+        let mut late = EventCursor::<Ping>::default();
+
+        events.send(Ping(1));
+        assert_eq!(early.read(&events).collect::<Vec<_>>(), vec![&Ping(1)]);
+
+        events.update();
+        events.send(Ping(2));
+        // 1回読んだものは二度と出てこない
+        assert_eq!(early.read(&events).collect::<Vec<_>>(), vec![&Ping(2)]);
+        // 読むのが遅くても、2回の update までは残ってる
+        assert_eq!(late.read(&events).collect::<Vec<_>>(), vec![&Ping(1), &Ping(2)]);
+
+        events.update();
+        events.update();
+        assert!(events.is_empty());
+        assert_eq!(early.read(&events).count(), 0);
+        println!("イベントのダブルバッファテスト、成功！📨");
+    }
+}
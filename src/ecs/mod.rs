@@ -1,13 +1,29 @@
 // src/ecs/mod.rs
 //! ECS (Entity-Component-System) core implementation.
 
+pub mod change_detection;
 pub mod component;
 pub mod entity;
+pub mod entity_map;
+pub mod event;
+pub mod query;
+pub mod resource;
+pub mod schedule;
+pub mod snapshot;
 pub mod system;
+pub mod time;
 pub mod world;
 
 // Re-export key types for easier use via `crate::ecs::X`
+pub use change_detection::ComponentTicks;
 pub use component::Component;
 pub use entity::Entity;
+pub use entity_map::EntityMap;
+pub use event::{Event, EventCursor, Events};
+pub use query::{With, Without};
+pub use resource::Resource;
+pub use schedule::{Schedule, Stage};
+pub use snapshot::{SerializableComponent, SnapshotDelta, WorldSnapshot};
 pub use system::System;
+pub use time::Time;
 pub use world::World; 
\ No newline at end of file
@@ -0,0 +1,664 @@
+// src/ecs/query.rs
+//! World から「このコンポーネントとこのコンポーネントを持ってるエンティティ全部！」を
+//! まとめて取り出すためのクエリ API だよ！🔍✨
+//!
+//! `get_all_entities_with_component` → `get_component` を何回も呼ぶ代わりに、
+//! `world.query::<(Entity, &Card, &StackInfo, Option<&Position>)>()` って書くだけで
+//! タプルがそのまま出てくる！
+//!
+//! - 読み取り専用: `World::query` / `World::query_filtered` (`&T`, `Option<&T>`, `Entity`)
+//! - 書き込みあり: `World::query_mut` / `World::query_filtered_mut` (上に加えて `&mut T`, `Option<&mut T>`)
+//! - フィルター: `With<T>` (T を持ってる) / `Without<T>` (T を持ってない)、タプルで AND になる
+//!
+//! 結果はいつも Entity の昇順で返ってくるよ (HashMap の順番に左右されないので、サーバーとクライアントで結果が揃う👍)。
+//!
+//! ストレージはクエリの最初に1回だけ借りて、必ず要る型 (`&T`, `&mut T`, `With<T>`) のうち
+//! 一番持ってるエンティティが少ないストレージから回すので、関係ないエンティティは見もしないよ。
+//!
+//! `&mut T` で取り出したコンポーネントは、取り出した時点で「書き換えられた」(changed) として記録されるよ。
+
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use crate::ecs::change_detection::ChangeTrackers;
+use crate::ecs::component::Component;
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+
+/// 読み取り専用クエリで取り出せる型だよ。`&T`, `Option<&T>`, `Entity` と、それらのタプル (8個まで)。
+pub trait QueryData {
+    /// 1エンティティ分の取り出し結果。
+    type Item<'w>;
+    /// クエリ中ずっと持っておく、コンポーネントストレージの借用 (クエリ開始時に1回だけ借りる)。
+    type State<'w>;
+
+    /// クエリ開始時に、必要なストレージを World から借りてくる。
+    fn init_state(world: &World) -> Self::State<'_>;
+
+    /// どのエンティティから探せばいいか (必ず要るストレージのうち一番小さいもの)。
+    fn candidates<'w>(state: &Self::State<'w>) -> Candidates<'w>;
+
+    /// `entity` から取り出す。必要なコンポーネントが足りなければ `None` (= そのエンティティはスキップ)。
+    fn fetch<'w>(state: &Self::State<'w>, entity: Entity) -> Option<Self::Item<'w>>;
+}
+
+/// 書き込みありのクエリで取り出せる型だよ。`QueryData` の型に加えて `&mut T`, `Option<&mut T>` も使える。
+///
+/// 同じコンポーネント型をタプルに2回書くと、同じデータへの可変参照が2つできちゃうので実行時にパニックするよ！🙅‍♀️
+pub trait QueryDataMut {
+    /// 1エンティティ分の取り出し結果。
+    type Item<'w>;
+    /// クエリ中ずっと持っておく、コンポーネントストレージの借用。
+    type State<'w>;
+
+    /// どのエンティティから探せばいいか。可変借用する前に決めるので、World から直接見る。
+    fn candidates(world: &World) -> Candidates<'_>;
+
+    /// 型 `type_id` のコンポーネントを `&mut` で取り出すか (StackInfo の索引を捨てるかどうかに使う)。
+    fn writes(type_id: TypeId) -> bool;
+
+    /// クエリ開始時に、必要なストレージを `StoreBorrows` から借りてくる。
+    /// `candidates` は取り出すかもしれないエンティティ (昇順)。`&mut T` はこの分だけバラしておく。
+    fn init_state<'w>(stores: &mut StoreBorrows<'w>, candidates: &[Entity]) -> Self::State<'w>;
+
+    /// `entity` が必要なコンポーネントを全部持ってるか。`take` の前にこれで確かめるので、
+    /// タプルの途中で足りなくて、前の方の `&mut T` だけ changed 扱いになっちゃう…みたいなことが起きないよ。
+    fn contains(state: &Self::State<'_>, entity: Entity) -> bool;
+
+    /// `entity` の分を取り出す。必要なコンポーネントが足りなければ `None`。
+    fn take<'w>(state: &mut Self::State<'w>, entity: Entity) -> Option<Self::Item<'w>>;
+}
+
+/// クエリの絞り込み条件だよ。`()` は条件なし、タプルは全部満たす (AND)。
+pub trait QueryFilter {
+    /// クエリ中ずっと持っておく、コンポーネントストレージの借用。
+    type State<'w>;
+
+    /// クエリ開始時に、必要なストレージを World から借りてくる。
+    fn init_state(world: &World) -> Self::State<'_>;
+
+    /// どのエンティティから探せばいいか (`With<T>` なら T を持ってるエンティティだけ)。
+    fn candidates<'w>(state: &Self::State<'w>) -> Candidates<'w>;
+
+    /// `entity` が条件を満たすなら `true`。
+    fn matches(state: &Self::State<'_>, entity: Entity) -> bool;
+}
+
+/// 「コンポーネント `T` を持ってるエンティティだけ」のフィルター。中身は取り出さないよ。
+pub struct With<T>(PhantomData<T>);
+
+/// 「コンポーネント `T` を持ってないエンティティだけ」のフィルター。
+pub struct Without<T>(PhantomData<T>);
+
+/// コンポーネントの型を隠して、「どのエンティティが持ってるか」だけ見せるためのトレイト。
+pub trait EntitySet {
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// 入ってるエンティティ全部 (順番はバラバラ)。
+    fn entities(&self) -> Vec<Entity>;
+}
+
+impl<T> EntitySet for HashMap<Entity, T> {
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn entities(&self) -> Vec<Entity> {
+        self.keys().copied().collect()
+    }
+}
+
+/// クエリで見るエンティティの候補。必ず要るストレージが分かれば、生きてるエンティティ全部じゃなくてそこだけ回すよ。
+#[derive(Clone, Copy)]
+pub enum Candidates<'w> {
+    /// 絞り込めない (生きてるエンティティ全部から探す)。
+    All,
+    /// 必ず要る型が登録されてないので、何も出てこない。
+    Nothing,
+    /// このストレージに入ってるエンティティだけ見ればいい。
+    Store(&'w dyn EntitySet),
+}
+
+impl<'w> Candidates<'w> {
+    /// 型 `T` のストレージ (登録されてなければ `Nothing`)。
+    fn of<T: Component>(storage: Option<&'w HashMap<Entity, T>>) -> Self {
+        match storage {
+            Some(storage) => Candidates::Store(storage),
+            None => Candidates::Nothing,
+        }
+    }
+
+    /// 両方の条件を満たすなら、どっちから探せばいいか (少ない方)。
+    pub fn narrower(self, other: Self) -> Self {
+        match (self, other) {
+            (Candidates::Nothing, _) | (_, Candidates::Nothing) => Candidates::Nothing,
+            (Candidates::All, other) => other,
+            (this, Candidates::All) => this,
+            (Candidates::Store(a), Candidates::Store(b)) => Candidates::Store(if b.len() < a.len() { b } else { a }),
+        }
+    }
+}
+
+/// World から型 `T` のストレージを借りる。登録されてなければ `None`。
+fn store<T: Component>(world: &World) -> Option<&HashMap<Entity, T>> {
+    world.storage::<T>()?.downcast_ref::<HashMap<Entity, T>>()
+}
+
+/// 可変クエリ中に、各コンポーネントストレージを1回ずつだけ貸し出す係だよ。
+/// World の `component_stores` をまとめて可変借用して、型ごとにバラして渡すことで、
+/// `unsafe` 無しで「Card は読むだけ、StackInfo は書き換える」みたいなことができる！
+/// ストレージと一緒に、その型の変更記録 (`ChangeTrackers`) も貸し出すよ。
+pub struct StoreBorrows<'w> {
+    stores: HashMap<TypeId, (&'w mut (dyn Any + Send + Sync), &'w mut ChangeTrackers)>,
+    /// もう貸し出した型。同じ型を2回借りようとしたらパニックさせる。
+    lent: HashSet<TypeId>,
+    /// `&mut T` で取り出したコンポーネントを changed として記録する時の tick。
+    change_tick: u64,
+}
+
+impl<'w> StoreBorrows<'w> {
+    pub(crate) fn new(
+        stores: HashMap<TypeId, (&'w mut (dyn Any + Send + Sync), &'w mut ChangeTrackers)>,
+        change_tick: u64,
+    ) -> Self {
+        Self { stores, lent: HashSet::new(), change_tick }
+    }
+
+    /// `T` のストレージ (`HashMap<Entity, T>`) と変更記録を借りる。登録されてない型なら `None`。
+    fn borrow<T: Component>(&mut self) -> Option<(&'w mut HashMap<Entity, T>, &'w mut ChangeTrackers)> {
+        let type_id = TypeId::of::<T>();
+        if !self.lent.insert(type_id) {
+            panic!(
+                "Component type {} appears more than once in a mutable query!",
+                std::any::type_name::<T>()
+            );
+        }
+        let (storage, trackers) = self.stores.remove(&type_id)?;
+        storage.downcast_mut::<HashMap<Entity, T>>().map(|storage| (storage, trackers))
+    }
+}
+
+/// `&mut T` / `Option<&mut T>` のクエリ中の状態。取り出すたびに changed を記録する。
+pub struct MutState<'w, T> {
+    /// 候補のエンティティの `&mut T` だけ先にバラしておいて、`take` で1個ずつ渡す。
+    components: HashMap<Entity, &'w mut T>,
+    trackers: Option<&'w mut ChangeTrackers>,
+    change_tick: u64,
+}
+
+impl<'w, T: Component> MutState<'w, T> {
+    fn new(stores: &mut StoreBorrows<'w>, candidates: &[Entity]) -> Self {
+        let change_tick = stores.change_tick;
+        match stores.borrow::<T>() {
+            Some((storage, trackers)) => Self {
+                // ストレージ全部じゃなくて、クエリで見るエンティティの分だけ
+                components: storage
+                    .iter_mut()
+                    .filter(|(entity, _)| candidates.binary_search(entity).is_ok())
+                    .map(|(entity, component)| (*entity, component))
+                    .collect(),
+                trackers: Some(trackers),
+                change_tick,
+            },
+            None => Self { components: HashMap::new(), trackers: None, change_tick },
+        }
+    }
+
+    fn take(&mut self, entity: Entity) -> Option<&'w mut T> {
+        let component = self.components.remove(&entity)?;
+        if let Some(trackers) = self.trackers.as_deref_mut() {
+            trackers.on_change(entity, self.change_tick);
+        }
+        Some(component)
+    }
+}
+
+// --- 単体の型の実装 ---
+
+impl QueryData for Entity {
+    type Item<'w> = Entity;
+    /// 借りるものは無いけど、ライフタイム `'w` をシグネチャに出しておく必要があるので PhantomData。
+    type State<'w> = PhantomData<&'w ()>;
+
+    fn init_state(_world: &World) -> Self::State<'_> {
+        PhantomData
+    }
+
+    fn candidates<'w>(_state: &Self::State<'w>) -> Candidates<'w> {
+        Candidates::All
+    }
+
+    // `where Self: 'w` は、'w がどこにも出てこないとトレイト側の宣言と形が合わなくなるので付けてるおまじない
+    fn fetch<'w>(_state: &Self::State<'w>, entity: Entity) -> Option<Entity>
+    where
+        Self: 'w,
+    {
+        Some(entity)
+    }
+}
+
+impl<T: Component> QueryData for &T {
+    type Item<'w> = &'w T;
+    type State<'w> = Option<&'w HashMap<Entity, T>>;
+
+    fn init_state(world: &World) -> Self::State<'_> {
+        store::<T>(world)
+    }
+
+    fn candidates<'w>(state: &Self::State<'w>) -> Candidates<'w> {
+        Candidates::of(*state)
+    }
+
+    fn fetch<'w>(state: &Self::State<'w>, entity: Entity) -> Option<&'w T> {
+        state.and_then(|storage| storage.get(&entity))
+    }
+}
+
+impl<T: Component> QueryData for Option<&T> {
+    type Item<'w> = Option<&'w T>;
+    type State<'w> = Option<&'w HashMap<Entity, T>>;
+
+    fn init_state(world: &World) -> Self::State<'_> {
+        store::<T>(world)
+    }
+
+    fn candidates<'w>(_state: &Self::State<'w>) -> Candidates<'w> {
+        Candidates::All
+    }
+
+    fn fetch<'w>(state: &Self::State<'w>, entity: Entity) -> Option<Option<&'w T>> {
+        Some(state.and_then(|storage| storage.get(&entity)))
+    }
+}
+
+impl QueryDataMut for Entity {
+    type Item<'w> = Entity;
+    /// 借りるものは無いけど、ライフタイム `'w` をシグネチャに出しておく必要があるので PhantomData。
+    type State<'w> = PhantomData<&'w ()>;
+
+    fn candidates(_world: &World) -> Candidates<'_> {
+        Candidates::All
+    }
+
+    fn writes(_type_id: TypeId) -> bool {
+        false
+    }
+
+    fn init_state<'w>(_stores: &mut StoreBorrows<'w>, _candidates: &[Entity]) -> Self::State<'w> {
+        PhantomData
+    }
+
+    fn contains(_state: &Self::State<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    fn take<'w>(_state: &mut Self::State<'w>, entity: Entity) -> Option<Self::Item<'w>> {
+        Some(entity)
+    }
+}
+
+impl<T: Component> QueryDataMut for &T {
+    type Item<'w> = &'w T;
+    type State<'w> = Option<&'w HashMap<Entity, T>>;
+
+    fn candidates(world: &World) -> Candidates<'_> {
+        Candidates::of(store::<T>(world))
+    }
+
+    fn writes(_type_id: TypeId) -> bool {
+        false
+    }
+
+    fn init_state<'w>(stores: &mut StoreBorrows<'w>, _candidates: &[Entity]) -> Self::State<'w> {
+        stores.borrow::<T>().map(|(storage, _)| &*storage)
+    }
+
+    fn contains(state: &Self::State<'_>, entity: Entity) -> bool {
+        state.is_some_and(|storage| storage.contains_key(&entity))
+    }
+
+    fn take<'w>(state: &mut Self::State<'w>, entity: Entity) -> Option<&'w T> {
+        state.and_then(|storage| storage.get(&entity))
+    }
+}
+
+impl<T: Component> QueryDataMut for Option<&T> {
+    type Item<'w> = Option<&'w T>;
+    type State<'w> = Option<&'w HashMap<Entity, T>>;
+
+    fn candidates(_world: &World) -> Candidates<'_> {
+        Candidates::All
+    }
+
+    fn writes(_type_id: TypeId) -> bool {
+        false
+    }
+
+    fn init_state<'w>(stores: &mut StoreBorrows<'w>, _candidates: &[Entity]) -> Self::State<'w> {
+        stores.borrow::<T>().map(|(storage, _)| &*storage)
+    }
+
+    fn contains(_state: &Self::State<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    fn take<'w>(state: &mut Self::State<'w>, entity: Entity) -> Option<Option<&'w T>> {
+        Some(state.and_then(|storage| storage.get(&entity)))
+    }
+}
+
+impl<T: Component> QueryDataMut for &mut T {
+    type Item<'w> = &'w mut T;
+    type State<'w> = MutState<'w, T>;
+
+    fn candidates(world: &World) -> Candidates<'_> {
+        Candidates::of(store::<T>(world))
+    }
+
+    fn writes(type_id: TypeId) -> bool {
+        type_id == TypeId::of::<T>()
+    }
+
+    fn init_state<'w>(stores: &mut StoreBorrows<'w>, candidates: &[Entity]) -> Self::State<'w> {
+        MutState::new(stores, candidates)
+    }
+
+    fn contains(state: &Self::State<'_>, entity: Entity) -> bool {
+        state.components.contains_key(&entity)
+    }
+
+    fn take<'w>(state: &mut Self::State<'w>, entity: Entity) -> Option<&'w mut T> {
+        state.take(entity)
+    }
+}
+
+impl<T: Component> QueryDataMut for Option<&mut T> {
+    type Item<'w> = Option<&'w mut T>;
+    type State<'w> = MutState<'w, T>;
+
+    fn candidates(_world: &World) -> Candidates<'_> {
+        Candidates::All
+    }
+
+    fn writes(type_id: TypeId) -> bool {
+        type_id == TypeId::of::<T>()
+    }
+
+    fn init_state<'w>(stores: &mut StoreBorrows<'w>, candidates: &[Entity]) -> Self::State<'w> {
+        MutState::new(stores, candidates)
+    }
+
+    fn contains(_state: &Self::State<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    fn take<'w>(state: &mut Self::State<'w>, entity: Entity) -> Option<Option<&'w mut T>> {
+        Some(state.take(entity))
+    }
+}
+
+impl QueryFilter for () {
+    type State<'w> = ();
+
+    fn init_state(_world: &World) {}
+
+    fn candidates<'w>(_state: &()) -> Candidates<'w> {
+        Candidates::All
+    }
+
+    fn matches(_state: &(), _entity: Entity) -> bool {
+        true
+    }
+}
+
+impl<T: Component> QueryFilter for With<T> {
+    type State<'w> = Option<&'w HashMap<Entity, T>>;
+
+    fn init_state(world: &World) -> Self::State<'_> {
+        store::<T>(world)
+    }
+
+    fn candidates<'w>(state: &Self::State<'w>) -> Candidates<'w> {
+        Candidates::of(*state)
+    }
+
+    fn matches(state: &Self::State<'_>, entity: Entity) -> bool {
+        state.is_some_and(|storage| storage.contains_key(&entity))
+    }
+}
+
+impl<T: Component> QueryFilter for Without<T> {
+    type State<'w> = Option<&'w HashMap<Entity, T>>;
+
+    fn init_state(world: &World) -> Self::State<'_> {
+        store::<T>(world)
+    }
+
+    fn candidates<'w>(_state: &Self::State<'w>) -> Candidates<'w> {
+        Candidates::All
+    }
+
+    fn matches(state: &Self::State<'_>, entity: Entity) -> bool {
+        !state.is_some_and(|storage| storage.contains_key(&entity))
+    }
+}
+
+// --- タプルの実装 (マクロで 1〜8 個分まとめて作る) ---
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type State<'w> = ($($name::State<'w>,)+);
+
+            fn init_state(world: &World) -> Self::State<'_> {
+                ($($name::init_state(world),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn candidates<'w>(state: &Self::State<'w>) -> Candidates<'w> {
+                let ($($name,)+) = state;
+                Candidates::All$(.narrower($name::candidates($name)))+
+            }
+
+            #[allow(non_snake_case)]
+            fn fetch<'w>(state: &Self::State<'w>, entity: Entity) -> Option<Self::Item<'w>> {
+                let ($($name,)+) = state;
+                Some(($($name::fetch($name, entity)?,)+))
+            }
+        }
+
+        impl<$($name: QueryDataMut),+> QueryDataMut for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type State<'w> = ($($name::State<'w>,)+);
+
+            fn candidates(world: &World) -> Candidates<'_> {
+                Candidates::All$(.narrower($name::candidates(world)))+
+            }
+
+            fn writes(type_id: TypeId) -> bool {
+                $($name::writes(type_id))||+
+            }
+
+            fn init_state<'w>(stores: &mut StoreBorrows<'w>, candidates: &[Entity]) -> Self::State<'w> {
+                ($($name::init_state(stores, candidates),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn contains(state: &Self::State<'_>, entity: Entity) -> bool {
+                let ($($name,)+) = state;
+                $($name::contains($name, entity))&&+
+            }
+
+            #[allow(non_snake_case)]
+            fn take<'w>(state: &mut Self::State<'w>, entity: Entity) -> Option<Self::Item<'w>> {
+                let ($($name,)+) = state;
+                Some(($($name::take($name, entity)?,)+))
+            }
+        }
+
+        impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            type State<'w> = ($($name::State<'w>,)+);
+
+            fn init_state(world: &World) -> Self::State<'_> {
+                ($($name::init_state(world),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn candidates<'w>(state: &Self::State<'w>) -> Candidates<'w> {
+                let ($($name,)+) = state;
+                Candidates::All$(.narrower($name::candidates($name)))+
+            }
+
+            #[allow(non_snake_case)]
+            fn matches(state: &Self::State<'_>, entity: Entity) -> bool {
+                let ($($name,)+) = state;
+                $($name::matches($name, entity))&&+
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+impl_query_tuple!(A, B, C, D, E, F, G);
+impl_query_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::card::{Card, Rank, Suit};
+    use crate::components::position::Position;
+    use crate::components::stack::{StackInfo, StackType};
+
+    /// カード3枚 (うち1枚は Position 無し) と、Card を持たないエンティティ1個の World を作る。
+    fn setup_world() -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        world.register_component::<Card>();
+        world.register_component::<StackInfo>();
+        world.register_component::<Position>();
+
+        let mut entities = Vec::new();
+        for (i, rank) in [Rank::Ace, Rank::Two, Rank::Three].into_iter().enumerate() {
+            let entity = world.create_entity();
+            world.add_component(entity, Card::new(Suit::Spade, rank));
+            world.add_component(entity, StackInfo::new(StackType::Tableau(0), i as u8));
+            if i != 1 {
+                world.add_component(entity, Position { x: i as f32, y: 0.0 });
+            }
+            entities.push(entity);
+        }
+        let not_a_card = world.create_entity();
+        world.add_component(not_a_card, StackInfo::new(StackType::Stock, 0));
+        entities.push(not_a_card);
+
+        (world, entities)
+    }
+
+    #[test]
+    fn query_yields_matching_tuples_in_entity_order() {
+        let (world, entities) = setup_world();
+
+        let found: Vec<(Entity, Rank, Option<f32>)> = world
+            .query::<(Entity, &Card, &StackInfo, Option<&Position>)>()
+            .map(|(entity, card, _, position)| (entity, card.rank, position.map(|p| p.x)))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (entities[0], Rank::Ace, Some(0.0)),
+                (entities[1], Rank::Two, None),
+                (entities[2], Rank::Three, Some(2.0)),
+            ]
+        );
+        println!("query のタプル取り出しテスト、成功！🎉");
+    }
+
+    #[test]
+    fn query_filtered_with_and_without() {
+        let (world, entities) = setup_world();
+
+        let with_position: Vec<Entity> = world.query_filtered::<Entity, (With<Card>, With<Position>)>().collect();
+        assert_eq!(with_position, vec![entities[0], entities[2]]);
+
+        let without_card: Vec<Entity> = world.query_filtered::<Entity, Without<Card>>().collect();
+        assert_eq!(without_card, vec![entities[3]]);
+        println!("With / Without フィルターのテスト、成功！🎉");
+    }
+
+    #[test]
+    fn query_mut_updates_components_in_place() {
+        let (mut world, entities) = setup_world();
+
+        for (card, stack_info) in world.query_mut::<(&Card, &mut StackInfo)>() {
+            if card.rank == Rank::Ace {
+                stack_info.stack_type = StackType::Foundation(3);
+            }
+        }
+        for (_, position) in world.query_filtered_mut::<(Entity, Option<&mut Position>), With<Card>>() {
+            if let Some(position) = position {
+                position.y = 42.0;
+            }
+        }
+
+        assert_eq!(world.get_component::<StackInfo>(entities[0]).unwrap().stack_type, StackType::Foundation(3));
+        assert_eq!(world.get_component::<StackInfo>(entities[2]).unwrap().stack_type, StackType::Tableau(0));
+        assert_eq!(world.get_component::<StackInfo>(entities[3]).unwrap().stack_type, StackType::Stock);
+        assert_eq!(world.get_component::<Position>(entities[2]).unwrap().y, 42.0);
+        println!("query_mut の書き換えテスト、成功！🎉");
+    }
+
+    #[test]
+    fn query_skips_unregistered_and_dead_entities() {
+        let (mut world, entities) = setup_world();
+        world.destroy_entity(entities[0]);
+
+        assert_eq!(world.query::<&Card>().count(), 2);
+        assert_eq!(world.query_mut::<&mut Card>().count(), 2);
+        // 登録されてない型を要求したら、何も出てこない (パニックしない)
+        assert_eq!(world.query::<(&Card, &crate::components::player::Player)>().count(), 0);
+        println!("死んだエンティティ・未登録の型のテスト、成功！🎉");
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn query_mut_rejects_duplicate_component_types() {
+        let (mut world, _) = setup_world();
+        let _ = world.query_mut::<(&mut Card, &Card)>().count();
+    }
+
+    #[test]
+    fn queries_start_from_the_smallest_required_store() {
+        let (world, _) = setup_world();
+        let len = |candidates: Candidates<'_>| match candidates {
+            Candidates::Store(store) => Some(store.len()),
+            Candidates::All | Candidates::Nothing => None,
+        };
+
+        // StackInfo は4個、Card は3個、Position は2個。必ず要るものの中で一番少ないのから回す
+        type Cards<'a> = (&'a StackInfo, &'a Card, Option<&'a Position>);
+        let state = <Cards as QueryData>::init_state(&world);
+        assert_eq!(len(<Cards as QueryData>::candidates(&state)), Some(3));
+        let filter = <With<Position> as QueryFilter>::init_state(&world);
+        assert_eq!(len(<Cards as QueryData>::candidates(&state).narrower(With::<Position>::candidates(&filter))), Some(2));
+        // 登録されてない型が要るなら、何も見ない
+        type WithPlayer<'a> = (Entity, &'a crate::components::player::Player);
+        let state = <WithPlayer as QueryData>::init_state(&world);
+        assert!(matches!(<WithPlayer as QueryData>::candidates(&state), Candidates::Nothing));
+
+        // StackInfo の索引を捨てるのは、StackInfo を &mut で取り出すクエリだけ
+        let stack_info = TypeId::of::<StackInfo>();
+        assert!(<(Entity, &mut StackInfo)>::writes(stack_info));
+        assert!(<Option<&mut StackInfo>>::writes(stack_info));
+        assert!(!<(&StackInfo, &mut Position)>::writes(stack_info));
+        println!("一番小さいストレージから回すテスト、成功！🎉");
+    }
+}
@@ -0,0 +1,21 @@
+// src/ecs/resource.rs
+
+// === このファイルは何？ 🤔 ===
+// ゲーム全体で「1個だけ」あればいいデータ (リソース、シングルトンとも言うよ) の目印になる
+// `Resource` トレイトを定義してるよ！🌏
+//
+// 例えば「今のゲーム状態 (GameState)」とか「自分のプレイヤーID (LocalPlayer)」とか。
+// こういうのをどこかのエンティティ (Entity(0) とか！) にくっつけると、
+// 「どのエンティティが持ってるんだっけ？」って魔法の数字を覚えておかなきゃいけなくなっちゃう😵
+// リソースなら型だけで `world.resource::<GameState>()` って取り出せるからスッキリ！✨
+
+use std::any::Any;
+
+/// Resource (リソース) トレイト ✨ World に1個だけ置けるデータの証！ ✨
+///
+/// `Component` と同じくマーカートレイトなので、中身は空っぽでOK！
+/// `impl Resource for GameState {}` って書けば `world.insert_resource(...)` で World に置けるよ。
+///
+/// 型ごとに1個だけしか置けないので、同じ型をもう一回 `insert_resource` すると上書きになるよ。
+/// `Send + Sync + 'static` が付いてる理由は `Component` と同じ (World ごとスレッド間で受け渡せるように)。
+pub trait Resource: Any + Send + Sync + 'static {}
@@ -0,0 +1,258 @@
+// src/ecs/schedule.rs
+//! 登録したシステムを、決まった順番で毎フレーム実行する「スケジュール」だよ！📅
+//!
+//! システムはステージ (`Stage`) ごとに登録して、
+//! Input → Logic → Network → Layout → RenderPrep の順に実行される。
+//! 同じステージの中では登録した順番。順番がいつも同じなので、同じ入力なら同じ結果になるよ。
+//!
+//! システムごとに「前回実行した時の tick」を覚えてて、実行前に World にセットするので、
+//! システムの中で `world.changed::<T>()` とかを呼ぶと「自分が前回動いてからの変化」が取れる！👀
+
+use crate::ecs::system::System;
+use crate::ecs::time::Time;
+use crate::ecs::world::World;
+
+/// システムを実行するタイミング (ステージ)。上から順に実行されるよ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    /// プレイヤーの入力を World に反映する。
+    Input,
+    /// ゲームのルール (カード移動、勝利判定など)。
+    Logic,
+    /// サーバーに送るものをまとめる。
+    Network,
+    /// カードの表示位置を計算する。
+    Layout,
+    /// 描画の直前の準備。
+    RenderPrep,
+}
+
+impl Stage {
+    /// 全ステージを実行順に並べたもの。
+    pub const ALL: [Stage; 5] = [Stage::Input, Stage::Logic, Stage::Network, Stage::Layout, Stage::RenderPrep];
+
+    /// ログ用の名前。
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Input => "input",
+            Stage::Logic => "logic",
+            Stage::Network => "network",
+            Stage::Layout => "layout",
+            Stage::RenderPrep => "render-prep",
+        }
+    }
+}
+
+/// 登録されたシステム1個分。名前はログやデバッグ用。
+struct ScheduledSystem {
+    name: &'static str,
+    system: Box<dyn System + Send>,
+    /// 前回実行した時の World の tick (まだ1回も動いてなければ 0 = 全部が「新しい」)。
+    last_run: u64,
+}
+
+/// ステージごとにシステムを持っておいて、`run` / `tick` でまとめて実行するよ。
+#[derive(Default)]
+pub struct Schedule {
+    /// `Stage::ALL` と同じ順番で、ステージごとのシステム一覧。
+    stages: [Vec<ScheduledSystem>; Stage::ALL.len()],
+    /// 前回と前々回に `tick` を始めた時の World の tick。消されたコンポーネントの記録をどこまで捨てていいかの目安。
+    last_frame_start: u64,
+    previous_frame_start: u64,
+}
+
+impl Schedule {
+    /// 空のスケジュールを作る。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `stage` の最後にシステムを追加する。メソッドチェーンできるよ。
+    pub fn add_system<S: System + Send + 'static>(&mut self, stage: Stage, system: S) -> &mut Self {
+        self.stages[stage as usize].push(ScheduledSystem {
+            name: std::any::type_name::<S>(),
+            system: Box::new(system),
+            last_run: 0,
+        });
+        self
+    }
+
+    /// 登録されてるシステムの名前を、実行順に返す (デバッグ用)。
+    pub fn system_names(&self) -> Vec<(Stage, &'static str)> {
+        Stage::ALL
+            .iter()
+            .flat_map(|&stage| self.stages[stage as usize].iter().map(move |s| (stage, s.name)))
+            .collect()
+    }
+
+    /// 全ステージのシステムを順番に1回ずつ実行する。
+    /// システムを1個動かすごとに World の tick を1つ進めるので、各システムは自分の書き換えを次回 `changed` で拾わないよ。
+    pub fn run(&mut self, world: &mut World) {
+        let outer_last_change_tick = world.last_change_tick();
+        for stage in Stage::ALL {
+            for scheduled in &mut self.stages[stage as usize] {
+                log::trace!("[Schedule] {} / {}", stage.name(), scheduled.name);
+                world.set_last_change_tick(scheduled.last_run);
+                scheduled.system.run(world);
+                scheduled.last_run = world.increment_change_tick();
+            }
+        }
+        // スケジュールの外から `changed` とかを呼ぶ人には影響が出ないように戻しておく
+        world.set_last_change_tick(outer_last_change_tick);
+    }
+
+    /// 1フレーム進める！`Time` リソースを `dt` 秒進めて (無ければ作って)、
+    /// イベントのバッファを1つ進めてから `run` する。
+    ///
+    /// 消されたコンポーネントの記録はイベントと同じで2フレーム分だけ残るよ。
+    /// 毎フレーム1回 `removed_since` で見に行けば取りこぼさない。
+    pub fn tick(&mut self, world: &mut World, dt: f64) {
+        let frame_start = world.increment_change_tick();
+        world.clear_removed_until(self.previous_frame_start);
+        self.previous_frame_start = self.last_frame_start;
+        self.last_frame_start = frame_start;
+
+        if !world.contains_resource::<Time>() {
+            world.insert_resource(Time::default());
+        }
+        if let Some(time) = world.resource_mut::<Time>() {
+            time.advance(dt);
+        }
+        world.update_events();
+        self.run(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::position::Position;
+    use crate::ecs::entity::Entity;
+    use crate::ecs::resource::Resource;
+
+    const FRAME: f64 = 1.0 / 60.0;
+
+    /// システムが実行された順番を記録するリソース。
+    #[derive(Default)]
+    struct CallLog(Vec<&'static str>);
+    impl Resource for CallLog {}
+
+    struct Record(&'static str);
+    impl System for Record {
+        fn run(&mut self, world: &mut World) {
+            world.resource_mut::<CallLog>().unwrap().0.push(self.0);
+        }
+    }
+
+    #[test]
+    fn systems_run_in_stage_then_registration_order() {
+        let mut world = World::new();
+        world.insert_resource(CallLog::default());
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Layout, Record("layout"))
+            .add_system(Stage::Input, Record("input-1"))
+            .add_system(Stage::Logic, Record("logic"))
+            .add_system(Stage::Input, Record("input-2"))
+            .add_system(Stage::RenderPrep, Record("render-prep"));
+
+        schedule.run(&mut world);
+
+        assert_eq!(
+            world.resource::<CallLog>().unwrap().0,
+            vec!["input-1", "input-2", "logic", "layout", "render-prep"]
+        );
+        assert_eq!(schedule.system_names().len(), 5);
+        println!("ステージ順の実行テスト、成功！🎉");
+    }
+
+    #[test]
+    fn tick_advances_time_deterministically() {
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+
+        schedule.tick(&mut world, 0.5);
+        schedule.tick(&mut world, 0.25);
+        schedule.tick(&mut world, -1.0); // 巻き戻りは 0 秒扱い
+
+        let time = world.resource::<Time>().unwrap();
+        assert_eq!(time.frame, 3);
+        assert_eq!(time.delta_seconds, 0.0);
+        assert_eq!(time.elapsed_seconds, 0.75);
+        println!("tick の時間管理テスト、成功！⏱️");
+    }
+
+    /// 毎回 `changed::<Position>()` の結果を記録するシステム。
+    #[derive(Default)]
+    struct SeenChanges(Vec<Vec<Entity>>);
+    impl Resource for SeenChanges {}
+
+    struct WatchPositions;
+    impl System for WatchPositions {
+        fn run(&mut self, world: &mut World) {
+            let changed = world.changed::<Position>();
+            world.resource_mut::<SeenChanges>().unwrap().0.push(changed);
+        }
+    }
+
+    /// 1回目だけ全部の Position を動かすシステム。2回目以降に自分の書き換えが見えないかも確認する。
+    struct NudgeOnce {
+        runs: u32,
+    }
+    impl System for NudgeOnce {
+        fn run(&mut self, world: &mut World) {
+            self.runs += 1;
+            match self.runs {
+                1 => {
+                    for position in world.query_mut::<&mut Position>() {
+                        position.x += 1.0;
+                    }
+                }
+                2 => assert!(world.changed::<Position>().is_empty(), "自分の書き換えは見えないはず"),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn systems_see_changes_since_their_last_run() {
+        let mut world = World::new();
+        world.register_component::<Position>();
+        world.insert_resource(SeenChanges::default());
+        let card = world.create_entity();
+        world.add_component(card, Position { x: 0.0, y: 0.0 });
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Logic, NudgeOnce { runs: 0 })
+            .add_system(Stage::RenderPrep, WatchPositions);
+
+        schedule.run(&mut world); // 追加 + NudgeOnce の書き換え
+        schedule.run(&mut world); // 何も変わってない
+        world.get_component_mut::<Position>(card).unwrap().y = 5.0; // スケジュールの外で書き換え
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<SeenChanges>().unwrap().0, vec![vec![card], vec![], vec![card]]);
+        println!("システムごとの変更検知テスト、成功！👀");
+    }
+
+    #[test]
+    fn removals_are_kept_for_two_frames() {
+        let mut world = World::new();
+        world.register_component::<Position>();
+        let card = world.create_entity();
+        world.add_component(card, Position { x: 0.0, y: 0.0 });
+        let mut schedule = Schedule::new();
+
+        let since = world.increment_change_tick();
+        world.destroy_entity(card);
+        schedule.tick(&mut world, FRAME);
+        assert_eq!(world.removed_since::<Position>(since), vec![card]);
+        schedule.tick(&mut world, FRAME);
+        assert_eq!(world.removed_since::<Position>(since), vec![card]);
+        schedule.tick(&mut world, FRAME);
+        assert!(world.removed_since::<Position>(since).is_empty());
+        println!("削除の記録の掃除テスト、成功！🧹");
+    }
+}
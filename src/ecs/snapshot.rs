@@ -0,0 +1,123 @@
+// src/ecs/snapshot.rs
+//! World の中身を丸ごと写したスナップショットだよ！📸 セーブデータ、サーバーとの同期、巻き戻しに使う。
+//!
+//! 入るのは `World::register_serializable_component` で登録したコンポーネント (`SerializableComponent`) だけ。
+//! ドラッグ中の状態みたいに手元だけのものは、普通の `register_component` で登録しておけば入らないよ。
+//! 中身は「エンティティ → (コンポーネントの名前 → JSON の値)」なので、そのまま serde で送ったり保存したりできる。
+//!
+//! - `World::snapshot` で撮って、`World::restore` で同じ World に戻す (エンティティもそのまま戻る)
+//! - よその World (サーバー、セーブデータ) のスナップショットは `World::restore_mapped` で、
+//!   `EntityMap` でこっちのエンティティに付け替えながら戻す
+//! - 2つのスナップショットの違い (`WorldSnapshot::diff` → `SnapshotDelta`) だけ送って、
+//!   `World::apply_delta_mapped` で重ねることもできる (サーバーの差分配信)
+//!
+//! コンポーネントの中に入ってる `Entity` までは付け替えないので、エンティティを指すコンポーネントは登録しないでね。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ecs::component::Component;
+use crate::ecs::entity::Entity;
+
+/// スナップショットに入れられるコンポーネント。
+pub trait SerializableComponent: Component + Serialize + DeserializeOwned {
+    /// スナップショットの中での名前。クライアントとサーバー、古いセーブデータで揃ってないといけないので、変えないでね。
+    const NAME: &'static str;
+}
+
+/// World のスナップショット。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// 登録されたコンポーネントを1つ以上持ってるエンティティ (エンティティの昇順)。
+    pub entities: Vec<EntitySnapshot>,
+}
+
+/// 2つのスナップショットの違い。古い方に重ねる (`WorldSnapshot::apply` / `World::apply_delta_mapped`) と新しい方になる。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    /// 新しく出てきたか、どれかのコンポーネントが変わったエンティティ。変わってないコンポーネントも全部入ってるよ。
+    pub changed: WorldSnapshot,
+    /// 無くなったエンティティ (昇順)。
+    pub removed: Vec<Entity>,
+}
+
+impl SnapshotDelta {
+    /// 何も変わってないか。
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// エンティティ1個分。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    pub entity: Entity,
+    /// `SerializableComponent::NAME` → 値。
+    pub components: BTreeMap<String, Value>,
+}
+
+impl WorldSnapshot {
+    /// エンティティの数。
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// `entity` の `T` (無いか、読めなければ `None`)。
+    pub fn get<T: SerializableComponent>(&self, entity: Entity) -> Option<T> {
+        let index = self.entities.binary_search_by_key(&entity, |saved| saved.entity).ok()?;
+        let value = self.entities[index].components.get(T::NAME)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// `T` を持ってるエンティティの数。
+    pub fn count<T: SerializableComponent>(&self) -> usize {
+        self.entities.iter().filter(|saved| saved.components.contains_key(T::NAME)).count()
+    }
+
+    /// `self` → `newer` の違い。
+    pub fn diff(&self, newer: &WorldSnapshot) -> SnapshotDelta {
+        let old: HashMap<Entity, &EntitySnapshot> = self.entities.iter().map(|saved| (saved.entity, saved)).collect();
+        let changed = newer
+            .entities
+            .iter()
+            .filter(|saved| old.get(&saved.entity).is_none_or(|before| before.components != saved.components))
+            .cloned()
+            .collect();
+        let kept: HashSet<Entity> = newer.entities.iter().map(|saved| saved.entity).collect();
+        let removed = self.entities.iter().map(|saved| saved.entity).filter(|entity| !kept.contains(entity)).collect();
+        SnapshotDelta { changed: WorldSnapshot { entities: changed }, removed }
+    }
+
+    /// `diff` で作った違いを重ねる (`World` の外でスナップショットを最新にしておきたい時用)。
+    pub fn apply(&mut self, delta: &SnapshotDelta) {
+        self.entities.retain(|saved| !delta.removed.contains(&saved.entity));
+        for changed in &delta.changed.entities {
+            match self.entities.binary_search_by_key(&changed.entity, |saved| saved.entity) {
+                Ok(index) => self.entities[index] = changed.clone(),
+                Err(index) => self.entities.insert(index, changed.clone()),
+            }
+        }
+    }
+
+    /// `entity` に `T` を入れる (World を作らずにスナップショットを組み立てる時用。テストとか)。
+    pub fn insert<T: SerializableComponent>(&mut self, entity: Entity, component: &T) -> Result<(), String> {
+        let value = serde_json::to_value(component)
+            .map_err(|e| format!("Failed to serialize {} for {:?}: {}", T::NAME, entity, e))?;
+        let index = match self.entities.binary_search_by_key(&entity, |saved| saved.entity) {
+            Ok(index) => index,
+            Err(index) => {
+                self.entities.insert(index, EntitySnapshot { entity, components: BTreeMap::new() });
+                index
+            }
+        };
+        self.entities[index].components.insert(T::NAME.to_string(), value);
+        Ok(())
+    }
+}
@@ -23,7 +23,7 @@ pub trait System {
     ///
     /// # 引数
     /// - `world`: ゲーム世界のデータ（エンティティとコンポーネント）を保持する World への可変参照。
-    ///           これを使って、必要なコンポーネントを取得したり、変更したりするよ。
+    ///   これを使って、必要なコンポーネントを取得したり、変更したりするよ。
     ///
     /// ここに具体的なゲームロジックを実装していくことになるんだ。ワクワクするね！🤩
     fn run(&mut self, world: &mut World);
@@ -0,0 +1,31 @@
+// src/ecs/time.rs
+//! フレームの時間を持つリソース `Time` だよ！⏱️
+//!
+//! `Schedule::tick(world, dt)` が毎回更新するので、システムは
+//! `world.resource::<Time>()` で「前のフレームから何秒経ったか」を知れる。
+//! 時計 (Date.now() とか) を直接見ないので、テストでは好きな dt を渡して決定的に動かせるよ👍
+
+use crate::ecs::resource::Resource;
+
+/// 経過時間のリソース。単位は全部「秒」。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Time {
+    /// 直前の tick からの経過時間。
+    pub delta_seconds: f64,
+    /// 最初の tick からの合計経過時間。
+    pub elapsed_seconds: f64,
+    /// 何回 tick したか。
+    pub frame: u64,
+}
+
+impl Time {
+    /// `dt` 秒だけ時間を進める。マイナスや NaN が来たら 0 秒扱いにするよ (時計が巻き戻った時の保険)。
+    pub fn advance(&mut self, dt: f64) {
+        let dt = if dt.is_finite() && dt > 0.0 { dt } else { 0.0 };
+        self.delta_seconds = dt;
+        self.elapsed_seconds += dt;
+        self.frame += 1;
+    }
+}
+
+impl Resource for Time {}
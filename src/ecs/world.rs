@@ -5,16 +5,28 @@
 // TypeId: プログラム実行中に、それぞれの型にユニークなIDを割り当てるためのもの。コンポーネントの種類を区別するキーとして使う。
 use std::any::{Any, TypeId};
 // HashMap: キーと値のペアを高速に格納・検索できるデータ構造。TypeId をキーにして、その型のコンポーネントストレージ (Box<dyn Any> でラップ) を値として保持するのに使う。
-use std::collections::HashMap;
-// HashSet: 重複しない要素を格納するデータ構造。現在生存しているエンティティIDを管理するのに使う。
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+// OnceLock: 一度だけ作って使い回すキャッシュ。Stacks 索引に使う (&self からでも作れて、Sync なのがポイント)
+use std::sync::OnceLock;
 
 // === このクレート (プロジェクト) 内の他のモジュールからのインポート ===
 // Entity: エンティティを表す単純な構造体 (通常はIDをラップしたもの)。
-use crate::ecs::entity::Entity;
+use crate::ecs::entity::{Entity, EntityAllocator};
 // Component: 全てのコンポーネントが実装すべきマーカートレイト (中身は空でもOK)。ジェネリクスでコンポーネント型を制約するのに使う。
 use crate::ecs::component::Component;
-use crate::components::stack::{StackInfo, StackType};
+// Resource: World に型ごとに1個だけ置けるデータ (GameState とか) の目印トレイト。
+use crate::ecs::resource::Resource;
+// Event / Events: システム同士のメッセージ (中身は ecs/event.rs)
+use crate::ecs::event::{Event, Events};
+use crate::components::stack::{StackInfo, StackType, Stacks};
+// クエリ API の型たち (中身は ecs/query.rs)
+use crate::ecs::query::{Candidates, QueryData, QueryDataMut, QueryFilter, StoreBorrows};
+// 変更検知 (いつ追加/書き換え/削除されたか) の記録係 (中身は ecs/change_detection.rs)
+use crate::ecs::change_detection::{ChangeTrackers, ComponentTicks};
+// スナップショット (中身は ecs/snapshot.rs)
+use crate::ecs::entity_map::EntityMap;
+use crate::ecs::snapshot::{EntitySnapshot, SerializableComponent, SnapshotDelta, WorldSnapshot};
+use serde_json::Value;
 
 /// コンポーネントストレージとその操作をまとめた内部的な構造体だよ！✨
 /// これを使うことで、`World` の `component_stores` で型情報を隠蔽しつつも、
@@ -24,80 +36,90 @@ struct ComponentStoreEntry {
     /// `Box<dyn Any>` で型情報を隠蔽 (型消去) してるんだ。これにより、
     /// いろんな型の `HashMap<Entity, T>` を一つの `HashMap` (`component_stores`) で
     /// まとめて管理できる！マジ便利！💖
-    storage: Box<dyn Any>,
+    /// (`Component` は `Send + Sync` 必須なので、`World` ごとスレッド間で受け渡しできるように `Send + Sync` も付けてるよ)
+    storage: Box<dyn Any + Send + Sync>,
 
     /// 指定されたエンティティに対応するコンポーネントを `storage` から削除するための関数ポインタ。🧹
     /// `storage` (Box<dyn Any>) と削除対象の `entity` を引数に取るよ。
     /// この関数ポインタがあるおかげで、`destroy_entity` の中で `storage` の具体的な型 (`T`) を
     /// 知らなくても、型ごとに最適化された削除処理を呼び出せるんだ！天才的アイディア！💡
-    /// `fn(&mut Box<dyn Any>, Entity)` っていう型は、「`Box<dyn Any>` の可変参照と `Entity` を受け取って、何も返さない関数」って意味だよ！
-    remover: fn(&mut Box<dyn Any>, Entity),
-    // TODO: 将来的には、コンポーネントのシリアライズ/デシリアライズ関数とか、
-    //       他の型ごとの操作関数もここに追加できるかもね！🤔
+    /// `fn(&mut Box<dyn Any + Send + Sync>, Entity)` っていう型は、「`Box<dyn Any>` の可変参照と `Entity` を受け取って、何も返さない関数」って意味だよ！
+    remover: fn(&mut Box<dyn Any + Send + Sync>, Entity),
+    /// この型のコンポーネントがいつ追加/書き換え/削除されたかの記録。型 `T` を知らなくていいので、ここに一緒に置いとく！👀
+    trackers: ChangeTrackers,
+    /// `register_serializable_component` で登録された型なら、スナップショット用の読み書き関数。📸
+    serde: Option<ComponentSerde>,
 }
 
+/// スナップショットに入る型の、名前と読み書きの関数ポインタ (`remover` と同じく、型 `T` を知らずに呼べる)。
+struct ComponentSerde {
+    /// `SerializableComponent::NAME`。
+    name: &'static str,
+    /// ストレージの中身を全部 JSON にする。
+    save: fn(&(dyn Any + Send + Sync)) -> Vec<(Entity, Value)>,
+    /// JSON を1個読む。まだ World には入れないので、途中で失敗しても World は壊れない。
+    decode: fn(&Value) -> Result<Box<dyn Any + Send>, String>,
+    /// `decode` したものをエンティティに付ける (`add_component` 経由なので変更検知も効く)。
+    insert: fn(&mut World, Entity, Box<dyn Any + Send>),
+}
+
+/// エンティティ1個分の、`decode` 済みでまだ World に入れてないコンポーネント。
+type DecodedComponents = Vec<(TypeId, Box<dyn Any + Send>)>;
+
 /// ゲーム世界の全てのエンティティとコンポーネントを管理する中心的な構造体 (自作ECSのコア！)。
 /// エンティティの生存管理、コンポーネントの型ごとの保存とアクセス機能を提供するよ。
 pub struct World {
-    /// 現在生存しているエンティティIDのセット。エンティティが存在するかどうかを高速にチェックできる。
-    entities: HashSet<Entity>,
-    /// 次に生成するエンティティに割り当てるID。エンティティが作成されるたびにインクリメントされる。
-    next_entity_id: usize,
+    /// エンティティIDの発行係。生きてるかどうかの管理と、消された番号の再利用 (世代付き) をやってくれる。
+    entities: EntityAllocator,
     /// コンポーネントの種類 (TypeId) ごとに、その型のコンポーネントデータを格納するストレージと操作をまとめたもの。
     /// `TypeId` をキーとし、`ComponentStoreEntry` を値として持つ HashMap。
     /// これにより、型安全なコンポーネント削除とかが可能になる！✨
     component_stores: HashMap<TypeId, ComponentStoreEntry>,
-    // 削除済みエンティティIDを再利用するためのリスト (今は使わないけど、将来的にメモリ効率↑のために使えるかも)
-    // free_list: Vec<usize>,
+    /// StackInfo から作ったスタックごとの索引 (キャッシュ)。空なら次に `stacks()` が呼ばれた時に作り直す。
+    /// StackInfo が書き換わるかもしれない操作 (`get_component_mut::<StackInfo>` など) をするたびに空にするよ。
+    stacks: OnceLock<Stacks>,
+    /// リソース (型ごとに1個だけのグローバルなデータ) の置き場。`TypeId` → 中身 (`Box<dyn Any>` で型消去)。
+    resources: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    /// `add_event` で登録されたイベント型ごとの「バッファを1つ進める」関数。`update_events` で全部呼ぶよ。
+    event_updaters: Vec<fn(&mut World)>,
+    /// 今の tick。コンポーネントの追加や書き換えはこの値で記録されるよ。`increment_change_tick` で進む。
+    /// (u64 なので、毎フレーム何回進めても溢れる心配はしなくてOK)
+    change_tick: u64,
+    /// `added` / `changed` / `removed` の基準になる tick。`Schedule` がシステムを実行する直前に、
+    /// そのシステムが前回実行された時の tick をセットしてくれる。
+    last_change_tick: u64,
 }
 
 impl World {
     /// 新しい空の World を作成するコンストラクタ。
-    /// 各フィールドを初期状態 (空の HashSet, ID カウンタ 0, 空の HashMap) に設定する。
+    /// 各フィールドを初期状態 (エンティティ無し, 空の HashMap) に設定する。
     pub fn new() -> Self {
         World {
-            entities: HashSet::new(),
-            next_entity_id: 0,
+            entities: EntityAllocator::default(),
             component_stores: HashMap::new(),
-            // free_list: Vec::new(),
+            stacks: OnceLock::new(),
+            resources: HashMap::new(),
+            event_updaters: Vec::new(),
+            // 0 を「まだ何も見てない」にしたいので 1 から始める
+            change_tick: 1,
+            last_change_tick: 0,
         }
     }
 
     /// 新しいエンティティを生成し、その Entity を返す。
-    /// `next_entity_id` をインクリメントして、ユニークなIDを保証する。
-    /// 生成されたエンティティIDは `entities` セットにも追加される。
+    /// 前に消されたエンティティの番号が空いてれば、世代を1つ上げてそれを使い回すよ♻️
     ///
     /// # 戻り値
     /// 新しく作成された `Entity`。
     pub fn create_entity(&mut self) -> Entity {
-        let entity_id = self.next_entity_id;
-        self.next_entity_id += 1;
-        let entity = Entity(entity_id);
-        // 新しく作った Entity を生存リストに追加
-        self.entities.insert(entity);
+        let entity = self.entities.allocate();
         // log(&format!("World: Created entity with ID {}", entity_id)); // logマクロは wasm_bindgen 経由じゃないと使えないのでコメントアウト
-        println!("World: Created entity with ID {}", entity_id); // 標準出力で代替 (デバッグ用)
+        println!("World: Created entity {:?}", entity); // 標準出力で代替 (デバッグ用)
         entity
     }
 
-    /// 指定されたIDで新しいエンティティを作成する。
-    /// サーバーから受け取った状態を再現する場合などに使うことを想定。
-    /// **注意:** 既に存在するIDを指定した場合、既存のエンティティは上書きされず、
-    ///       単に `entities` セットに追加されるだけ (セットなので重複はしない)。
-    ///       ID の衝突管理はこのメソッドの責務外。呼び出し側で注意が必要。
-    ///
-    /// # 引数
-    /// * `entity` - 作成したいエンティティの `Entity` (IDを含む)。
-    pub fn create_entity_with_id(&mut self, entity: Entity) {
-        // 指定された Entity を生存リストに追加
-        self.entities.insert(entity);
-        // next_entity_id を必要なら更新 (指定IDが現在値以上なら、次のIDが重複しないように)
-        // entity.0 は Entity 構造体のタプル要素 (pub usize) にアクセスする方法
-        self.next_entity_id = self.next_entity_id.max(entity.0 + 1);
-        println!("World: Created entity with specific ID {}", entity.0); // 標準出力
-    }
-
     /// 指定されたエンティティが存在するかどうかを確認する。
+    /// 番号が同じでも世代が違う (= もう消されたエンティティを指してる) ハンドルは `false` だよ。
     ///
     /// # 引数
     /// * `entity` - 存在を確認したいエンティティ。
@@ -105,7 +127,13 @@ impl World {
     /// # 戻り値
     /// エンティティが存在すれば `true`、しなければ `false`。
     pub fn is_entity_alive(&self, entity: Entity) -> bool {
-        self.entities.contains(&entity)
+        self.entities.is_alive(entity)
+    }
+
+    /// 番号だけ分かってる時に、その番号で今生きてるエンティティを返す。
+    /// (JS には番号だけ渡してるので、JS から戻ってきた番号を Entity に戻すのに使うよ)
+    pub fn entity_from_index(&self, index: u32) -> Option<Entity> {
+        self.entities.alive_at(index)
     }
 
     /// 指定されたエンティティを削除 (破棄) する。 ✨超重要メソッド！✨
@@ -119,14 +147,16 @@ impl World {
     /// エンティティが存在しなかった場合は `false`。
     pub fn destroy_entity(&mut self, entity: Entity) -> bool {
         // まず、エンティティが生存リストにいるか確認。いなければ何もせず false を返す。
-        if self.entities.remove(&entity) {
-            println!("World: Destroying entity with ID {}", entity.0); // 標準出力
+        if self.entities.free(entity) {
+            println!("World: Destroying entity {:?}", entity); // 標準出力
 
             // よっしゃ！エンティティは生存リストから消した！👍
             // 次は、このエンティティにくっついてたコンポーネントたちを全種類お掃除する番だ！🧹💨
 
             // `component_stores` (型ごとの倉庫&お掃除係のマップ) の中身を全部見て回るよ！
             // `values_mut()` を使うと、各倉庫 (`ComponentStoreEntry`) の中身を書き換えられる可変参照が手に入る！🔥
+            self.invalidate_stacks(); // StackInfo を持ってたかもしれないので索引は作り直し
+            let tick = self.change_tick;
             for entry in self.component_stores.values_mut() {
                 // 各 `ComponentStoreEntry` には、お掃除専用の関数 `remover` が登録されてる！✨
                 // この `remover` 関数に、実際のデータ倉庫 (`entry.storage` の可変参照) と
@@ -134,15 +164,14 @@ impl World {
                 // これで、`destroy_entity` 関数自体は `storage` の中身の具体的な型を知らなくても、
                 // 型ごとに最適化された削除処理を安全に呼び出せるんだ！マジ天才！😎💖
                 (entry.remover)(&mut entry.storage, entity);
+                // 持ってた型だけ「削除された」として記録される
+                entry.trackers.on_remove(entity, tick);
             }
 
-            // TODO: 将来的には、ここで free_list に entity.0 を追加してID再利用を実装できるかも
-            // self.free_list.push(entity.0);
-
             true // 削除成功！✨
         } else {
             // 指定されたエンティティは元々存在しなかったみたい…🤔
-            println!("World: Attempted to destroy non-existent entity with ID {}", entity.0);
+            println!("World: Attempted to destroy non-existent entity {:?}", entity);
             false // 削除失敗 (というか対象がいなかった)
         }
     }
@@ -154,13 +183,14 @@ impl World {
     ///
     /// # 型パラメータ
     /// * `T` - 登録したいコンポーネントの型。`Component` トレイトと `Any` トレイトを実装し、
-    ///         `'static` ライフタイムを持つ必要がある (`'static` はデータがプログラム終了まで生存可能という意味)。
+    ///   `'static` ライフタイムを持つ必要がある (`'static` はデータがプログラム終了まで生存可能という意味)。
     ///
     /// # パニック
     /// すでに同じ型のコンポーネントが登録されている場合にパニックする可能性がある (HashMap::insert の仕様による)。
     /// 通常はゲーム初期化時に一度だけ呼ぶ。
     pub fn register_component<T: Component + Any + 'static>(&mut self) {
         let type_id = TypeId::of::<T>();
+        self.invalidate_stacks_if::<T>();
         println!("World: Registering component type {:?} ({})", type_id, std::any::type_name::<T>()); // 型名もログに出す！
 
         // 型ごとの削除処理を行うための関数を定義するよ！✨
@@ -168,7 +198,7 @@ impl World {
         // 引数として `Box<dyn Any>` の可変参照と `Entity` を取る。
         // 関数の中では、`downcast_mut` を使って `Box<dyn Any>` を安全に `HashMap<Entity, T>` に変換して、
         // `remove` メソッドを呼び出す！👍
-        let remover_fn: fn(&mut Box<dyn Any>, Entity) = |storage_any, entity| {
+        let remover_fn: fn(&mut Box<dyn Any + Send + Sync>, Entity) = |storage_any, entity| {
             // storage_any (Box<dyn Any>) を HashMap<Entity, T> にダウンキャスト試行！
             if let Some(storage) = storage_any.downcast_mut::<HashMap<Entity, T>>() {
                 // 成功したら、HashMap から entity をキーにしてコンポーネントを削除！🧹
@@ -196,6 +226,8 @@ impl World {
         let entry = ComponentStoreEntry {
             storage: Box::new(new_storage), // HashMap を Box に入れて Any で型消去！
             remover: remover_fn,           // 型 T 専用のお掃除関数ポインタ！🧹
+            trackers: ChangeTrackers::default(),
+            serde: None,
         };
 
         // `component_stores` に、この型の `TypeId` をキーとして、作成した `ComponentStoreEntry` を挿入！
@@ -212,6 +244,48 @@ impl World {
         }
     }
 
+    /// `register_component` と同じだけど、この型は `snapshot` / `restore` にも入るようになる (オプトイン)。📸
+    /// 名前 (`T::NAME`) が他の型とかぶってたらパニック (スナップショットが読めなくなっちゃうので)。
+    pub fn register_serializable_component<T: SerializableComponent>(&mut self) {
+        let taken = self
+            .component_stores
+            .iter()
+            .any(|(type_id, entry)| *type_id != TypeId::of::<T>() && entry.serde.as_ref().is_some_and(|serde| serde.name == T::NAME));
+        if taken {
+            panic!("World: Snapshot name {:?} ({}) is already used by another component!", T::NAME, std::any::type_name::<T>());
+        }
+        self.register_component::<T>();
+
+        let serde = ComponentSerde {
+            name: T::NAME,
+            save: |storage_any| {
+                let storage = storage_any
+                    .downcast_ref::<HashMap<Entity, T>>()
+                    .expect("component storage should match its serializer");
+                storage
+                    .iter()
+                    .map(|(&entity, component)| {
+                        let value = serde_json::to_value(component)
+                            .unwrap_or_else(|e| panic!("Failed to serialize {} for {:?}: {}", T::NAME, entity, e));
+                        (entity, value)
+                    })
+                    .collect()
+            },
+            decode: |value| {
+                serde_json::from_value::<T>(value.clone())
+                    .map(|component| Box::new(component) as Box<dyn Any + Send>)
+                    .map_err(|e| format!("Failed to read {}: {}", T::NAME, e))
+            },
+            insert: |world, entity, component| {
+                let component = component.downcast::<T>().expect("decoded component should match its type");
+                world.add_component(entity, *component);
+            },
+        };
+        if let Some(entry) = self.component_stores.get_mut(&TypeId::of::<T>()) {
+            entry.serde = Some(serde);
+        }
+    }
+
     /// 指定されたエンティティにコンポーネントを追加する。
     /// もしエンティティが生存していなければ、コンポーネントは追加されない (エラーにはならず、単に無視)。
     /// もし指定された型のコンポーネントストレージが存在しなければ (register_component 忘れ)、パニックする。
@@ -232,6 +306,7 @@ impl World {
         }
 
         let type_id = TypeId::of::<T>();
+        self.invalidate_stacks_if::<T>();
         // println!("World: Adding component {:?} to entity {}", type_id, entity.0); // デバッグ用ログ
 
         // 1. `component_stores` から `TypeId` に対応する `ComponentStoreEntry` を可変参照で取得する。
@@ -246,6 +321,8 @@ impl World {
                 // if old_component.is_some() {
                 //     println!("World: Replaced existing component {:?} for entity {}", type_id, entity.0);
                 // }
+                // 新規なら added も changed も今の tick、上書きなら changed だけ更新
+                entry.trackers.on_insert(entity, self.change_tick);
             } else {
                 // ダウンキャスト失敗。これは register_component で登録した型と違う型で add_component を呼んでるなど、
                 // プログラムのロジックエラーの可能性が高い。register_component の実装ミスかも？
@@ -309,13 +386,17 @@ impl World {
         }
 
         let type_id = TypeId::of::<T>();
+        // 呼び出し側が StackInfo を書き換えるかもしれないので、索引は作り直し扱いにしておく
+        self.invalidate_stacks_if::<T>();
+        let tick = self.change_tick;
         // 1. `component_stores` から可変参照で `ComponentStoreEntry` を取得。
-        self.component_stores.get_mut(&type_id)
-            // 2. `and_then` で `entry.storage` のダウンキャスト (可変参照版 `downcast_mut`)。
-            .and_then(|entry| entry.storage.downcast_mut::<HashMap<Entity, T>>())
-            // 3. `and_then` で `HashMap` から可変参照を取得 (`get_mut`)。
-            .and_then(|storage| storage.get_mut(&entity))
-            // これも None 安全！👍
+        let entry = self.component_stores.get_mut(&type_id)?;
+        // 2. `entry.storage` のダウンキャスト (可変参照版 `downcast_mut`) して、`HashMap` から可変参照を取得 (`get_mut`)。
+        let component = entry.storage.downcast_mut::<HashMap<Entity, T>>()?.get_mut(&entity)?;
+        // 3. 書き換えられるかもしれないので、changed として記録しておく
+        entry.trackers.on_change(entity, tick);
+        Some(component)
+        // これも None 安全！👍
     }
 
     /// 指定されたエンティティから、指定された型のコンポーネントを **削除** する。
@@ -339,14 +420,16 @@ impl World {
         // }
 
         let type_id = TypeId::of::<T>();
+        self.invalidate_stacks_if::<T>();
         // 1. `component_stores` から可変参照で `ComponentStoreEntry` を取得。
-        self.component_stores.get_mut(&type_id)
-            // 2. `and_then` で `entry.storage` を `HashMap<Entity, T>` にダウンキャスト (可変参照)。
-            .and_then(|entry| entry.storage.downcast_mut::<HashMap<Entity, T>>())
-            // 3. `and_then` で `HashMap` から `remove` を呼び出す！
-            //    `remove(&entity)` は `Option<T>` を返す。これがまさに欲しい戻り値！✨
-            .and_then(|storage| storage.remove(&entity))
-            // これで完了！シンプル！👍
+        let entry = self.component_stores.get_mut(&type_id)?;
+        // 2. `entry.storage` を `HashMap<Entity, T>` にダウンキャスト (可変参照) して `remove` を呼び出す！
+        //    `remove(&entity)` は `Option<T>` を返す。これがまさに欲しい戻り値！✨
+        let removed = entry.storage.downcast_mut::<HashMap<Entity, T>>()?.remove(&entity)?;
+        // 3. removed として記録
+        entry.trackers.on_remove(entity, self.change_tick);
+        Some(removed)
+        // これで完了！シンプル！👍
     }
 
     /// 指定された型のコンポーネントを持つ **全ての生存しているエンティティ** のリストを取得する。
@@ -390,9 +473,412 @@ impl World {
         // })
     }
 
-    /// 指定された StackType を持つ最初のエンティティを探す。
-    /// StackInfo コンポーネントを持つ全エンティティを検索し、
-    /// stack_type が一致するものが見つかったらその Entity を返す。
+    /// 複数のコンポーネントをまとめて取り出す **読み取り専用** クエリ！🔍
+    /// `Q` には `&T`, `Option<&T>`, `Entity` とそのタプルが使えるよ。
+    /// 結果は Entity の昇順で返ってくる。
+    ///
+    /// ```ignore
+    /// for (entity, card, stack_info) in world.query::<(Entity, &Card, &StackInfo)>() { ... }
+    /// ```
+    pub fn query<Q: QueryData + 'static>(&self) -> impl Iterator<Item = Q::Item<'_>> + '_ {
+        self.query_filtered::<Q, ()>()
+    }
+
+    /// `query` に `With<T>` / `Without<T>` の絞り込みを付けたバージョン。
+    /// (`Q` と `F` は型の目印に使うだけなので `'static` 制約付き。`&Card` みたいに書けば参照のライフタイムは勝手に推論されるよ)
+    pub fn query_filtered<Q: QueryData + 'static, F: QueryFilter + 'static>(&self) -> impl Iterator<Item = Q::Item<'_>> + '_ {
+        let state = Q::init_state(self);
+        let filter = F::init_state(self);
+        self.candidate_entities(Q::candidates(&state).narrower(F::candidates(&filter)))
+            .into_iter()
+            .filter(move |&entity| F::matches(&filter, entity))
+            .filter_map(move |entity| Q::fetch(&state, entity))
+    }
+
+    /// 複数のコンポーネントをまとめて取り出す **書き込み可能** クエリ！✍️
+    /// `Q` には `query` の型に加えて `&mut T`, `Option<&mut T>` も使えるよ。
+    ///
+    /// # パニック
+    /// 同じコンポーネント型を `Q` に2回以上書いた場合 (例: `(&mut Card, &Card)`)。
+    pub fn query_mut<Q: QueryDataMut + 'static>(&mut self) -> impl Iterator<Item = Q::Item<'_>> + '_ {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// `query_mut` に `With<T>` / `Without<T>` の絞り込みを付けたバージョン。
+    /// (`Q` は型の目印に使うだけなので `'static` 制約付き。`&mut Card` みたいに書けば参照のライフタイムは勝手に推論されるよ)
+    pub fn query_filtered_mut<Q: QueryDataMut + 'static, F: QueryFilter + 'static>(&mut self) -> impl Iterator<Item = Q::Item<'_>> + '_ {
+        // フィルターは &World で判定するので、可変借用する前に対象エンティティを決めちゃう
+        let candidates: Vec<Entity> = {
+            let filter = F::init_state(self);
+            let mut candidates = self.candidate_entities(Q::candidates(self).narrower(F::candidates(&filter)));
+            candidates.retain(|&entity| F::matches(&filter, entity));
+            candidates
+        };
+
+        // StackInfo を `&mut` で取り出すクエリなら、索引は作り直し扱い
+        if Q::writes(TypeId::of::<StackInfo>()) {
+            self.invalidate_stacks();
+        }
+        // `&mut T` で取り出されたコンポーネントは、StoreBorrows がその場で changed として記録してくれる
+        let mut stores = StoreBorrows::new(
+            self.component_stores
+                .iter_mut()
+                .map(|(type_id, entry)| {
+                    (*type_id, (&mut *entry.storage as &mut (dyn Any + Send + Sync), &mut entry.trackers))
+                })
+                .collect(),
+            self.change_tick,
+        );
+        let mut state = Q::init_state(&mut stores, &candidates);
+        candidates
+            .into_iter()
+            .filter_map(move |entity| if Q::contains(&state, entity) { Q::take(&mut state, entity) } else { None })
+    }
+
+    /// クエリで見るエンティティ (生きてるものだけ、番号の昇順)。
+    fn candidate_entities(&self, candidates: Candidates<'_>) -> Vec<Entity> {
+        match candidates {
+            Candidates::All => self.sorted_entities(),
+            Candidates::Nothing => Vec::new(),
+            Candidates::Store(store) => {
+                let mut entities = store.entities();
+                entities.retain(|&entity| self.is_entity_alive(entity));
+                entities.sort();
+                entities
+            }
+        }
+    }
+
+    /// 今の tick。コンポーネントの追加や書き換えはこの値で記録される。
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick
+    }
+
+    /// tick を1つ進めて、進める前の tick を返す。
+    /// 返ってきた tick を覚えておいて次に `changed_since` などに渡せば、「この呼び出し以降の変化」がもれなく取れるよ。
+    /// (`Schedule` はシステムを1個実行するたびにこれを呼んでる)
+    pub fn increment_change_tick(&mut self) -> u64 {
+        let tick = self.change_tick;
+        self.change_tick += 1;
+        tick
+    }
+
+    /// `added` / `changed` / `removed` の基準の tick (= 今実行中のシステムが前回実行された時の tick)。
+    pub fn last_change_tick(&self) -> u64 {
+        self.last_change_tick
+    }
+
+    /// `added` / `changed` / `removed` の基準の tick をセットする。普段は `Schedule` が呼ぶよ。
+    pub fn set_last_change_tick(&mut self, tick: u64) {
+        self.last_change_tick = tick;
+    }
+
+    /// `entity` の `T` がいつ追加/書き換えされたか。持ってなければ `None`。
+    pub fn component_ticks<T: Component + Any + 'static>(&self, entity: Entity) -> Option<ComponentTicks> {
+        self.trackers::<T>().and_then(|trackers| trackers.get(entity))
+    }
+
+    /// 今のシステムが前回実行されてから `T` が追加されたエンティティ (昇順)。
+    pub fn added<T: Component + Any + 'static>(&self) -> Vec<Entity> {
+        self.added_since::<T>(self.last_change_tick)
+    }
+
+    /// 今のシステムが前回実行されてから `T` が追加 or 書き換えされたエンティティ (昇順)。
+    pub fn changed<T: Component + Any + 'static>(&self) -> Vec<Entity> {
+        self.changed_since::<T>(self.last_change_tick)
+    }
+
+    /// 今のシステムが前回実行されてから `T` を消された (エンティティごと消された場合も含む) エンティティ (昇順)。
+    pub fn removed<T: Component + Any + 'static>(&self) -> Vec<Entity> {
+        self.removed_since::<T>(self.last_change_tick)
+    }
+
+    /// `since` より後に `T` が追加されたエンティティ (昇順)。
+    pub fn added_since<T: Component + Any + 'static>(&self, since: u64) -> Vec<Entity> {
+        Self::sorted(self.trackers::<T>().map(|trackers| trackers.added_since(since).collect()))
+    }
+
+    /// `since` より後に `T` が追加 or 書き換えされたエンティティ (昇順)。
+    pub fn changed_since<T: Component + Any + 'static>(&self, since: u64) -> Vec<Entity> {
+        Self::sorted(self.trackers::<T>().map(|trackers| trackers.changed_since(since).collect()))
+    }
+
+    /// `since` より後に `T` を消されたエンティティ (昇順、重複なし)。
+    /// 消された記録は `clear_removed_until` で捨てられるまでしか残らないので注意してね。
+    pub fn removed_since<T: Component + Any + 'static>(&self, since: u64) -> Vec<Entity> {
+        let mut entities = Self::sorted(self.trackers::<T>().map(|trackers| trackers.removed_since(since).collect()));
+        entities.dedup();
+        entities
+    }
+
+    /// `tick` までに消されたコンポーネントの記録を全部の型から捨てる。`Schedule::tick` が毎フレーム呼ぶよ。
+    pub fn clear_removed_until(&mut self, tick: u64) {
+        for entry in self.component_stores.values_mut() {
+            entry.trackers.clear_removed_until(tick);
+        }
+    }
+
+    fn trackers<T: Component + Any + 'static>(&self) -> Option<&ChangeTrackers> {
+        self.component_stores.get(&TypeId::of::<T>()).map(|entry| &entry.trackers)
+    }
+
+    fn sorted(entities: Option<Vec<Entity>>) -> Vec<Entity> {
+        let mut entities = entities.unwrap_or_default();
+        entities.sort();
+        entities
+    }
+
+    /// 生存している全エンティティを番号の昇順で返す (クエリの結果の順番を決めるため)。
+    fn sorted_entities(&self) -> Vec<Entity> {
+        self.entities.iter().collect()
+    }
+
+    /// リソースを World に置く。同じ型のリソースが既にあったら上書きして、古い方を返すよ。
+    /// (コンポーネントと違って `register_component` みたいな事前登録はいらない！)
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> Option<R> {
+        self.resources
+            .insert(TypeId::of::<R>(), Box::new(resource))
+            .and_then(|old| (old as Box<dyn Any>).downcast::<R>().ok())
+            .map(|old| *old)
+    }
+
+    /// リソースへの **読み取り専用** 参照。まだ置かれてなければ `None`。
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources
+            .get(&TypeId::of::<R>())
+            .and_then(|resource| resource.downcast_ref::<R>())
+    }
+
+    /// リソースへの **書き込み可能** 参照。まだ置かれてなければ `None`。
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources
+            .get_mut(&TypeId::of::<R>())
+            .and_then(|resource| resource.downcast_mut::<R>())
+    }
+
+    /// リソースを World から取り除いて返す。無ければ `None`。
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        self.resources
+            .remove(&TypeId::of::<R>())
+            .and_then(|resource| (resource as Box<dyn Any>).downcast::<R>().ok())
+            .map(|resource| *resource)
+    }
+
+    /// リソースが置かれてるかどうか。
+    pub fn contains_resource<R: Resource>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    /// イベント型 `T` を使えるようにする (`Events<T>` リソースを置く)。2回目以降は何もしないよ。
+    pub fn add_event<T: Event>(&mut self) {
+        if self.contains_resource::<Events<T>>() {
+            return;
+        }
+        self.insert_resource(Events::<T>::default());
+        self.event_updaters.push(|world| {
+            if let Some(events) = world.resource_mut::<Events<T>>() {
+                events.update();
+            }
+        });
+    }
+
+    /// イベントを送る。`add_event::<T>()` されてない (= 誰も聞いてない) 型なら何もしないで捨てるよ。
+    pub fn send_event<T: Event>(&mut self, event: T) {
+        if let Some(events) = self.resource_mut::<Events<T>>() {
+            events.send(event);
+        }
+    }
+
+    /// 登録済みの全イベント型のバッファを1つ進める。`Schedule::tick` が毎フレームの最初に呼ぶよ。
+    pub fn update_events(&mut self) {
+        for update in self.event_updaters.clone() {
+            update(self);
+        }
+    }
+
+    /// スタックごとのカードの並び (索引) を返すよ！📚
+    /// 前回から StackInfo が変わってなければ作り置きをそのまま返すので、何回呼んでも軽い。
+    /// (`top` / `cards` / `len` / `location` が使える。詳しくは `Stacks` を見てね)
+    pub fn stacks(&self) -> &Stacks {
+        self.stacks
+            .get_or_init(|| Stacks::build(self.query::<(Entity, &StackInfo)>()))
+    }
+
+    /// `from` の `index` 番目 (下から 0 始まり) から上のカードを、ひとかたまりのまま `to` の一番上に移す。
+    /// StackInfo (stack_type と position_in_stack) を書き換えて、索引も作り直さずにその場で更新するよ。
+    /// Position や表裏はいじらないので、必要なら呼び出し側でやってね。
+    ///
+    /// # 戻り値
+    /// 動いたエンティティ (下から順)。`index` が範囲外なら空。
+    pub fn move_run(&mut self, from: StackType, index: usize, to: StackType) -> Vec<Entity> {
+        let mut stacks = match self.stacks.take() {
+            Some(stacks) => stacks,
+            None => Stacks::build(self.query::<(Entity, &StackInfo)>()),
+        };
+        let moved = stacks.move_run(from, index, to);
+        let base = stacks.len(to) - moved.len();
+        for (offset, &entity) in moved.iter().enumerate() {
+            if let Some(stack_info) = self.get_component_mut::<StackInfo>(entity) {
+                stack_info.stack_type = to;
+                stack_info.position_in_stack = (base + offset) as u8;
+            }
+        }
+        // 上の get_component_mut で空になってるので、更新済みの索引を戻しておく
+        self.stacks = OnceLock::from(stacks);
+        moved
+    }
+
+    /// 型 `T` が StackInfo なら Stacks 索引を捨てる (次に `stacks()` を呼んだ時に作り直される)。
+    fn invalidate_stacks_if<T: 'static>(&mut self) {
+        if TypeId::of::<T>() == TypeId::of::<StackInfo>() {
+            self.invalidate_stacks();
+        }
+    }
+
+    fn invalidate_stacks(&mut self) {
+        self.stacks.take();
+    }
+
+    /// スナップショット対象の型 (`register_serializable_component` で登録したもの) を全部写す。📸
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let mut entities: BTreeMap<Entity, BTreeMap<String, Value>> = BTreeMap::new();
+        for entry in self.component_stores.values() {
+            let Some(serde) = &entry.serde else { continue };
+            for (entity, value) in (serde.save)(&*entry.storage) {
+                entities.entry(entity).or_default().insert(serde.name.to_string(), value);
+            }
+        }
+        WorldSnapshot {
+            entities: entities.into_iter().map(|(entity, components)| EntitySnapshot { entity, components }).collect(),
+        }
+    }
+
+    /// `snapshot` で撮った時の状態に戻す。エンティティも同じ番号・世代で戻るので、
+    /// 撮った時に持ってた `Entity` (履歴とか) はそのまま使えるよ。
+    ///
+    /// - スナップショット対象の型は、スナップショットの中身と全く同じになる (無いものは外す)
+    /// - スナップショットに無いのに対象の型を持ってるエンティティは消す
+    /// - 対象じゃない型 (`DraggingInfo` とか) は触らない
+    ///
+    /// 知らない名前や読めない値があったら、何も変えずに `Err`。
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> Result<(), String> {
+        self.restore_with(snapshot, true, |world, entity| {
+            if !world.is_entity_alive(entity) {
+                // 同じ番号で別の (スナップショットに無い) エンティティが生きてたら、どいてもらう
+                if let Some(in_the_way) = world.entity_from_index(entity.index()) {
+                    world.destroy_entity(in_the_way);
+                }
+                world.entities.spawn_at(entity);
+            }
+            entity
+        })?;
+        Ok(())
+    }
+
+    /// よその World (サーバー、セーブデータ) のスナップショットを、こっちのエンティティに付け替えて戻す。
+    /// `map` に対応が無い (か、もう消えた) エンティティは新しく作って `map` に登録する。
+    /// 消したエンティティは `map` からも外すよ。それ以外は `restore` と同じ。
+    pub fn restore_mapped(&mut self, snapshot: &WorldSnapshot, map: &mut EntityMap) -> Result<(), String> {
+        let destroyed = self.restore_with(snapshot, true, |world, remote| world.local_entity(map, remote))?;
+        for entity in destroyed {
+            map.remove_local(entity);
+        }
+        Ok(())
+    }
+
+    /// よその World のスナップショットの違い (`WorldSnapshot::diff`) を、こっちのエンティティに付け替えて重ねる。
+    /// `delta.changed` のエンティティは `restore_mapped` と同じように付け直して、`delta.removed` のエンティティは消す。
+    /// どっちにも入ってないエンティティは触らないよ。読めない値があったら、何も変えずに `Err`。
+    pub fn apply_delta_mapped(&mut self, delta: &SnapshotDelta, map: &mut EntityMap) -> Result<(), String> {
+        self.restore_with(&delta.changed, false, |world, remote| world.local_entity(map, remote))?;
+        for &remote in &delta.removed {
+            if let Some(local) = map.local(remote) {
+                self.destroy_entity(local);
+                map.remove_local(local);
+            }
+        }
+        Ok(())
+    }
+
+    /// よその World の `remote` に対応する、こっちの生きてるエンティティ。無ければ作って `map` に登録する。
+    fn local_entity(&mut self, map: &mut EntityMap, remote: Entity) -> Entity {
+        match map.local(remote).filter(|&local| self.is_entity_alive(local)) {
+            Some(local) => local,
+            None => {
+                let local = self.create_entity();
+                map.insert(remote, local);
+                local
+            }
+        }
+    }
+
+    /// `restore` / `restore_mapped` / `apply_delta_mapped` の中身。`local` でスナップショットのエンティティ → こっちのエンティティにする。
+    /// `whole` なら、スナップショットに無いエンティティ (対象の型を持ってるもの) を消す。消したエンティティを返すよ。
+    fn restore_with(
+        &mut self,
+        snapshot: &WorldSnapshot,
+        whole: bool,
+        mut local: impl FnMut(&mut World, Entity) -> Entity,
+    ) -> Result<Vec<Entity>, String> {
+        // 1. 先に全部読んでおく (失敗したら World は何も変えない)
+        let by_name: HashMap<&str, TypeId> = self
+            .component_stores
+            .iter()
+            .filter_map(|(type_id, entry)| entry.serde.as_ref().map(|serde| (serde.name, *type_id)))
+            .collect();
+        let mut decoded = Vec::with_capacity(snapshot.len());
+        for saved in &snapshot.entities {
+            let mut components: DecodedComponents = Vec::with_capacity(saved.components.len());
+            for (name, value) in &saved.components {
+                let type_id = *by_name
+                    .get(name.as_str())
+                    .ok_or_else(|| format!("Unknown component {:?} in the snapshot", name))?;
+                let serde = self.component_stores[&type_id].serde.as_ref().expect("looked up by its serializer");
+                let component = (serde.decode)(value).map_err(|e| format!("{:?}: {}", saved.entity, e))?;
+                components.push((type_id, component));
+            }
+            decoded.push((saved.entity, components));
+        }
+
+        // 2. スナップショットのエンティティを、こっちのエンティティにする
+        let decoded: Vec<(Entity, DecodedComponents)> =
+            decoded.into_iter().map(|(entity, components)| (local(self, entity), components)).collect();
+        let kept: HashSet<Entity> = decoded.iter().map(|(entity, _)| *entity).collect();
+
+        // 3. スナップショットに無いのに対象の型を持ってるエンティティは消す (丸ごと戻す時だけ)
+        let mut stale: Vec<Entity> = self
+            .component_stores
+            .values()
+            .filter(|entry| whole && entry.serde.is_some())
+            .flat_map(|entry| entry.trackers.entities())
+            .filter(|entity| !kept.contains(entity))
+            .collect();
+        stale.sort();
+        stale.dedup();
+        for &entity in &stale {
+            self.destroy_entity(entity);
+        }
+
+        // 4. 対象の型を付け直す (スナップショットに無い型は外す)
+        self.invalidate_stacks();
+        let tick = self.change_tick;
+        for (entity, components) in decoded {
+            let present: HashSet<TypeId> = components.iter().map(|(type_id, _)| *type_id).collect();
+            for (type_id, entry) in self.component_stores.iter_mut() {
+                if entry.serde.is_some() && !present.contains(type_id) {
+                    (entry.remover)(&mut entry.storage, entity);
+                    entry.trackers.on_remove(entity, tick);
+                }
+            }
+            for (type_id, component) in components {
+                let insert = self.component_stores[&type_id].serde.as_ref().expect("decoded by its serializer").insert;
+                insert(self, entity, component);
+            }
+        }
+        Ok(stale)
+    }
+
+    /// 指定された StackType を持つ最初のエンティティ (スタックの一番下) を探す。
     ///
     /// # 引数
     /// * `stack_type`: 検索したいスタックの種類 (`StackType`)。
@@ -401,28 +887,8 @@ impl World {
     /// * `Some(Entity)`: 指定された `stack_type` を持つ最初のエンティティが見つかった場合。
     /// * `None`: 見つからなかった場合。
     pub fn find_entity_by_stack_type(&self, stack_type: StackType) -> Option<Entity> {
-        // 1. StackInfo コンポーネントを持つ全てのエンティティのリストを取得する。
-        let entities_with_stack_info = self.get_all_entities_with_component::<StackInfo>();
-
-        // 2. リスト内の各エンティティについてループ処理を行う。
-        for entity in entities_with_stack_info {
-            // 3. 各エンティティから StackInfo コンポーネントへの参照を取得する。
-            //    get_component は Option<&StackInfo> を返す。
-            if let Some(stack_info) = self.get_component::<StackInfo>(entity) {
-                // 4. StackInfo の stack_type フィールドが、引数で受け取った stack_type と一致するか比較する。
-                if stack_info.stack_type == stack_type {
-                    // 5. 一致したら、そのエンティティを Some でラップして返し、関数を終了する。
-                    println!("World: Found entity {:?} for stack type {:?}", entity, stack_type); // デバッグ用ログ
-                    return Some(entity);
-                }
-            }
-            // get_component が None を返した場合や、stack_type が一致しなかった場合は、
-            // ループの次のエンティティに進む。
-        }
-
-        // 6. ループが最後まで実行されても見つからなかった場合は、None を返す。
-        println!("World: No entity found for stack type {:?}", stack_type); // デバッグ用ログ
-        None
+        // Stacks 索引から、そのスタックの一番下のエンティティを取る (よく呼ばれるのでログは出さない)
+        self.stacks().cards(stack_type).first().copied()
     }
 
     // --- 以下、テストコード用のヘルパーメソッド (外部公開はしない想定) ---
@@ -433,7 +899,7 @@ impl World {
     pub(crate) fn storage<T: Component + Any + 'static>(&self) -> Option<&dyn Any> {
         let type_id = TypeId::of::<T>();
         self.component_stores.get(&type_id)
-            .map(|entry| &*entry.storage) // ComponentStoreEntry から中の Box<dyn Any> をデリファレンスして &dyn Any を返す！
+            .map(|entry| &*entry.storage as &dyn Any) // ComponentStoreEntry から中の Box<dyn Any> をデリファレンスして &dyn Any を返す！
     }
 
     /// 特定の型のコンポーネントストレージ (`HashMap<Entity, T>` が入った `Box<dyn Any>`) への
@@ -441,12 +907,20 @@ impl World {
     #[allow(dead_code)] // テスト以外で使わないので警告抑制
     pub(crate) fn storage_mut<T: Component + Any + 'static>(&mut self) -> Option<&mut dyn Any> {
         let type_id = TypeId::of::<T>();
+        self.invalidate_stacks_if::<T>();
         self.component_stores.get_mut(&type_id)
-            .map(|entry| &mut *entry.storage) // ComponentStoreEntry から中の Box<dyn Any> をデリファレンスして &mut dyn Any を返す！
+            .map(|entry| &mut *entry.storage as &mut dyn Any) // ComponentStoreEntry から中の Box<dyn Any> をデリファレンスして &mut dyn Any を返す！
     }
 
 } // impl World の終わり
 
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // テストコードは world_tests.rs に移動
 #[cfg(test)]
-mod world_tests; 
\ No newline at end of file
+#[path = "world_tests.rs"]
+mod world_tests;
\ No newline at end of file
@@ -5,14 +5,15 @@
 use super::*;
 // テストで使う標準ライブラリもインポート！
 use std::any::TypeId;
-use std::collections::{HashMap, HashSet}; // HashMap と HashSet を使う
-use wasm_bindgen_test::*; // ★ wasm-bindgen-test をインポート ★★★
+use std::collections::HashMap; // HashMap を使う
 use crate::ecs::component::Component; // Component トレイトも使う
 // ★ StackType も使う可能性があるのでインポート (find_entity_by_stack_type のテストなど) ★
 use crate::components::stack::StackType; // ★追加★
 // ★ StackInfo と Card もインポート ★
 use crate::components::stack::StackInfo;
 use crate::components::card::{Card, Suit, Rank};
+use crate::ecs::entity_map::EntityMap;
+use crate::ecs::snapshot::WorldSnapshot;
 
 // --- テスト用のダミーコンポーネントを定義 ---
 
@@ -35,74 +36,74 @@ struct Velocity {
 impl Component for Velocity {}
 
 // --- テスト関数たち ---
-// 各テスト関数には #[wasm_bindgen_test] アトリビュートを付けるよ！
+// ネイティブの `cargo test` で動くように、普通の #[test] にしてるよ！
 
-#[wasm_bindgen_test]
+#[test]
 fn test_new_world_is_empty() {
     let world = World::new();
     assert!(world.entities.is_empty(), "New world should have no entities");
-    assert_eq!(world.next_entity_id, 0, "Next entity ID should start at 0");
     assert!(world.component_stores.is_empty(), "New world should have no component stores");
-    // assert!(world.free_list.is_empty(), "New world should have an empty free list"); // free_list を使う場合はこれも
     println!("test_new_world_is_empty: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_create_entity() {
     let mut world = World::new();
     let entity1 = world.create_entity();
     let entity2 = world.create_entity();
 
-    assert_eq!(entity1, Entity(0), "First entity ID should be 0");
-    assert_eq!(entity2, Entity(1), "Second entity ID should be 1");
-    assert_eq!(world.next_entity_id, 2, "Next entity ID should be 2");
+    assert_eq!(entity1, Entity::new(0, 0), "First entity ID should be 0");
+    assert_eq!(entity2, Entity::new(1, 0), "Second entity ID should be 1");
     assert_eq!(world.entities.len(), 2, "World should contain 2 entities");
-    assert!(world.entities.contains(&entity1), "World should contain entity1");
-    assert!(world.entities.contains(&entity2), "World should contain entity2");
+    assert!(world.is_entity_alive(entity1), "World should contain entity1");
+    assert!(world.is_entity_alive(entity2), "World should contain entity2");
     println!("test_create_entity: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
-fn test_create_entity_with_id() {
+#[test]
+fn test_destroyed_ids_are_reused_with_new_generation() {
     let mut world = World::new();
-    let entity5 = Entity(5);
-    let entity2 = Entity(2);
-
-    world.create_entity_with_id(entity5);
-    assert!(world.is_entity_alive(entity5), "Entity 5 should be alive");
-    assert_eq!(world.next_entity_id, 6, "Next ID should be 6 after adding entity 5");
-    assert_eq!(world.entities.len(), 1, "World should have 1 entity");
-
-    world.create_entity_with_id(entity2);
-    assert!(world.is_entity_alive(entity2), "Entity 2 should be alive");
-    assert_eq!(world.next_entity_id, 6, "Next ID should still be 6 after adding entity 2");
-    assert_eq!(world.entities.len(), 2, "World should have 2 entities");
-
-    // 通常の create_entity を呼ぶと、next_entity_id から新しい ID が使われる
-    let entity6 = world.create_entity();
-    assert_eq!(entity6, Entity(6), "Next created entity should have ID 6");
-    assert_eq!(world.next_entity_id, 7, "Next ID should become 7");
-    assert_eq!(world.entities.len(), 3, "World should have 3 entities");
-
-    println!("test_create_entity_with_id: PASSED ✅");
+    world.register_component::<Position>();
+    let old = world.create_entity();
+    world.add_component(old, Position { x: 1, y: 1 });
+    let _other = world.create_entity();
+
+    assert!(world.destroy_entity(old));
+    let reused = world.create_entity();
+
+    // 番号は使い回されるけど、世代が違うので別のエンティティ
+    assert_eq!(reused.index(), old.index(), "Destroyed index should be reused");
+    assert_ne!(reused, old, "Reused entity should have a new generation");
+    assert!(!world.is_entity_alive(old), "Stale handle should not be alive");
+    assert!(world.is_entity_alive(reused));
+    assert_eq!(world.entity_from_index(old.index()), Some(reused));
+
+    // 古いハンドル経由では新しいエンティティを触れない
+    world.add_component(old, Position { x: 9, y: 9 });
+    assert_eq!(world.get_component::<Position>(reused), None, "Stale handle must not write to the new entity");
+    assert_eq!(world.get_component::<Position>(old), None);
+    assert!(!world.destroy_entity(old), "Destroying a stale handle should fail");
+    assert!(world.is_entity_alive(reused));
+
+    println!("test_destroyed_ids_are_reused_with_new_generation: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_is_entity_alive() {
     let mut world = World::new();
     let entity0 = world.create_entity();
-    let entity1 = Entity(1); // まだ作ってない
+    let entity1 = Entity::new(1, 0); // まだ作ってない
 
     assert!(world.is_entity_alive(entity0), "Entity 0 should be alive");
     assert!(!world.is_entity_alive(entity1), "Entity 1 should not be alive yet");
 
-    world.create_entity_with_id(entity1);
+    assert_eq!(world.create_entity(), entity1);
     assert!(world.is_entity_alive(entity1), "Entity 1 should be alive now");
 
     println!("test_is_entity_alive: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_register_and_add_component() {
     let mut world = World::new();
     world.register_component::<Position>(); // Position 型のコンポーネントを使えるように登録！
@@ -130,7 +131,7 @@ fn test_register_and_add_component() {
     assert_eq!(world.get_component::<Position>(entity1), Some(&pos1));
 
     // 存在しないエンティティに追加しようとしても何も起こらないはず
-    let non_existent_entity = Entity(99);
+    let non_existent_entity = Entity::new(99, 0);
     world.add_component(non_existent_entity, Position { x: 0, y: 0 });
     assert_eq!(world.get_component::<Position>(non_existent_entity), None);
 
@@ -148,7 +149,7 @@ fn test_register_and_add_component() {
 //     // ここに到達したらテスト失敗！
 // }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_get_component() {
     let mut world = World::new();
     world.register_component::<Position>();
@@ -174,7 +175,7 @@ fn test_get_component() {
     assert_eq!(world.get_component::<Velocity>(entity2), None);
 
     // 存在しないエンティティは None
-    assert_eq!(world.get_component::<Position>(Entity(99)), None);
+    assert_eq!(world.get_component::<Position>(Entity::new(99, 0)), None);
 
     // 登録されていないコンポーネント型は None (パニックしない！)
     #[derive(Debug, Clone, Copy, PartialEq, Eq)] struct UnregisteredComponent;
@@ -184,7 +185,7 @@ fn test_get_component() {
     println!("test_get_component: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_get_component_mut() {
     let mut world = World::new();
     world.register_component::<Position>();
@@ -205,7 +206,7 @@ fn test_get_component_mut() {
     assert_eq!(world.get_component::<Position>(entity1), Some(&expected_pos));
 
     // 持っていない、存在しない、登録されていない場合は None
-    assert_eq!(world.get_component_mut::<Position>(Entity(99)), None);
+    assert_eq!(world.get_component_mut::<Position>(Entity::new(99, 0)), None);
     assert_eq!(world.get_component_mut::<Velocity>(entity1), None); // Velocity は登録されてない
     #[derive(Debug, PartialEq)] // <- PartialEq を追加
     struct Unregistered; impl Component for Unregistered {}
@@ -214,7 +215,7 @@ fn test_get_component_mut() {
     println!("test_get_component_mut: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_remove_component() {
     let mut world = World::new();
     world.register_component::<Position>();
@@ -242,7 +243,7 @@ fn test_remove_component() {
     assert_eq!(removed_again, None, "Removing again should return None");
 
     // 存在しないエンティティから削除しようとしても None
-    assert_eq!(world.remove_component::<Velocity>(Entity(99)), None);
+    assert_eq!(world.remove_component::<Velocity>(Entity::new(99, 0)), None);
 
     // 登録されていないコンポーネント型を削除しようとしても None (パニックしない)
     #[derive(Debug, PartialEq)] struct Unregistered; impl Component for Unregistered {}
@@ -252,7 +253,7 @@ fn test_remove_component() {
 }
 
 
-#[wasm_bindgen_test]
+#[test]
 fn test_get_all_entities_with_component() {
     let mut world = World::new();
     world.register_component::<Position>();
@@ -285,7 +286,7 @@ fn test_get_all_entities_with_component() {
     println!("test_get_all_entities_with_component: PASSED ✅");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn test_destroy_entity_removes_components() {
     let mut world = World::new();
     world.register_component::<Position>();
@@ -319,14 +320,14 @@ fn test_destroy_entity_removes_components() {
     assert!(world.storage::<Position>().unwrap().downcast_ref::<HashMap<Entity, Position>>().unwrap().get(&entity2).is_some(), "Pos map for entity 2");
 
     // 存在しないエンティティを削除しようとしても false が返る
-    let not_destroyed = world.destroy_entity(Entity(99));
+    let not_destroyed = world.destroy_entity(Entity::new(99, 0));
     assert!(!not_destroyed, "Destroying non-existent entity should return false");
 
     println!("test_destroy_entity_removes_components: PASSED ✅");
 }
 
 
-#[wasm_bindgen_test]
+#[test]
 fn test_find_entity_by_stack_type() {
     let mut world = World::new();
     world.register_component::<StackInfo>();
@@ -356,6 +357,271 @@ fn test_find_entity_by_stack_type() {
 }
 
 
+#[test]
+fn test_stacks_index_follows_stack_info_changes() {
+    let mut world = World::new();
+    world.register_component::<StackInfo>();
+
+    let bottom = world.create_entity();
+    world.add_component(bottom, StackInfo::new(StackType::Tableau(0), 0));
+    let top = world.create_entity();
+    world.add_component(top, StackInfo::new(StackType::Tableau(0), 1));
+
+    assert_eq!(world.stacks().top(StackType::Tableau(0)), Some(top));
+    assert_eq!(world.stacks().len(StackType::Tableau(0)), 2);
+
+    // get_component_mut で直接書き換えても、次の stacks() には反映される
+    world.get_component_mut::<StackInfo>(top).unwrap().stack_type = StackType::Waste;
+    assert_eq!(world.stacks().cards(StackType::Tableau(0)), &[bottom]);
+    assert_eq!(world.stacks().top(StackType::Waste), Some(top));
+
+    // move_run は StackInfo も書き換える
+    let moved = world.move_run(StackType::Tableau(0), 0, StackType::Waste);
+    assert_eq!(moved, vec![bottom]);
+    let stack_info = world.get_component::<StackInfo>(bottom).unwrap();
+    assert_eq!(stack_info.stack_type, StackType::Waste);
+    assert_eq!(stack_info.position_in_stack, 1);
+    assert_eq!(world.stacks().cards(StackType::Waste), &[top, bottom]);
+
+    // destroy したエンティティは索引からも消える
+    world.destroy_entity(top);
+    assert_eq!(world.stacks().cards(StackType::Waste), &[bottom]);
+
+    println!("test_stacks_index_follows_stack_info_changes: PASSED ✅");
+}
+
+// テスト用のダミーリソース
+#[derive(Debug, PartialEq)]
+struct Score(u32);
+impl crate::ecs::resource::Resource for Score {}
+
+#[test]
+fn test_resources_insert_get_and_remove() {
+    let mut world = World::new();
+    assert!(!world.contains_resource::<Score>());
+    assert_eq!(world.resource::<Score>(), None);
+
+    // 初めて置くときは古い値なし、2回目は上書きして古い値が返ってくる
+    assert_eq!(world.insert_resource(Score(10)), None);
+    assert_eq!(world.insert_resource(Score(20)), Some(Score(10)));
+    assert_eq!(world.resource::<Score>(), Some(&Score(20)));
+
+    world.resource_mut::<Score>().unwrap().0 += 5;
+    assert_eq!(world.resource::<Score>(), Some(&Score(25)));
+
+    assert_eq!(world.remove_resource::<Score>(), Some(Score(25)));
+    assert!(!world.contains_resource::<Score>());
+
+    println!("test_resources_insert_get_and_remove: PASSED ✅");
+}
+
+#[test]
+fn test_change_detection_tracks_added_changed_and_removed() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    let still = world.create_entity();
+    let moving = world.create_entity();
+    world.add_component(still, Position { x: 0, y: 0 });
+    world.add_component(moving, Position { x: 0, y: 0 });
+    world.add_component(moving, Velocity { dx: 1, dy: 0 });
+
+    // まだ誰も見てないので全部「追加された」
+    assert_eq!(world.added::<Position>(), vec![still, moving]);
+    assert_eq!(world.changed::<Velocity>(), vec![moving]);
+
+    // ここまで見たことにして、tick を進める
+    let seen = world.increment_change_tick();
+    world.set_last_change_tick(seen);
+    assert!(world.changed::<Position>().is_empty());
+
+    // &mut クエリで取り出したものは changed、&T で読むだけなら changed にならない
+    for (position, velocity) in world.query_mut::<(&mut Position, &Velocity)>() {
+        position.x += velocity.dx;
+    }
+    assert_eq!(world.changed::<Position>(), vec![moving]);
+    assert!(world.added::<Position>().is_empty());
+    assert!(world.changed::<Velocity>().is_empty());
+    let ticks = world.component_ticks::<Position>(moving).unwrap();
+    assert!(ticks.added < ticks.changed);
+
+    // 上書きは changed だけど added じゃない
+    world.add_component(still, Position { x: 9, y: 9 });
+    assert_eq!(world.changed::<Position>(), vec![still, moving]);
+    assert!(world.added::<Position>().is_empty());
+
+    // remove_component と destroy_entity はどっちも removed に出る
+    world.remove_component::<Velocity>(moving);
+    world.destroy_entity(still);
+    assert_eq!(world.removed::<Velocity>(), vec![moving]);
+    assert_eq!(world.removed::<Position>(), vec![still]);
+    assert_eq!(world.changed::<Position>(), vec![moving]);
+
+    // 記録を捨てたら removed は空
+    let now = world.change_tick();
+    world.clear_removed_until(now);
+    assert!(world.removed::<Position>().is_empty());
+
+    println!("test_change_detection_tracks_added_changed_and_removed: PASSED ✅");
+}
+
+/// Card と StackInfo はスナップショットに入れて、Velocity は入れない World。
+fn snapshot_world() -> World {
+    let mut world = World::new();
+    world.register_serializable_component::<Card>();
+    world.register_serializable_component::<StackInfo>();
+    world.register_component::<Velocity>();
+    world
+}
+
+fn ace(is_face_up: bool) -> Card {
+    Card { suit: Suit::Spade, rank: Rank::Ace, is_face_up }
+}
+
+#[test]
+fn test_snapshot_and_restore_bring_back_the_same_entities() {
+    let mut world = snapshot_world();
+    let kept = world.create_entity();
+    let removed = world.create_entity();
+    let bystander = world.create_entity(); // スナップショットに入る型を持ってない
+    world.add_component(kept, ace(false));
+    world.add_component(kept, StackInfo::new(StackType::Stock, 0));
+    world.add_component(removed, ace(true));
+    world.add_component(bystander, Velocity { dx: 1, dy: 1 });
+
+    let snapshot = world.snapshot();
+    assert_eq!(snapshot.len(), 2, "Velocity しか持ってないエンティティは入らない");
+    assert_eq!(snapshot.get::<Card>(kept), Some(ace(false)));
+    assert_eq!(snapshot.count::<StackInfo>(), 1);
+    // JSON にしても同じものに戻る
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(serde_json::from_str::<WorldSnapshot>(&json).unwrap(), snapshot);
+
+    // いろいろ変える: めくる、場所を外す、消す、新しいカードを作る (消した番号を使い回す)
+    world.get_component_mut::<Card>(kept).unwrap().is_face_up = true;
+    world.remove_component::<StackInfo>(kept);
+    world.add_component(kept, Velocity { dx: 2, dy: 0 });
+    world.destroy_entity(removed);
+    let added = world.create_entity();
+    world.add_component(added, ace(true));
+    assert_eq!(added.index(), removed.index());
+
+    world.restore(&snapshot).unwrap();
+    assert_eq!(world.snapshot(), snapshot);
+    assert!(world.is_entity_alive(removed), "同じ番号・世代で生き返る");
+    assert!(!world.is_entity_alive(added));
+    assert_eq!(world.get_component::<Card>(kept), Some(&ace(false)));
+    assert_eq!(world.stacks().top(StackType::Stock), Some(kept));
+    // スナップショットに入らない型はそのまま
+    assert_eq!(world.get_component::<Velocity>(kept), Some(&Velocity { dx: 2, dy: 0 }));
+    assert_eq!(world.get_component::<Velocity>(bystander), Some(&Velocity { dx: 1, dy: 1 }));
+
+    println!("test_snapshot_and_restore_bring_back_the_same_entities: PASSED ✅");
+}
+
+#[test]
+fn test_restore_mapped_uses_local_entities() {
+    let mut server = snapshot_world();
+    for stack_type in [StackType::Waste, StackType::Stock] {
+        let entity = server.create_entity();
+        server.add_component(entity, ace(true));
+        server.add_component(entity, StackInfo::new(stack_type, 0));
+    }
+    let snapshot = server.snapshot();
+
+    // こっちの World は番号がずれてて、サーバーに無いカードも持ってる
+    let mut client = snapshot_world();
+    client.create_entity();
+    let stale = client.create_entity();
+    client.add_component(stale, ace(false));
+    let mut map = EntityMap::default();
+    client.restore_mapped(&snapshot, &mut map).unwrap();
+    assert!(!client.is_entity_alive(stale));
+    assert_eq!(client.get_all_entities_with_component::<Card>().len(), 2);
+    for saved in &snapshot.entities {
+        let local = map.local(saved.entity).unwrap();
+        assert_ne!(local, saved.entity);
+        assert_eq!(client.get_component::<Card>(local), Some(&ace(true)));
+    }
+    let waste = client.stacks().top(StackType::Waste).unwrap();
+
+    // 2回目は同じ対応表で、同じエンティティに戻る
+    client.restore_mapped(&snapshot, &mut map).unwrap();
+    assert_eq!(client.stacks().top(StackType::Waste), Some(waste));
+    assert_eq!(map.len(), 2);
+
+    println!("test_restore_mapped_uses_local_entities: PASSED ✅");
+}
+
+#[test]
+fn test_restore_rejects_unknown_or_broken_snapshots_without_changes() {
+    let mut world = snapshot_world();
+    let entity = world.create_entity();
+    world.add_component(entity, ace(false));
+    let before = world.snapshot();
+
+    let mut unknown = before.clone();
+    unknown.entities[0].components.insert("velocity".to_string(), serde_json::json!({ "dx": 1, "dy": 0 }));
+    assert!(world.restore(&unknown).is_err());
+    let mut broken = WorldSnapshot::default();
+    broken.insert(Entity::new(5, 0), &ace(true)).unwrap();
+    broken.entities[0].components.insert("stack_info".to_string(), serde_json::json!("not a stack"));
+    assert!(world.restore(&broken).is_err());
+
+    assert_eq!(world.snapshot(), before);
+    assert!(!world.is_entity_alive(Entity::new(5, 0)));
+
+    println!("test_restore_rejects_unknown_or_broken_snapshots_without_changes: PASSED ✅");
+}
+
+#[test]
+fn test_snapshot_delta_carries_only_changes() {
+    let mut server = snapshot_world();
+    let mut cards = Vec::new();
+    for position_in_stack in 0..3 {
+        let entity = server.create_entity();
+        server.add_component(entity, ace(false));
+        server.add_component(entity, StackInfo::new(StackType::Stock, position_in_stack));
+        cards.push(entity);
+    }
+    let before = server.snapshot();
+    let mut client = snapshot_world();
+    client.create_entity(); // 番号をずらしておく
+    let mut map = EntityMap::default();
+    client.restore_mapped(&before, &mut map).unwrap();
+    let untouched = map.local(cards[0]).unwrap();
+    client.add_component(untouched, Velocity { dx: 3, dy: 0 }); // 手元だけのもの
+
+    // 1枚めくって、1枚消して、1枚増やす
+    server.get_component_mut::<Card>(cards[2]).unwrap().is_face_up = true;
+    server.destroy_entity(cards[1]);
+    let added = server.create_entity();
+    server.add_component(added, ace(true));
+    let after = server.snapshot();
+
+    let delta = before.diff(&after);
+    let changed: Vec<Entity> = delta.changed.entities.iter().map(|saved| saved.entity).collect();
+    assert_eq!(changed.len(), 2, "変わってないエンティティは入らない");
+    assert!(changed.contains(&cards[2]) && changed.contains(&added));
+    assert_eq!(delta.removed, vec![cards[1]]);
+    assert!(after.diff(&after).is_empty());
+    let mut patched = before.clone();
+    patched.apply(&delta);
+    assert_eq!(patched, after);
+
+    let removed = map.local(cards[1]).unwrap();
+    client.apply_delta_mapped(&delta, &mut map).unwrap();
+    assert!(!client.is_entity_alive(removed));
+    assert_eq!(map.remote(removed), None);
+    assert_eq!(client.get_component::<Card>(map.local(cards[2]).unwrap()), Some(&ace(true)));
+    assert_eq!(client.get_component::<Card>(map.local(added).unwrap()), Some(&ace(true)));
+    assert_eq!(client.get_all_entities_with_component::<Card>().len(), 3);
+    // 差分に入ってないエンティティは触らない
+    assert_eq!(client.get_component::<Velocity>(untouched), Some(&Velocity { dx: 3, dy: 0 }));
+
+    println!("test_snapshot_delta_carries_only_changes: PASSED ✅");
+}
+
 // TODO: free_list を実装したら、destroy -> create で ID が再利用されるかのテストも追加！
-// #[wasm_bindgen_test]
+// #[test]
 // fn test_entity_id_reuse() { ... } 
\ No newline at end of file
@@ -0,0 +1,94 @@
+// src/events.rs
+//! ゲームで使うイベントたちだよ！📨
+//!
+//! 入力 (ドラッグ、ダブルクリック、山札クリック、undo / redo、オートコンプリート) は World を直接いじらずに `*Requested` / `StockClicked` を送るだけ。
+//! ルールのチェックと World の更新は Logic ステージのシステムがやって、結果を `MoveApplied` などで知らせる。
+//! サーバーへの通知は Network ステージがその結果イベントを見て組み立てるよ。
+
+use crate::components::stack::StackType;
+use crate::ecs::entity::Entity;
+use crate::ecs::event::Event;
+use crate::ecs::world::World;
+
+/// 「このカード (から上) を `target` に動かしたい」という入力。`MoveCardSystem` が処理する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveRequested {
+    pub entity: Entity,
+    pub target: StackType,
+}
+impl Event for MoveRequested {}
+
+/// 移動がルールチェックを通って World に反映された。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveApplied {
+    pub entity: Entity,
+    pub from: StackType,
+    pub to: StackType,
+    /// 一緒に動いた枚数 (`entity` を含む)。
+    pub count: usize,
+}
+impl Event for MoveApplied {}
+
+/// 移動がルール違反で却下された。カードは Layout ステージで元の位置に戻るよ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveRejected {
+    pub entity: Entity,
+    pub reason: String,
+}
+impl Event for MoveRejected {}
+
+/// 山札がクリックされた (1枚めくる or 捨て札を戻す、はシステムが決める)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StockClicked;
+impl Event for StockClicked {}
+
+/// 山札の操作が行われた。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StockCycled {
+    /// 山札から捨て札にめくった (中身は捨て札の一番上)。捨て札が無い遊び方なら、山札から場札に配った。
+    Drew(Entity),
+    /// 捨て札を全部山札に戻した。
+    Reset,
+}
+impl Event for StockCycled {}
+
+/// 「1つ前の操作を元に戻したい」という入力。`HistorySystem` が処理して、サーバーにも伝えるよ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoRequested;
+impl Event for UndoRequested {}
+
+/// 「元に戻した操作をやり直したい」という入力。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedoRequested;
+impl Event for RedoRequested {}
+
+/// 「残りのカードを全部自動で組札に上げて」という入力。`AutoCompleteSystem` が1枚ずつ `MoveRequested` にするよ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoCompleteRequested;
+impl Event for AutoCompleteRequested {}
+
+/// 場札の裏向きカードが表になった。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CardFlipped {
+    pub entity: Entity,
+}
+impl Event for CardFlipped {}
+
+/// ゲームに勝った！🏆
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameWon;
+impl Event for GameWon {}
+
+/// 上のイベントを全部 World に登録する。クライアントやテストハーネスの World を作る時に呼んでね。
+pub fn register_game_events(world: &mut World) {
+    world.add_event::<MoveRequested>();
+    world.add_event::<MoveApplied>();
+    world.add_event::<MoveRejected>();
+    world.add_event::<StockClicked>();
+    world.add_event::<StockCycled>();
+    world.add_event::<UndoRequested>();
+    world.add_event::<RedoRequested>();
+    world.add_event::<AutoCompleteRequested>();
+    world.add_event::<CardFlipped>();
+    world.add_event::<GameWon>();
+}
@@ -0,0 +1,167 @@
+// src/harness.rs
+//! ブラウザ無しでゲームの World を動かすためのハーネスだよ！🧪
+//!
+//! `GameApp::tick` と同じ `systems::build_schedule()` を、決まった dt で回す。
+//! 時計も乱数も使わないので、同じ操作をすれば何回やっても同じ結果になるよ👍
+//!
+//! システムを回さないルールや盤面のテスト用に、カードを並べるだけの `card_world` / `push_card` / `world_with` もあるよ。
+
+use crate::components::{Card, DraggingInfo, GameState, Player, Position, Rank, StackInfo, StackType, Suit};
+use crate::ecs::{entity::Entity, schedule::Schedule, world::World};
+use crate::events::register_game_events;
+use crate::logic::history::History;
+use crate::systems::{self, network_outbox_system::Outbox};
+
+/// 決定的に tick できる World + Schedule のセット。
+pub struct GameHarness {
+    pub world: World,
+    pub schedule: Schedule,
+}
+
+impl GameHarness {
+    /// 1フレームの長さ (60fps)。
+    pub const FRAME_SECONDS: f64 = 1.0 / 60.0;
+
+    /// クライアントと同じコンポーネントとイベントを登録した、空っぽの World で始める。
+    pub fn new() -> Self {
+        let mut world = World::new();
+        world.register_serializable_component::<Card>();
+        world.register_serializable_component::<Position>();
+        world.register_serializable_component::<StackInfo>();
+        world.register_component::<Player>();
+        world.register_component::<DraggingInfo>();
+        world.insert_resource(GameState::playing());
+        world.insert_resource(Outbox::default());
+        world.insert_resource(History::default());
+        register_game_events(&mut world);
+
+        Self { world, schedule: systems::build_schedule() }
+    }
+
+    /// 1フレーム (`FRAME_SECONDS`) 進める。
+    pub fn tick(&mut self) {
+        self.schedule.tick(&mut self.world, Self::FRAME_SECONDS);
+    }
+
+    /// 入力イベントを送る (次の tick で処理される)。
+    pub fn send<T: crate::ecs::event::Event>(&mut self, event: T) {
+        self.world.send_event(event);
+    }
+
+    /// サーバーに送られるはずだったメッセージを取り出す。
+    pub fn take_outgoing(&mut self) -> Vec<crate::protocol::ClientMessage> {
+        self.world.resource_mut::<Outbox>().map(|outbox| std::mem::take(&mut outbox.0)).unwrap_or_default()
+    }
+
+    /// `frames` フレームまとめて進める。
+    pub fn tick_frames(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.tick();
+        }
+    }
+}
+
+impl Default for GameHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// カード (Card, StackInfo, Position) と履歴だけの World。
+pub fn card_world() -> World {
+    let mut world = World::new();
+    world.register_serializable_component::<Card>();
+    world.register_serializable_component::<StackInfo>();
+    world.register_serializable_component::<Position>();
+    world.insert_resource(History::default());
+    world
+}
+
+/// `stack_type` の一番上にカードを1枚積む。
+pub fn push_card(world: &mut World, suit: Suit, rank: Rank, stack_type: StackType, is_face_up: bool) -> Entity {
+    let position_in_stack = world.stacks().len(stack_type) as u8;
+    let entity = world.create_entity();
+    world.add_component(entity, Card { suit, rank, is_face_up });
+    world.add_component(entity, StackInfo::new(stack_type, position_in_stack));
+    world.add_component(entity, Position { x: 0.0, y: 0.0 });
+    entity
+}
+
+/// (スート, ランク, 場所, 表向き?) を前から順に積んだ `card_world` と、そのエンティティ (同じ順番)。
+pub fn world_with(cards: &[(Suit, Rank, StackType, bool)]) -> (World, Vec<Entity>) {
+    let mut world = card_world();
+    let entities = cards
+        .iter()
+        .map(|&(suit, rank, stack_type, is_face_up)| push_card(&mut world, suit, rank, stack_type, is_face_up))
+        .collect();
+    (world, entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::GameStatus;
+    use crate::ecs::event::Events;
+    use crate::ecs::time::Time;
+    use crate::events::{GameWon, MoveRequested, StockClicked};
+    use crate::protocol::ClientMessage;
+    use crate::logic::deck::create_standard_deck;
+    use crate::logic::layout_calculator;
+
+    #[test]
+    fn ticking_a_finished_board_declares_the_win() {
+        let mut harness = GameHarness::new();
+        // 52枚を13枚ずつ組札に積んだ「クリア直前」の盤面
+        for (i, mut card) in create_standard_deck().into_iter().enumerate() {
+            card.is_face_up = true;
+            let entity = harness.world.create_entity();
+            harness.world.add_component(entity, card);
+            harness.world.add_component(entity, StackInfo::new(StackType::Foundation((i / 13) as u8), (i % 13) as u8));
+            harness.world.add_component(entity, Position { x: 0.0, y: 0.0 });
+        }
+
+        harness.tick();
+        assert_eq!(harness.world.resource::<GameState>().unwrap().status, GameStatus::Won);
+        assert_eq!(harness.world.resource::<Events<GameWon>>().unwrap().iter().count(), 1);
+
+        // 勝った後は何フレーム回しても GameWon は増えないし、古いイベントは消えていく
+        harness.tick_frames(2);
+        assert!(harness.world.resource::<Events<GameWon>>().unwrap().is_empty());
+        let time = harness.world.resource::<Time>().unwrap();
+        assert_eq!(time.frame, 3);
+        assert_eq!(time.elapsed_seconds, 3.0 * GameHarness::FRAME_SECONDS);
+        // Layout ステージも回ってるので、カードは組札の位置に揃ってる
+        let expected = layout_calculator::calculate_card_position(StackType::Foundation(3), 0, &harness.world);
+        assert!(harness.world.query::<&Position>().skip(39).all(|position| *position == expected));
+        println!("ハーネスで tick して勝利判定、成功！🏆");
+    }
+
+    #[test]
+    fn input_events_flow_through_logic_into_the_outbox() {
+        let mut harness = GameHarness::new();
+        let ace = push_card(&mut harness.world, Suit::Heart, Rank::Ace, StackType::Stock, false);
+
+        // 1フレーム目: 山札クリック → Ace が捨て札へ
+        harness.send(StockClicked);
+        harness.tick();
+        assert_eq!(harness.world.stacks().location(ace), Some((StackType::Waste, 0)));
+
+        // 2フレーム目: Ace を組札へ
+        harness.send(MoveRequested { entity: ace, target: StackType::Foundation(0) });
+        harness.tick();
+        assert_eq!(harness.world.stacks().location(ace), Some((StackType::Foundation(0), 0)));
+
+        let outgoing = harness.take_outgoing();
+        assert!(matches!(
+            outgoing.as_slice(),
+            [
+                ClientMessage::DrawFromStock,
+                ClientMessage::MakeMove { moved_entity, target_stack: StackType::Foundation(0) },
+            ] if *moved_entity == ace
+        ));
+        // 何も入力しなければ何も送らない
+        harness.tick_frames(2);
+        assert!(harness.take_outgoing().is_empty());
+        println!("入力 → ロジック → 送信キュー のテスト、成功！📮");
+    }
+}
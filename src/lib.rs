@@ -1,6 +1,7 @@
 // src/lib.rs
 
-// WASM と JavaScript を繋ぐための基本！
+// WASM と JavaScript を繋ぐための基本！ (ブラウザ版 = `web` feature の時だけ)
+#[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 // ★復活！ JsCast トレイトを使う！★
 // use wasm_bindgen::JsCast; // game_app.rs に移動
@@ -18,13 +19,23 @@ use wasm_bindgen::prelude::*;
 pub mod ecs;
 pub mod components;
 pub mod systems;
-pub mod network;
+pub mod events; // システム同士でやり取りするゲームのイベント
+#[cfg(feature = "web")]
+pub mod network; // ブラウザの WebSocket を使うので web 専用
 pub mod protocol;
 pub mod logic;
-pub mod app;
+#[cfg(feature = "web")]
+pub mod app; // DOM や Canvas を触るので web 専用
 pub mod config;
+pub mod server; // ネイティブサーバー用の権威ゲームセッション
+#[cfg(test)]
+mod harness; // ブラウザ無しで World を tick するテスト用ハーネス (テストの時だけ)
+pub mod persistence; // セーブ / ロード (ページを再読み込みしても続きから)
+#[cfg(feature = "web")]
+mod console_logger; // log クレートの出力をブラウザの console へ
 
 // ★追加: GameApp を lib.rs のスコープに公開！
+#[cfg(feature = "web")]
 pub use app::game_app::GameApp;
 
 // 各モジュールから必要な型をインポート！
@@ -57,6 +68,7 @@ pub use app::game_app::GameApp;
 // network と protocol 関連
 
 // JavaScript の console.log を Rust から呼び出すための準備 (extern ブロック)。
+#[cfg(feature = "web")]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
@@ -67,13 +79,23 @@ extern "C" {
 }
 
 // main 関数の代わりに、Wasm がロードされた時に最初に実行される関数だよ。
+#[cfg(feature = "web")]
 #[wasm_bindgen(start)]
 pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
+    console_logger::init();
     log("Panic hook set!");
 }
 
+// console に出すログのレベルを変える (`"debug"` とか)。JS からは `set_log_level("debug")`
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+pub fn set_log_level(level: &str) -> Result<(), JsValue> {
+    console_logger::set_level(level).map_err(|e| JsValue::from_str(&e))
+}
+
 // 簡単なテスト用の関数 (これはマルチプレイには直接関係ない)
+#[cfg(feature = "web")]
 #[wasm_bindgen]
 pub fn greet(name: &str) {
     log(&format!("Hello from Rust, {}!", name));
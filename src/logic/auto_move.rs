@@ -4,15 +4,15 @@
 
 // --- 必要なものをインポート ---
 // use crate::components::card::{Card, Suit, Rank}; // 関数内で Card, Suit, Rank は直接使わなくなったため不要
+use crate::components::card::Card; // オートコンプリートで表向きかどうかを見る
 use crate::components::stack::{StackType, /*StackInfo*/}; // StackInfo は使わないので削除
 use crate::ecs::entity::Entity; // components の Entity を使う
 use crate::ecs::world::World; // 自作 World を使うため
-use wasm_bindgen::JsValue; // ★ JsValue を使うために追加
-use web_sys::console;      // ★ console を使うために追加
+use log::debug; // ログは log クレート経由！ブラウザでもネイティブ (サーバー) でも動くよ
 // use crate::rules::can_move_to_foundation; // ⛔️ 古いパス！
 // use crate::logic::rules::can_move_to_foundation; // ✨ 新しいパスに修正！ rules モジュールは logic の下にお引越ししたよ！
-// ↑ rules モジュールの関数を直接使うので、use文を追加
-use crate::logic::rules;
+// ↑ 組札に置けるかどうかは、今の遊び方のルール (variants) に聞く
+use crate::logic::variants;
 
 // --- ヘルパー関数 (このモジュール内でのみ使用) ---
 // 不要になったので削除！ (get_foundation_suit と get_foundation_top_card)
@@ -23,6 +23,7 @@ use crate::logic::rules;
 /// 特定のカードエンティティが、現在のワールドの状態において、
 /// 自動的に移動できる組札（Foundation）があるかどうかを探す関数だよ。
 /// 見つかった場合は、移動先の StackType (Foundation のインデックス付き) を返す。
+/// どこを探すかは今の遊び方 (`RuleSet::auto_move_target`) が決めるよ。
 ///
 /// # 引数
 /// * `world`: 現在の World の状態への参照 (自作World)。
@@ -36,47 +37,58 @@ pub fn find_automatic_foundation_move(
     card_to_move_entity: Entity // 引数を &Card から Entity に変更！
 ) -> Option<StackType> {
     // どのカードをチェックしているか、Entity ID をログに出力するよ。
-    console::log_1(&JsValue::from_str(&format!("[AutoMove] Finding automatic foundation move for Entity {:?}...", card_to_move_entity)));
-
-    // 4つの Foundation (インデックス 0 から 3 まで) を順番にチェックするループだよ。
-    for i in 0..4u8 { // u8 型の 0 から 3 までループする。
-
-        // *** 修正点 ***
-        // 以前はここで移動先の Suit や Top Card を取得していたけど、
-        // 新しい `rules::can_move_to_foundation` が内部で全部やってくれるようになったので、
-        // それらのヘルパー呼び出しは削除！コードがスッキリ！✨
-
-        // 移動可能かチェック！
-        // `rules` モジュールにある `can_move_to_foundation` 関数を呼び出す。
-        // 引数には、world への参照、移動させたいカードの Entity ID、
-        // そしてチェック対象の Foundation のインデックス `i` を渡すよ。
-        if rules::can_move_to_foundation(world, card_to_move_entity, i) {
-            // 移動可能な Foundation が見つかった！🎉
-            // どの Foundation に移動できるかログに出力する。
-            console::log_1(&JsValue::from_str(&format!("  Found valid foundation [{}] for Entity {:?}.", i, card_to_move_entity)));
-            // 移動先の Foundation の StackType (例: StackType::Foundation(0)) を
-            // Option::Some で包んで返す。これで関数は終了するよ。
-            return Some(StackType::Foundation(i));
-        }
-        // もし↑の if が false なら、この Foundation には移動できないので、
-        // ループは次のインデックス (次の Foundation) に進むよ。
+    debug!("[AutoMove] Finding automatic foundation move for Entity {:?}...", card_to_move_entity);
+
+    let target = variants::active(world).auto_move_target(world, card_to_move_entity);
+    match target {
+        Some(target) => debug!("  Found valid target {:?} for Entity {:?}.", target, card_to_move_entity),
+        None => debug!("  No suitable foundation found for Entity {:?}.", card_to_move_entity),
+    }
+    target
+}
+
+/// 「あとは組札に上げていくだけ」の盤面？ (オートコンプリートできる？)
+/// 山札も捨て札も空っぽで、場札のカードが全部表向きなら、どの順番で上げても必ず最後まで行けるよ
+/// (そう言える遊び方 = `RuleSet::allows_auto_complete` の時だけ)。
+/// 場札にカードが1枚も無い (もう上がってる) 時は `false`。
+pub fn can_auto_complete(world: &World) -> bool {
+    let rule_set = variants::active(world);
+    if !rule_set.allows_auto_complete() {
+        return false;
+    }
+    let stacks = world.stacks();
+    if !stacks.is_empty(StackType::Stock) || !stacks.is_empty(StackType::Waste) {
+        return false;
     }
+    let mut tableau_cards = rule_set.kinds().tableaux().flat_map(|column| stacks.cards(column).iter().copied()).peekable();
+    tableau_cards.peek().is_some()
+        && tableau_cards.all(|entity| world.get_component::<Card>(entity).is_some_and(|card| card.is_face_up))
+}
 
-    // ループが最後まで終わっても、移動可能な Foundation が見つからなかった場合。
-    console::log_1(&JsValue::from_str(&format!("  No suitable foundation found for Entity {:?}.", card_to_move_entity)));
-    // Option::None を返して、移動先がなかったことを示すよ。
-    None
+/// オートコンプリートの次の1手。場札の一番上のカードのうち、組札に上げられる一番小さいランクのものを選ぶよ
+/// (同じランクなら左の列から)。上げられるカードが無ければ `None`。
+pub fn next_auto_complete_move(world: &World) -> Option<(Entity, StackType)> {
+    variants::active(world)
+        .kinds()
+        .tableaux()
+        .filter_map(|column| world.stacks().top(column))
+        .filter_map(|entity| {
+            let target = find_automatic_foundation_move(world, entity)?;
+            let rank = world.get_component::<Card>(entity)?.rank;
+            Some((rank, entity, target))
+        })
+        .min_by_key(|&(rank, _, _)| rank)
+        .map(|(_, entity, target)| (entity, target))
 }
 
 // --- テストコード (rules.rs から移動) ---
 #[cfg(test)]
 mod tests {
     use super::*; // このモジュール内の要素 (find_automatic_foundation_move など) を使う
-    use crate::world::World; // 自作World
+    use crate::ecs::world::World; // 自作World
     use crate::components::card::{Card, Suit, Rank}; // Card関連 (テストでは使う！)
     use crate::components::stack::{StackType, StackInfo}; // Stack関連 (テストでは StackInfo も使う！)
-    use crate::entity::Entity; // Entity を使う
-    use crate::logic::rules; // rules モジュールも使う (テストデータ作成などで)
+    use crate::ecs::entity::Entity; // Entity を使う
     // use crate::log; // log マクロは使わないので削除！
 
     // ヘルパー: テスト用の World に Foundation カードを追加する (仮)
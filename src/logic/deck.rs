@@ -1,8 +1,6 @@
 // src/logic/deck.rs
 
 use crate::components::card::{Card, /*Rank, Suit,*/ ALL_RANKS, ALL_SUITS};
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 
 /// 標準的な52枚のカードデッキ（ソリティア用）を生成する関数だよ！🃏
 ///
@@ -24,19 +22,73 @@ pub fn create_standard_deck() -> Vec<Card> {
     deck // 完成したデッキを返す！
 }
 
-/// カードデッキをシャッフルする関数だよ。
+/// シード付きの小さな乱数生成器 (SplitMix64) だよ！🎲
+///
+/// `rand` の `thread_rng` や `shuffle` はバージョンやプラットフォームで結果が変わりうるので、
+/// 配り方を再現したいところ (バグ報告、デイリーチャレンジ、マルチプレイ) ではこっちを使う。
+/// アルゴリズムは固定で、同じシードならどの環境・どのバージョンでも同じ数列になるよ。
+/// ⚠️ 出力を変えると過去のシードの配り方が全部変わっちゃうので、中身はいじらないでね！
+#[derive(Debug, Clone)]
+pub struct DealRng {
+    state: u64,
+}
+
+impl DealRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// 次の 64bit の乱数。
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// `0..bound` の一様な乱数 (`bound` は 1 以上)。偏りが出ないように、はみ出た分は引き直すよ。
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "DealRng::below needs a positive bound");
+        // u64 の範囲を bound で割り切れる所までに切って、その中に入るまで引き直す
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// シードを1つランダムに決める (シード指定なしで配る時用)。
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+/// カードデッキを `seed` で決まる順番にシャッフルする (Fisher-Yates)。
+/// 同じシードなら、いつでもどこでも同じ並びになるよ。
+pub fn shuffle_deck_with_seed(deck: &mut [Card], seed: u64) {
+    let mut rng = DealRng::new(seed);
+    for i in (1..deck.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        deck.swap(i, j);
+    }
+}
+
+/// カードデッキをシャッフルする関数だよ。(シードはランダム。再現したいなら `shuffle_deck_with_seed` を使ってね)
 ///
 /// # 引数
 /// * `deck` - シャッフルしたいカードデッキ (`Vec<Card>`) への可変参照。
-pub fn shuffle_deck(deck: &mut Vec<Card>) {
-    let mut rng = thread_rng(); // 乱数生成器を取得
-    deck.shuffle(&mut rng); // デッキをシャッフル！
+pub fn shuffle_deck(deck: &mut [Card]) {
+    shuffle_deck_with_seed(deck, random_seed());
 }
 
 // --- テスト (移動した関数のテストもこちらに移動) ---
 #[cfg(test)]
 mod tests {
     use super::*; // 上で定義した関数と、インポートした Card, Suit, Rank を使う
+    use crate::components::card::{Rank, Suit};
 
     #[test]
     fn deck_creation() {
@@ -100,4 +152,31 @@ mod tests {
         // サイズは変わらないはず
         assert_eq!(initial_deck.len(), shuffled_deck.len(), "シャッフルでカード数が変わった！");
     }
+
+    #[test]
+    fn deal_rng_matches_reference_splitmix64() {
+        // SplitMix64 (シード 0) の公開されてる出力と一致するはず
+        let mut rng = DealRng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+        assert_eq!(rng.next_u64(), 0x06C4_5D18_8009_454F);
+    }
+
+    #[test]
+    fn same_seed_gives_same_order() {
+        let mut first = create_standard_deck();
+        let mut second = create_standard_deck();
+        shuffle_deck_with_seed(&mut first, 20240501);
+        shuffle_deck_with_seed(&mut second, 20240501);
+        assert_eq!(first, second);
+
+        let mut other = create_standard_deck();
+        shuffle_deck_with_seed(&mut other, 20240502);
+        assert_ne!(first, other);
+
+        // アルゴリズムが変わってないことの確認 (変わったら過去のシードが再現できなくなる！)
+        let top: Vec<(Suit, Rank)> = first.iter().take(3).map(|card| (card.suit, card.rank)).collect();
+        assert_eq!(top, vec![(Suit::Diamond, Rank::Jack), (Suit::Club, Rank::Queen), (Suit::Club, Rank::Ace)]);
+        println!("シード付きシャッフルの再現テスト、成功！🎲");
+    }
 } 
\ No newline at end of file
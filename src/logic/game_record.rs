@@ -0,0 +1,169 @@
+// src/logic/game_record.rs
+//! 1ゲーム分の棋譜 (シード・遊び方・ルール・手の列) だよ！📜
+//!
+//! 配った時に `DealInitialCardsSystem` が新しい `GameRecord` を World に置いて、
+//! 遊んでる間は `GameRecordSystem` が手を (`logic::notation` の記法で、経過時間つきで) 足していく。
+//! JSON にして保存すれば、同じシードで配り直して同じ手を順番に入力するだけで、盤面がそのまま再現できるよ。
+//! (スクショじゃなくて棋譜でバグ報告してもらうためのもの🐛)
+//!
+//! ```json
+//! {"version":1,"seed":42,"variant":"Klondike","rules":{"draw_count":1,...},"scoring":"Standard",
+//!  "moves":[{"at":0.0,"move":"S"},{"at":2.5,"move":"W->T5"}]}
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::score::ScoringMode;
+use crate::config::GameRules;
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::resource::Resource;
+use crate::ecs::schedule::Schedule;
+use crate::ecs::world::World;
+use crate::events::{MoveRejected, MoveRequested, RedoRequested, StockClicked, UndoRequested};
+use crate::logic::notation::Move;
+use crate::logic::variants::Variant;
+
+/// 今の棋譜の形式。形式を変えたら上げてね (古い棋譜を読めるかは `from_json` で決める)。
+pub const RECORD_VERSION: u32 = 1;
+
+/// 記録された1手。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMove {
+    /// 最初の1手からの経過秒数 (`GameClock`)。
+    pub at: f64,
+    #[serde(rename = "move")]
+    pub play: Move,
+}
+
+/// 1ゲーム分の棋譜。World にリソースとして置いておくと `GameRecordSystem` が手を足していくよ。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub version: u32,
+    pub seed: u64,
+    pub variant: Variant,
+    pub rules: GameRules,
+    pub scoring: ScoringMode,
+    pub moves: Vec<RecordedMove>,
+}
+
+impl Resource for GameRecord {}
+
+impl GameRecord {
+    /// まだ1手も指してない棋譜。
+    pub fn new(seed: u64, variant: Variant, rules: GameRules, scoring: ScoringMode) -> Self {
+        Self { version: RECORD_VERSION, seed, variant, rules, scoring, moves: Vec::new() }
+    }
+
+    /// 手を1つ足す。
+    pub fn push(&mut self, at: f64, play: Move) {
+        self.moves.push(RecordedMove { at, play });
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("Failed to serialize the game record: {}", e))
+    }
+
+    /// JSON から読む。知らない形式 (新しすぎる棋譜) ならエラー。
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let record: GameRecord =
+            serde_json::from_str(json).map_err(|e| format!("Failed to read the game record: {}", e))?;
+        if record.version != RECORD_VERSION {
+            return Err(format!("Unsupported game record version {} (expected {})", record.version, RECORD_VERSION));
+        }
+        Ok(record)
+    }
+}
+
+/// `play` をプレイヤーの入力と同じイベントにして送る (次の tick で処理される)。
+/// 掴むカードが無ければエラー。
+pub fn send_input(world: &mut World, play: Move) -> Result<(), String> {
+    match play {
+        Move::Stock => world.send_event(StockClicked),
+        Move::Undo => world.send_event(UndoRequested),
+        Move::Redo => world.send_event(RedoRequested),
+        Move::Cards { from, to, .. } => {
+            let entity = play.entity(world).ok_or_else(|| format!("{:?} does not have enough cards for {}", from, play))?;
+            world.send_event(MoveRequested { entity, target: to });
+        }
+    }
+    Ok(())
+}
+
+/// 配り終わった World で `moves` を1手ずつ入力して、1手ごとに `schedule` を1回 tick する。
+/// dt には記録された時間の差を使うので、`GameClock` も記録と同じくらい進むよ。
+/// どこかの手がルールで却下されたら (= 棋譜と盤面がずれた)、そこで止めてエラー。
+pub fn replay(world: &mut World, schedule: &mut Schedule, moves: &[RecordedMove]) -> Result<(), String> {
+    let mut rejections = EventCursor::<MoveRejected>::default();
+    if let Some(events) = world.resource::<Events<MoveRejected>>() {
+        rejections.read(events).for_each(drop); // 前から溜まってる分は関係ないので読み飛ばす
+    }
+    let mut last_at = 0.0;
+    for (number, recorded) in moves.iter().enumerate() {
+        let play = recorded.play;
+        send_input(world, play).map_err(|e| format!("Move {} ({}): {}", number + 1, play, e))?;
+        schedule.tick(world, (recorded.at - last_at).max(0.0));
+        last_at = recorded.at;
+        if let Some(rejected) = world.resource::<Events<MoveRejected>>().and_then(|events| rejections.read(events).next()) {
+            return Err(format!("Move {} ({}) was rejected: {}", number + 1, play, rejected.reason));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Card, StackInfo, StackType};
+    use crate::harness::GameHarness;
+    use crate::logic::hints::{self, HintMove};
+    use crate::systems::deal_system::DealInitialCardsSystem;
+
+    /// カードごとの (場所, 向き)。2つの World が同じ盤面か比べる用。
+    fn board(world: &World) -> Vec<(StackType, u8, bool)> {
+        world
+            .query::<(&StackInfo, &Card)>()
+            .map(|(stack_info, card)| (stack_info.stack_type, stack_info.position_in_stack, card.is_face_up))
+            .collect()
+    }
+
+    #[test]
+    fn a_recorded_game_replays_to_the_same_board() {
+        let mut played = GameHarness::new();
+        DealInitialCardsSystem.execute(&mut played.world, 2024);
+        // ヒントの通りに何手か指して、途中で undo / redo も挟む
+        for turn in 0..20 {
+            match hints::best_hint(&played.world).map(|hint| hint.action) {
+                Some(HintMove::Move { entity, to, .. }) => played.send(MoveRequested { entity, target: to }),
+                Some(_) => played.send(StockClicked),
+                None => break,
+            }
+            if turn == 5 {
+                played.send(UndoRequested);
+            }
+            if turn == 6 {
+                played.send(RedoRequested);
+            }
+            played.tick_frames(3);
+        }
+
+        let json = played.world.resource::<GameRecord>().unwrap().to_json().unwrap();
+        let record = GameRecord::from_json(&json).unwrap();
+        assert_eq!(record.seed, 2024);
+        assert!(record.moves.len() > 10);
+        assert!(record.moves.iter().any(|recorded| recorded.play == Move::Undo));
+
+        let mut replayed = GameHarness::new();
+        replayed.world.insert_resource(record.variant);
+        replayed.world.insert_resource(record.rules);
+        DealInitialCardsSystem.execute(&mut replayed.world, record.seed);
+        replay(&mut replayed.world, &mut replayed.schedule, &record.moves).unwrap();
+        assert_eq!(board(&replayed.world), board(&played.world));
+        assert_eq!(replayed.world.resource::<GameRecord>().unwrap().moves.len(), record.moves.len());
+
+        // 新しすぎる棋譜と、盤面と合わない手はエラー
+        assert!(GameRecord::from_json(&json.replace("\"version\":1", "\"version\":2")).is_err());
+        let bogus = [RecordedMove { at: 0.0, play: "F1->T1".parse().unwrap() }];
+        assert!(replay(&mut replayed.world, &mut replayed.schedule, &bogus).is_err());
+        println!("棋譜の保存と再生テスト、成功！📜");
+    }
+}
@@ -0,0 +1,255 @@
+// src/logic/hints.rs
+//! ヒント機能だよ！💡 今の盤面で指せる手を全部並べて、良さそうな順に点数をつける。
+//!
+//! `auto_move::find_automatic_foundation_move` は「このカード1枚を組札に上げられるか」しか見ないけど、
+//! こっちは盤面全体を見るよ:
+//! - 場札 → 場札 (上に積まれてるカードも一緒に)
+//! - 捨て札 → 場札 / 組札
+//! - 場札の一番上 → 組札
+//! - 組札 → 場札
+//! - フリーセル → 場札 / 組札、場札の一番上 → 空いてるフリーセル
+//! - 山札をめくる / 捨て札を山札に戻す
+//!
+//! 見るスタックは今の遊び方 (`variants::active`) が使うものだけだよ。
+//!
+//! 移動できるかどうかは全部 `rules::validate_move` で判定するので、ルールと食い違うことはないよ。
+//! ソルバー (`logic::solver`) と違って裏向きのカードは見ないので、プレイヤーに見せてもズルにならない！
+
+use serde::Serialize;
+
+use crate::components::{Card, GameState, StackInfo, StackType};
+use crate::config::GameRules;
+use crate::ecs::entity::Entity;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+use crate::logic::rules;
+use crate::logic::variants;
+
+/// ヒントで提案する1手。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum HintMove {
+    /// `entity` (場札ならその上のカードも一緒に) を `from` から `to` へ動かす。
+    Move { entity: Entity, from: StackType, to: StackType },
+    /// 山札をめくる (枚数は `GameRules::draw_count`)。
+    DrawFromStock,
+    /// 空の山札に捨て札を戻す。
+    RecycleWaste,
+}
+
+/// 点数つきのヒント。点数が大きいほどおすすめ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Hint {
+    pub action: HintMove,
+    pub score: i32,
+}
+
+impl Hint {
+    /// 描画で光らせる移動元。
+    pub fn source(&self) -> StackType {
+        match self.action {
+            HintMove::Move { from, .. } => from,
+            HintMove::DrawFromStock => StackType::Stock,
+            HintMove::RecycleWaste => StackType::Waste,
+        }
+    }
+
+    /// 描画で光らせる移動先。
+    pub fn target(&self) -> StackType {
+        match self.action {
+            HintMove::Move { to, .. } => to,
+            HintMove::DrawFromStock => StackType::Waste,
+            HintMove::RecycleWaste => StackType::Stock,
+        }
+    }
+}
+
+/// 今表示してるヒント。`GameApp::get_hint` が置いて、レンダラーが移動元と移動先を光らせる。
+#[derive(Debug, Clone, Copy)]
+pub struct ActiveHint {
+    pub hint: Hint,
+    /// ヒントを出した時の change tick。これより後にカードが動いたら古いヒントだよ。
+    pub since: u64,
+}
+
+impl Resource for ActiveHint {}
+
+impl ActiveHint {
+    /// ヒントを出した後で盤面 (カードの場所・向き) が変わった？
+    pub fn is_stale(&self, world: &World) -> bool {
+        !world.changed_since::<StackInfo>(self.since).is_empty()
+            || !world.changed_since::<Card>(self.since).is_empty()
+            || !world.removed_since::<Card>(self.since).is_empty()
+    }
+}
+
+// --- 点数 (大きいほど先に出す) ---
+/// 組札に上げる手。ゴールに一番近い！
+const SCORE_TO_FOUNDATION: i32 = 100;
+/// 裏向きのカードをめくれる場札の移動。下に残ってる裏向きの枚数だけ加点する。
+const SCORE_REVEAL: i32 = 80;
+/// 場札の列を丸ごと動かして、空の列を作る手。
+const SCORE_EMPTY_COLUMN: i32 = 60;
+/// 捨て札を場札に出す手。
+const SCORE_WASTE_TO_TABLEAU: i32 = 50;
+/// 山札をめくる / 戻す。
+const SCORE_DRAW: i32 = 20;
+const SCORE_RECYCLE: i32 = 15;
+/// 組札から場札に戻す手。
+const SCORE_FROM_FOUNDATION: i32 = 10;
+/// フリーセルから場札に戻す手。
+const SCORE_FROM_FREE_CELL: i32 = 40;
+/// 何もめくれない場札同士の移動 (ルール上はできるけど、たいてい意味がない)。
+const SCORE_SHUFFLE_TABLEAU: i32 = 5;
+/// 場札の一番上をフリーセルに逃がす手。空きが減るので最後の手段。
+const SCORE_TO_FREE_CELL: i32 = 1;
+
+/// 今の盤面で指せる手を全部、おすすめ順 (点数の高い順) に返す。
+pub fn legal_moves(world: &World) -> Vec<Hint> {
+    let stacks = world.stacks();
+    let kinds = variants::active(world).kinds();
+    let tableaux = kinds.tableaux();
+    let foundations = kinds.foundations();
+    let is_face_up = |entity: Entity| world.get_component::<Card>(entity).is_some_and(|card| card.is_face_up);
+    let mut hints = Vec::new();
+    let mut push = |entity: Entity, from: StackType, to: StackType, score: i32| {
+        if rules::validate_move(world, entity, to).is_ok() {
+            hints.push(Hint { action: HintMove::Move { entity, from, to }, score });
+        }
+    };
+
+    for from in tableaux.clone() {
+        let cards = stacks.cards(from);
+        // 組札に上げられるのは一番上の1枚だけ
+        if let Some(&top) = cards.last() {
+            let reveals = cards.len() >= 2 && !is_face_up(cards[cards.len() - 2]);
+            for to in foundations.clone() {
+                push(top, from, to, SCORE_TO_FOUNDATION + if reveals { 1 } else { 0 });
+            }
+        }
+        for (index, &entity) in cards.iter().enumerate().filter(|&(_, &entity)| is_face_up(entity)) {
+            let face_down_below = cards[..index].iter().filter(|&&below| !is_face_up(below)).count();
+            for to in tableaux.clone().filter(|&to| to != from) {
+                let to_empty = stacks.is_empty(to);
+                if index == 0 && to_empty {
+                    continue; // 列ごと空の列に移しても何も変わらない
+                }
+                let score = if index > 0 && !is_face_up(cards[index - 1]) {
+                    SCORE_REVEAL + face_down_below as i32
+                } else if index == 0 {
+                    SCORE_EMPTY_COLUMN
+                } else {
+                    SCORE_SHUFFLE_TABLEAU
+                };
+                push(entity, from, to, score);
+            }
+        }
+    }
+
+    if let Some(top) = stacks.top(StackType::Waste) {
+        for to in foundations.clone() {
+            push(top, StackType::Waste, to, SCORE_TO_FOUNDATION);
+        }
+        for to in tableaux.clone() {
+            push(top, StackType::Waste, to, SCORE_WASTE_TO_TABLEAU);
+        }
+    }
+
+    for from in kinds.free_cells() {
+        let Some(top) = stacks.top(from) else { continue };
+        for to in foundations.clone() {
+            push(top, from, to, SCORE_TO_FOUNDATION);
+        }
+        for to in tableaux.clone() {
+            push(top, from, to, SCORE_FROM_FREE_CELL);
+        }
+    }
+
+    // 空いてるフリーセルはどれも同じなので、最初の1つだけ提案する
+    if let Some(cell) = kinds.free_cells().find(|&cell| stacks.is_empty(cell)) {
+        for from in tableaux.clone() {
+            if let Some(top) = stacks.top(from) {
+                push(top, from, cell, SCORE_TO_FREE_CELL);
+            }
+        }
+    }
+
+    for from in foundations {
+        let Some(top) = stacks.top(from) else { continue };
+        for to in tableaux.clone() {
+            push(top, from, to, SCORE_FROM_FOUNDATION);
+        }
+    }
+
+    if kinds.stock {
+        let stock_is_empty = stacks.is_empty(StackType::Stock);
+        let recycles_done = world.resource::<GameState>().map_or(0, |state| state.stock_recycles);
+        if rules::can_deal_from_stock(stock_is_empty) {
+            hints.push(Hint { action: HintMove::DrawFromStock, score: SCORE_DRAW });
+        } else if rules::can_reset_stock_from_waste(
+            stock_is_empty,
+            stacks.is_empty(StackType::Waste),
+            recycles_done,
+            &GameRules::from_world(world),
+        ) {
+            hints.push(Hint { action: HintMove::RecycleWaste, score: SCORE_RECYCLE });
+        }
+    }
+
+    // 同点なら上で見つけた順 (場札 → 捨て札 → フリーセル → 組札 → 山札) のまま
+    hints.sort_by_key(|hint| std::cmp::Reverse(hint.score));
+    hints
+}
+
+/// 一番おすすめの手。指せる手が1つも無ければ `None`。
+pub fn best_hint(world: &World) -> Option<Hint> {
+    legal_moves(world).into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Rank, Suit};
+    use crate::harness::world_with;
+
+    #[test]
+    fn lists_every_kind_of_move_best_first() {
+        let (world, cards) = world_with(&[
+            (Suit::Spade, Rank::Ace, StackType::Foundation(3), true),
+            (Suit::Club, Rank::Nine, StackType::Tableau(0), false),
+            (Suit::Heart, Rank::Seven, StackType::Tableau(0), true),
+            (Suit::Club, Rank::Eight, StackType::Tableau(1), true),
+            (Suit::Spade, Rank::Two, StackType::Waste, true),
+            (Suit::Diamond, Rank::King, StackType::Tableau(2), true),
+            (Suit::Heart, Rank::Three, StackType::Stock, false),
+        ]);
+        let hints = legal_moves(&world);
+        let actions: Vec<HintMove> = hints.iter().map(|hint| hint.action).collect();
+
+        assert_eq!(actions, vec![
+            // 2♠ を組札へ
+            HintMove::Move { entity: cards[4], from: StackType::Waste, to: StackType::Foundation(3) },
+            // 7♥ を 8♣ へ動かすと、裏向きの 9♣ がめくれる
+            HintMove::Move { entity: cards[2], from: StackType::Tableau(0), to: StackType::Tableau(1) },
+            HintMove::DrawFromStock,
+            // A♠ は空の列には置けない (キングだけ) ので組札から戻せる手は無し。
+            // K♦ だけの列を空の列に移す手も意味がないので出さない。
+        ]);
+        assert_eq!(best_hint(&world).map(|hint| hint.target()), Some(StackType::Foundation(3)));
+        println!("ヒントの一覧と並び順テスト、成功！💡");
+    }
+
+    #[test]
+    fn recycle_when_stock_is_empty_and_stale_after_a_move() {
+        let (mut world, cards) = world_with(&[(Suit::Heart, Rank::Five, StackType::Waste, true)]);
+        let hint = best_hint(&world).expect("recycling the waste is always possible");
+        assert_eq!(hint.action, HintMove::RecycleWaste);
+        assert_eq!((hint.source(), hint.target()), (StackType::Waste, StackType::Stock));
+
+        let active = ActiveHint { hint, since: world.increment_change_tick() };
+        assert!(!active.is_stale(&world));
+        world.move_run(StackType::Waste, 0, StackType::Stock);
+        assert!(active.is_stale(&world));
+        assert!(world.get_component::<Card>(cards[0]).is_some());
+        println!("山札を戻すヒント & 古いヒントの判定テスト、成功！🎉");
+    }
+}
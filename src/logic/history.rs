@@ -0,0 +1,341 @@
+// src/logic/history.rs
+//! カード操作の「元に戻す / やり直す」(undo / redo) のための履歴だよ！⏪⏩
+//!
+//! World を直接いじる代わりに、操作を `GameCommand` (元に戻せる1手) として組み立てて `execute` すると、
+//! World に `History` リソースが置いてあればそこに記録される。
+//! `MoveCardSystem::process_move` や `stock_handler` はみんなこれを通るので、
+//! クライアントでもサーバーでも、普通にプレイするだけで履歴が溜まっていくよ。
+//!
+//! 1回の操作 (例: カードを動かして、下の裏向きカードをめくる) は `GameCommand` の列としてまとめて記録されて、
+//! `undo` / `redo` もその単位で行われる。
+//! その1回分でついた点数 (`logic::scoring`) も一緒に覚えておいて、undo で取り消し、redo でもう一度つけるよ。
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::score::Score;
+use crate::components::{Card, GameState, Position, StackType};
+use crate::ecs::entity::Entity;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+use crate::logic::layout_calculator;
+use crate::logic::variants;
+use log::{debug, warn};
+
+/// 元に戻せる、World への操作1つ分。
+/// 実行した直後の World に対してなら、`revert` でぴったり元の状態に戻るよ。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameCommand {
+    /// `from` の `index` 番目から上の `count` 枚を、そのまま `to` の一番上に移す。
+    MoveRun { from: StackType, index: usize, to: StackType, count: usize },
+    /// カードを `face_up` の向きにする (元の向きはその逆)。
+    Flip { entity: Entity, face_up: bool },
+    /// 山札の上から `count` 枚を1枚ずつ表向きで捨て札に置く (3枚めくりなら 3)。
+    DrawFromStock { count: usize },
+    /// 捨て札の `count` 枚を全部、裏向きで山札に戻す。`GameState::stock_recycles` も1つ増える。
+    RecycleWaste { count: usize },
+}
+
+impl GameCommand {
+    /// 操作を World に反映する。
+    pub fn apply(&self, world: &mut World) {
+        match *self {
+            GameCommand::MoveRun { from, index, to, .. } => {
+                let moved = world.move_run(from, index, to);
+                snap_to_stack(world, &moved);
+            }
+            GameCommand::Flip { entity, face_up } => set_face_up(world, &[entity], face_up),
+            GameCommand::DrawFromStock { count } => {
+                // 1枚ずつ移すので、めくった最後のカード (山札の上から count 枚目) が捨て札の一番上になる
+                for _ in 0..count {
+                    let stock_len = world.stacks().len(StackType::Stock);
+                    let Some(top) = stock_len.checked_sub(1) else {
+                        warn!("[History] DrawFromStock applied to an empty stock.");
+                        return;
+                    };
+                    let drawn = world.move_run(StackType::Stock, top, StackType::Waste);
+                    set_face_up(world, &drawn, true);
+                    snap_to_stack(world, &drawn);
+                }
+            }
+            GameCommand::RecycleWaste { .. } => {
+                let recycled = world.move_run(StackType::Waste, 0, StackType::Stock);
+                set_face_up(world, &recycled, false);
+                snap_to_stack(world, &recycled);
+                if let Some(state) = world.resource_mut::<GameState>() {
+                    state.stock_recycles += 1;
+                }
+            }
+        }
+    }
+
+    /// `apply` した分を元に戻す。
+    pub fn revert(&self, world: &mut World) {
+        match *self {
+            GameCommand::MoveRun { from, to, count, .. } => {
+                let moved = move_top(world, to, count, from);
+                snap_to_stack(world, &moved);
+            }
+            GameCommand::Flip { entity, face_up } => set_face_up(world, &[entity], !face_up),
+            GameCommand::DrawFromStock { count } => {
+                // めくった時と逆順に1枚ずつ戻す
+                for _ in 0..count {
+                    let returned = move_top(world, StackType::Waste, 1, StackType::Stock);
+                    set_face_up(world, &returned, false);
+                    snap_to_stack(world, &returned);
+                }
+            }
+            GameCommand::RecycleWaste { count } => {
+                let returned = move_top(world, StackType::Stock, count, StackType::Waste);
+                set_face_up(world, &returned, true);
+                snap_to_stack(world, &returned);
+                if let Some(state) = world.resource_mut::<GameState>() {
+                    state.stock_recycles = state.stock_recycles.saturating_sub(1);
+                }
+            }
+        }
+    }
+}
+
+/// 1回分の操作と、それでついた点数。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Step {
+    commands: Vec<GameCommand>,
+    /// この操作で実際に増えた (減った) 点数。スタンダードの0点止まりで減らなかった分は入らない。
+    points: i32,
+}
+
+/// 実行した操作の記録。World にリソースとして置いておくと `execute` が勝手に記録してくれるよ。
+/// セーブデータ (`persistence`) にもそのまま入る。
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    /// 実行済みの操作 (1回分ずつ)。最後が一番新しい。
+    done: Vec<Step>,
+    /// undo した操作。redo するとここから取り出す。新しい操作をしたら空になる。
+    undone: Vec<Step>,
+}
+
+impl Resource for History {}
+
+impl History {
+    /// 1回分の操作を記録する。redo できる操作は捨てる (歴史が分岐しちゃうので)。
+    pub fn record(&mut self, commands: Vec<GameCommand>) {
+        if commands.is_empty() {
+            return;
+        }
+        self.done.push(Step { commands, points: 0 });
+        self.undone.clear();
+    }
+
+    /// 一番新しい1回分に、それでついた点数を足しておく (`logic::scoring` が呼ぶ)。undo するとこの分が引かれる。
+    pub fn add_points(&mut self, points: i32) {
+        if let Some(step) = self.done.last_mut() {
+            step.points += points;
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// 記録を全部捨てる (World が履歴の外で書き換えられた時とか)。
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+
+    /// 記録の中のエンティティを付け替える (セーブデータを読み込んで、カードが別のエンティティになった時)。
+    /// `map` で付け替え先が見つからないカードがあったら、記録は全部捨てて `false`。
+    pub fn remap_entities(&mut self, map: impl Fn(Entity) -> Option<Entity>) -> bool {
+        for command in self.done.iter_mut().chain(self.undone.iter_mut()).flat_map(|step| &mut step.commands) {
+            if let GameCommand::Flip { entity, .. } = command {
+                match map(*entity) {
+                    Some(mapped) => *entity = mapped,
+                    None => {
+                        self.clear();
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// 操作を順番に World に反映して、`History` リソースがあれば1回分として記録する。
+/// 遊び方に続きの操作 (`RuleSet::follow_up`、スパイダーの揃った列を組札へ、とか) があれば、それも同じ1回分に含めるよ。
+pub fn execute(world: &mut World, mut commands: Vec<GameCommand>) {
+    for command in &commands {
+        debug!("[History] Applying {:?}", command);
+        command.apply(world);
+    }
+    loop {
+        let follow_up = variants::active(world).follow_up(world);
+        if follow_up.is_empty() {
+            break;
+        }
+        for command in &follow_up {
+            debug!("[History] Applying follow-up {:?}", command);
+            command.apply(world);
+        }
+        commands.extend(follow_up);
+    }
+    if let Some(history) = world.resource_mut::<History>() {
+        history.record(commands);
+    }
+}
+
+/// 一番新しい操作を1回分元に戻す。戻すものが無ければ `false`。
+pub fn undo(world: &mut World) -> bool {
+    let Some(step) = world.resource_mut::<History>().and_then(|history| history.done.pop()) else {
+        return false;
+    };
+    for command in step.commands.iter().rev() {
+        debug!("[History] Reverting {:?}", command);
+        command.revert(world);
+    }
+    add_score(world, -step.points);
+    if let Some(history) = world.resource_mut::<History>() {
+        history.undone.push(step);
+    }
+    true
+}
+
+/// 最後に undo した操作をやり直す。やり直すものが無ければ `false`。
+pub fn redo(world: &mut World) -> bool {
+    let Some(step) = world.resource_mut::<History>().and_then(|history| history.undone.pop()) else {
+        return false;
+    };
+    for command in &step.commands {
+        debug!("[History] Reapplying {:?}", command);
+        command.apply(world);
+    }
+    add_score(world, step.points);
+    if let Some(history) = world.resource_mut::<History>() {
+        history.done.push(step);
+    }
+    true
+}
+
+/// undo / redo した1回分の点数を `Score` に戻す (足す)。
+fn add_score(world: &mut World, points: i32) {
+    if points == 0 {
+        return;
+    }
+    if let Some(score) = world.resource_mut::<Score>() {
+        score.add(points);
+        debug!("[History] {:+} points for undo/redo -> {}", points, score.points);
+    }
+}
+
+/// `from` の上から `count` 枚を `to` に移す (`revert` 用)。
+fn move_top(world: &mut World, from: StackType, count: usize, to: StackType) -> Vec<Entity> {
+    let len = world.stacks().len(from);
+    if count > len {
+        warn!("[History] Cannot take {} card(s) from {:?}, it only has {}.", count, from, len);
+        return Vec::new();
+    }
+    world.move_run(from, len - count, to)
+}
+
+fn set_face_up(world: &mut World, entities: &[Entity], face_up: bool) {
+    for &entity in entities {
+        if let Some(card) = world.get_component_mut::<Card>(entity) {
+            card.is_face_up = face_up;
+        }
+    }
+}
+
+/// カードの Position を、今いるスタックの中の場所に合わせる。
+fn snap_to_stack(world: &mut World, entities: &[Entity]) {
+    for &entity in entities {
+        let Some((stack_type, index)) = world.stacks().location(entity) else { continue };
+        let position = layout_calculator::calculate_card_position(stack_type, index as u8, world);
+        if let Some(current) = world.get_component_mut::<Position>(entity) {
+            *current = position;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Rank, Suit};
+    use crate::harness::world_with;
+
+    fn snapshot(world: &World) -> Vec<(Entity, StackType, usize, bool)> {
+        world
+            .query::<(Entity, &Card)>()
+            .map(|(entity, card)| {
+                let (stack_type, index) = world.stacks().location(entity).unwrap();
+                (entity, stack_type, index, card.is_face_up)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn move_and_flip_undo_and_redo_as_one_step() {
+        let (mut world, cards) = world_with(&[
+            (Suit::Spade, Rank::Two, StackType::Tableau(0), false),
+            (Suit::Spade, Rank::Ace, StackType::Tableau(0), true),
+        ]);
+        let before = snapshot(&world);
+
+        execute(&mut world, vec![
+            GameCommand::MoveRun { from: StackType::Tableau(0), index: 1, to: StackType::Foundation(0), count: 1 },
+            GameCommand::Flip { entity: cards[0], face_up: true },
+        ]);
+        let after = snapshot(&world);
+        assert_eq!(world.stacks().location(cards[1]), Some((StackType::Foundation(0), 0)));
+        assert!(world.get_component::<Card>(cards[0]).unwrap().is_face_up);
+
+        assert!(undo(&mut world));
+        assert_eq!(snapshot(&world), before);
+        assert!(!undo(&mut world));
+
+        assert!(redo(&mut world));
+        assert_eq!(snapshot(&world), after);
+        assert!(!redo(&mut world));
+        println!("移動 + めくりの undo/redo テスト、成功！⏪");
+    }
+
+    #[test]
+    fn stock_draw_and_recycle_round_trip() {
+        let (mut world, _) = world_with(&[
+            (Suit::Spade, Rank::Three, StackType::Stock, false),
+            (Suit::Spade, Rank::Four, StackType::Stock, false),
+        ]);
+        let start = snapshot(&world);
+
+        execute(&mut world, vec![GameCommand::DrawFromStock { count: 1 }]);
+        execute(&mut world, vec![GameCommand::DrawFromStock { count: 1 }]);
+        let drawn = snapshot(&world);
+        world.insert_resource(GameState::playing());
+        execute(&mut world, vec![GameCommand::RecycleWaste { count: 2 }]);
+        assert!(world.stacks().is_empty(StackType::Waste));
+        assert_eq!(world.resource::<GameState>().unwrap().stock_recycles, 1);
+
+        assert!(undo(&mut world));
+        assert_eq!(snapshot(&world), drawn);
+        assert_eq!(world.resource::<GameState>().unwrap().stock_recycles, 0);
+        assert!(undo(&mut world) && undo(&mut world));
+        assert_eq!(snapshot(&world), start);
+        println!("山札の undo テスト、成功！🃏");
+    }
+
+    #[test]
+    fn new_command_discards_redo() {
+        let (mut world, _) = world_with(&[(Suit::Spade, Rank::Five, StackType::Stock, false)]);
+        execute(&mut world, vec![GameCommand::DrawFromStock { count: 1 }]);
+        assert!(undo(&mut world));
+        assert!(world.resource::<History>().unwrap().can_redo());
+
+        execute(&mut world, vec![GameCommand::DrawFromStock { count: 1 }]);
+        assert!(!world.resource::<History>().unwrap().can_redo());
+        assert!(!redo(&mut world));
+        println!("新しい操作で redo が消えるテスト、成功！🎉");
+    }
+}
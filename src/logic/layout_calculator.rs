@@ -0,0 +1,71 @@
+// src/logic/layout_calculator.rs
+//! Calculates the drawing position of cards in different stacks.
+
+use crate::ecs::world::World; // World が必要
+use crate::components::{Card, Position, StackType};
+use crate::config::GameRules; // 捨て札をずらす枚数
+// ★ 削除: 未使用のモジュールインポート ★
+// use crate::config::layout;
+use crate::config::layout::{
+    TABLEAU_START_X, TABLEAU_START_Y,
+    FOUNDATION_START_X, FOUNDATION_START_Y,
+    STOCK_POS_X, STOCK_POS_Y, WASTE_POS_X, WASTE_POS_Y, WASTE_FAN_X_OFFSET,
+    TABLEAU_Y_OFFSET_FACE_DOWN, TABLEAU_Y_OFFSET_FACE_UP,
+    FOUNDATION_X_OFFSET,
+    TABLEAU_X_OFFSET,
+    FREECELL_START_X, FREECELL_START_Y, FREECELL_X_OFFSET,
+};
+
+/// スタックタイプとスタック内での順序に基づいて、カードの描画位置を計算します。
+/// (元々は GameApp::update_world_and_notify_server 内にあったロジック)
+///
+/// # 引数
+/// * `stack_type`: カードが属するスタックのタイプ。
+/// * `position_in_stack`: スタック内でのカードの順序 (0から始まる)。
+/// * `world`: World への参照 (必要に応じてスタックの他のカード情報を参照するため)。
+///
+/// # 戻り値
+/// * 計算されたカードの `Position`。
+pub fn calculate_card_position(
+    stack_type: StackType,
+    position_in_stack: u8,
+    world: &World, // World を参照で受け取る
+) -> Position {
+    match stack_type {
+        StackType::Stock => Position { x: STOCK_POS_X, y: STOCK_POS_Y },
+        StackType::Waste => {
+            // 上から `waste_fan` 枚だけ右にずらして並べる (3枚めくりで、めくった3枚が見えるように)。
+            // それより下のカードは一番左に重ねておく
+            let fan = GameRules::from_world(world).waste_fan.max(1) as usize;
+            let first_fanned = world.stacks().len(StackType::Waste).saturating_sub(fan);
+            let slot = (position_in_stack as usize).saturating_sub(first_fanned).min(fan - 1);
+            Position { x: WASTE_POS_X + WASTE_FAN_X_OFFSET * slot as f32, y: WASTE_POS_Y }
+        }
+        StackType::Foundation(index) => {
+            let base_x = FOUNDATION_START_X + FOUNDATION_X_OFFSET * index as f32;
+            Position { x: base_x, y: FOUNDATION_START_Y }
+        }
+        StackType::FreeCell(index) => {
+            Position { x: FREECELL_START_X + FREECELL_X_OFFSET * index as f32, y: FREECELL_START_Y }
+        }
+        StackType::Tableau(index) => {
+            let base_x = TABLEAU_START_X + TABLEAU_X_OFFSET * index as f32;
+            // この列のカードを下から順に見て、position_in_stack 枚目までのオフセットを足していく。
+            // 既存の枚数より大きい position_in_stack (= 新しく一番上に置くカード) なら、全部足した位置になる
+            let calculated_y = TABLEAU_START_Y
+                + world
+                    .stacks()
+                    .cards(StackType::Tableau(index))
+                    .iter()
+                    .take(position_in_stack as usize)
+                    .map(|&entity| match world.get_component::<Card>(entity) {
+                        Some(card) if card.is_face_up => TABLEAU_Y_OFFSET_FACE_UP,
+                        _ => TABLEAU_Y_OFFSET_FACE_DOWN, // 裏向き (Card が無いのも念のため裏向き扱い)
+                    })
+                    .sum::<f32>();
+
+            Position { x: base_x, y: calculated_y }
+        }
+        StackType::Hand => todo!("Layout for Hand stack is not implemented yet"),
+    }
+} 
\ No newline at end of file
@@ -1,3 +1,12 @@
 pub mod auto_move;
 pub mod deck;
-pub mod rules; 
\ No newline at end of file
+pub mod game_record; // 1ゲーム分の棋譜 (保存して再生できる)
+pub mod hints; // 次の一手のヒント
+pub mod history; // undo / redo のための操作履歴
+pub mod layout_calculator; // カードの座標計算 (元 app/layout_calculator.rs)
+pub mod notation; // `T3->F1` みたいな1手の記法
+pub mod rules;
+pub mod scoring; // 何をしたら何点か
+pub mod solver; // 配りが勝てるか調べるソルバー
+pub mod stock_handler; // 山札クリックの処理 (元 app/stock_handler.rs)
+pub mod variants; // クロンダイク / スパイダー / フリーセル / ユーコンのルール
@@ -0,0 +1,152 @@
+// src/logic/notation.rs
+//! 1手を文字で書くための記法だよ！✍️ (棋譜の「7六歩」みたいなもの)
+//!
+//! スタックは `StackType` をそのまま短く書く (番号は人間向けに 1 から):
+//! - `S` 山札、`W` 捨て札、`T1`..`T10` 場札の列、`F1`..`F8` 組札、`C1`..`C4` フリーセル
+//!
+//! 1手は:
+//! - `T3->F1` / `W->T5`: 一番上のカードを動かす
+//! - `T3(3)->T5`: 上から3枚をまとめて動かす
+//! - `S`: 山札をクリック (めくる・捨て札を戻す・スパイダーなら全部の列に配る)
+//! - `undo` / `redo`
+//!
+//! 棋譜ファイル (`logic::game_record`) の手はこの記法で書かれるよ。
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::StackType;
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+
+/// スタックの書き方 (`T3` とか)。
+pub fn stack_label(stack: StackType) -> String {
+    match stack {
+        StackType::Stock => "S".to_string(),
+        StackType::Waste => "W".to_string(),
+        StackType::Tableau(index) => format!("T{}", index as u16 + 1),
+        StackType::Foundation(index) => format!("F{}", index as u16 + 1),
+        StackType::FreeCell(index) => format!("C{}", index as u16 + 1),
+        StackType::Hand => "H".to_string(),
+    }
+}
+
+/// `stack_label` の逆。手札 (`H`、ドラッグ中の一時置き場) は棋譜に出てこないのでエラー。
+pub fn parse_stack(text: &str) -> Result<StackType, String> {
+    match text {
+        "S" => return Ok(StackType::Stock),
+        "W" => return Ok(StackType::Waste),
+        _ => {}
+    }
+    let kind = text.get(..1).unwrap_or_default();
+    let number: u8 = text
+        .get(1..)
+        .and_then(|number| number.parse().ok())
+        .filter(|&number| number >= 1)
+        .ok_or_else(|| format!("Unknown stack {:?}", text))?;
+    match kind {
+        "T" => Ok(StackType::Tableau(number - 1)),
+        "F" => Ok(StackType::Foundation(number - 1)),
+        "C" => Ok(StackType::FreeCell(number - 1)),
+        _ => Err(format!("Unknown stack {:?}", text)),
+    }
+}
+
+/// 記法で書ける1手。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Move {
+    /// 山札をクリックした。
+    Stock,
+    /// `from` の上から `count` 枚を `to` に動かした。
+    Cards { from: StackType, to: StackType, count: usize },
+    Undo,
+    Redo,
+}
+
+impl Move {
+    /// 今の World で、この手が掴むカード (まとめて動かすなら一番下のカード)。
+    /// 山札クリックと undo / redo はカードを掴まないので `None`。
+    pub fn entity(&self, world: &World) -> Option<Entity> {
+        let Move::Cards { from, count, .. } = *self else { return None };
+        let cards = world.stacks().cards(from);
+        cards.len().checked_sub(count).map(|index| cards[index])
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Move::Stock => write!(f, "S"),
+            Move::Cards { from, to, count: 1 } => write!(f, "{}->{}", stack_label(from), stack_label(to)),
+            Move::Cards { from, to, count } => write!(f, "{}({})->{}", stack_label(from), count, stack_label(to)),
+            Move::Undo => write!(f, "undo"),
+            Move::Redo => write!(f, "redo"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim() {
+            "S" => return Ok(Move::Stock),
+            "undo" => return Ok(Move::Undo),
+            "redo" => return Ok(Move::Redo),
+            _ => {}
+        }
+        let (source, to) = text.trim().split_once("->").ok_or_else(|| format!("Cannot read the move {:?}", text))?;
+        let (from, count) = match source.strip_suffix(')').and_then(|source| source.split_once('(')) {
+            Some((from, count)) => {
+                let count = count.parse().ok().filter(|&count| count >= 1);
+                (from, count.ok_or_else(|| format!("Bad card count in the move {:?}", text))?)
+            }
+            None => (source, 1),
+        };
+        Ok(Move::Cards { from: parse_stack(from)?, to: parse_stack(to)?, count })
+    }
+}
+
+impl TryFrom<String> for Move {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Move> for String {
+    fn from(play: Move) -> Self {
+        play.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_round_trip_through_text() {
+        let cases = [
+            ("T3->F1", Move::Cards { from: StackType::Tableau(2), to: StackType::Foundation(0), count: 1 }),
+            ("W->T5", Move::Cards { from: StackType::Waste, to: StackType::Tableau(4), count: 1 }),
+            ("T10(3)->T1", Move::Cards { from: StackType::Tableau(9), to: StackType::Tableau(0), count: 3 }),
+            ("C2->F8", Move::Cards { from: StackType::FreeCell(1), to: StackType::Foundation(7), count: 1 }),
+            ("S", Move::Stock),
+            ("undo", Move::Undo),
+            ("redo", Move::Redo),
+        ];
+        for (text, play) in cases {
+            assert_eq!(text.parse::<Move>(), Ok(play));
+            assert_eq!(play.to_string(), text);
+        }
+        for bad in ["", "T0->F1", "T3->", "X1->T1", "T3(0)->T5", "H->T1", "T3 F1"] {
+            assert!(bad.parse::<Move>().is_err(), "{:?} は読めないはず", bad);
+        }
+        assert_eq!(serde_json::to_string(&Move::Stock).unwrap(), "\"S\"");
+        println!("1手の記法テスト、成功！✍️");
+    }
+}
@@ -1,7 +1,7 @@
 //! ルール判定で共通して使うヘルパー関数や型を置くよ。
 
 use crate::components::card::Suit; // Card を使う
-use crate::components::stack::{StackType, StackInfo}; // StackInfo を使う
+use crate::components::stack::StackType;
 use crate::ecs::entity::Entity;
 use crate::ecs::world::World;
 
@@ -36,17 +36,7 @@ pub(crate) fn get_foundation_suit(foundation_index: u8) -> Option<Suit> {
 }
 
 /// 指定されたスタック (`target_stack`) の一番上にあるカードのエンティティID (`Entity`) を取得するよ。
-/// StackInfo の position_in_stack が最大のものを探す。
+/// World の Stacks 索引を引くだけなので、全カードをスキャンしないよ。
 pub(crate) fn get_top_card_entity(world: &World, target_stack: StackType) -> Option<Entity> {
-    let stack_entities = world.get_all_entities_with_component::<StackInfo>();
-    stack_entities
-        .into_iter()
-        .filter(|&entity| {
-            world.get_component::<StackInfo>(entity)
-                .map_or(false, |stack_info| stack_info.stack_type == target_stack)
-        })
-        .max_by_key(|&entity| {
-            world.get_component::<StackInfo>(entity)
-                .map_or(0, |stack_info| stack_info.position_in_stack)
-        })
-} 
\ No newline at end of file
+    world.stacks().top(target_stack)
+}
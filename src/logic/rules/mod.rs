@@ -15,8 +15,8 @@ mod tests;
 pub use common::*;
 pub use foundation::*;
 pub use stock_waste::*;
-pub use move_validation::is_move_valid;
+pub use move_validation::{can_move_run, is_move_valid, validate_move};
 
 // サブモジュール内の主要な関数を、このモジュール (rules) の直下から使えるように re-export！
-pub use tableau::can_move_to_tableau;
+pub use tableau::{can_move_to_tableau, is_alternating_run};
 pub use win_condition::check_win_condition; 
\ No newline at end of file
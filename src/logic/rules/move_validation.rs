@@ -6,10 +6,10 @@ use crate::ecs::entity::Entity;
 use crate::components::stack::StackType;
 use crate::components::card::Card;
 use crate::logic::rules::{foundation, tableau}; // 各ルール関数を use
-use web_sys::console; // ログ出力用
-use wasm_bindgen::JsValue;
+use crate::logic::variants; // 遊び方ごとのルール
+use log::{debug, error}; // ログ出力用
 
-/// 指定されたエンティティを特定のスタックに移動できるか検証する。
+/// 指定されたエンティティを特定のスタックに移動できるか検証する (クロンダイクの置き方)。
 /// (元 MoveCardSystem::check_move_validity)
 pub fn is_move_valid(
     world: &World,
@@ -18,7 +18,7 @@ pub fn is_move_valid(
 ) -> bool {
     // 移動元カード情報を取得 (エラーチェックは呼び出し元で行う想定でも良いが、ここでも念のため)
     if world.get_component::<Card>(moved_entity).is_none() {
-        console::log_1(&JsValue::from_str(&format!("[Rules Validation Error] Moved entity {:?} has no Card component!", moved_entity)));
+        error!("[Rules Validation Error] Moved entity {:?} has no Card component!", moved_entity);
         return false;
     }
     // 移動元スタック情報はここでは不要なことが多い
@@ -33,10 +33,52 @@ pub fn is_move_valid(
             // 組札への移動ルールをチェック
             foundation::can_move_to_foundation(world, moved_entity, target_index)
         }
-        StackType::Stock | StackType::Waste | StackType::Hand => {
-            // Stock, Waste, Hand への直接移動は通常許可されない
-            console::log_1(&JsValue::from_str(&format!("[Rules Validation] Moving to {:?} is not allowed.", target_stack)));
+        StackType::Stock | StackType::Waste | StackType::FreeCell(_) | StackType::Hand => {
+            // Stock, Waste, Hand への直接移動は通常許可されない (フリーセルはクロンダイクには無い)
+            debug!("[Rules Validation] Moving to {:?} is not allowed.", target_stack);
             false
         }
     }
-} 
\ No newline at end of file
+}
+
+/// 何枚か (`cards`、下から順で `cards[0]` が掴んだカード) をまとめて `target_stack` に動かせるか検証する (クロンダイクの置き方)。
+/// `is_move_valid` は一番下のカードしか見ないけど、こっちは並び全体もチェックするよ。
+/// - 並びが色違い・ランク降順になってること (`tableau::is_alternating_run`)
+/// - 場札へは、一番下のカードが移動先に置けること
+/// - 組札へは1枚ずつ (まとめて上げるのはダメ)
+pub fn can_move_run(
+    world: &World,
+    cards: &[Entity],
+    target_stack: StackType,
+) -> bool {
+    let Some(&bottom) = cards.first() else {
+        error!("[Rules Validation Error] No cards to move to {:?}!", target_stack);
+        return false;
+    };
+    if matches!(target_stack, StackType::Foundation(_)) && cards.len() > 1 {
+        debug!("[Rules Validation] {} cards cannot go to {:?} together.", cards.len(), target_stack);
+        return false;
+    }
+    tableau::is_alternating_run(world, cards) && is_move_valid(world, bottom, target_stack)
+}
+
+/// `is_move_valid` の「理由つき」バージョンだよ！📝
+/// サーバーがクライアントに `MoveRejected { reason }` を返すときみたいに、
+/// ダメだった理由まで知りたい時に使うよ。
+///
+/// 今遊んでる遊び方 (`logic::variants::active`) の `RuleSet::can_move` でチェックする。
+/// どの遊び方でも、そもそも「そのカードを掴めるか」もチェックされるよ。
+/// - 裏向きのカードは動かせない
+/// - 山札 (Stock) のカードはめくる (配る) 操作でしか動かせない
+/// - 場札以外 (捨て札、組札、フリーセル) は一番上のカードしか動かせない
+///
+/// # 戻り値
+/// * `Ok(())`: 移動OK！
+/// * `Err(String)`: 移動NG。中身は人間が読める理由。
+pub fn validate_move(
+    world: &World,
+    moved_entity: Entity,
+    target_stack: StackType,
+) -> Result<(), String> {
+    variants::active(world).can_move(world, moved_entity, target_stack)
+}
@@ -1,5 +1,6 @@
 //! 山札 (Stock) と捨て札 (Waste) に関するルールを定義するよ。
 
+use crate::config::GameRules;
 use crate::ecs::entity::Entity;
 use crate::ecs::world::World;
 // 他のルール関数 (tableau, foundation) を使うためにインポート
@@ -12,8 +13,14 @@ pub fn can_deal_from_stock(stock_is_empty: bool) -> bool {
 }
 
 /// ストック（山札）が空のときに、ウェスト（捨て札）からストックにカードを戻せるかチェックする。
-pub fn can_reset_stock_from_waste(stock_is_empty: bool, waste_is_empty: bool) -> bool {
-    stock_is_empty && !waste_is_empty
+/// `recycles_done` はこのゲームで既に戻した回数。`rules.max_passes` の周回上限を超えるならダメ。
+pub fn can_reset_stock_from_waste(
+    stock_is_empty: bool,
+    waste_is_empty: bool,
+    recycles_done: u32,
+    rules: &GameRules,
+) -> bool {
+    stock_is_empty && !waste_is_empty && rules.allows_recycle(recycles_done)
 }
 
 /// ウェスト（捨て札）の一番上のカードが、特定の場札 (Tableau) の一番上に置けるかチェックする。
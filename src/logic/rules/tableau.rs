@@ -6,9 +6,8 @@ use crate::ecs::entity::Entity;
 use crate::ecs::world::World;
 // 共通ヘルパーを使うためにインポート
 use super::common::{CardColor, get_top_card_entity};
-// console::log を使うためにインポート
-use wasm_bindgen::JsValue;
-use web_sys::console;
+// ログ出力用 (log クレート経由)
+use log::{debug, error};
 
 /// 指定されたカードが、特定の場札 (Tableau) の一番上に置けるかチェックする。
 pub fn can_move_to_tableau(
@@ -19,7 +18,7 @@ pub fn can_move_to_tableau(
     let card_to_move = match world.get_component::<Card>(card_to_move_entity) {
         Some(card) => card,
         None => {
-            console::log_1(&JsValue::from_str(&format!("[Rules Error] 移動元エンティティ {:?} に Card コンポーネントが見つかりません！", card_to_move_entity)));
+            error!("[Rules Error] 移動元エンティティ {:?} に Card コンポーネントが見つかりません！", card_to_move_entity);
             return false;
         }
     };
@@ -32,7 +31,7 @@ pub fn can_move_to_tableau(
             let target_top_card = match world.get_component::<Card>(target_top_card_entity) {
                 Some(card) => card,
                 None => {
-                    console::log_1(&JsValue::from_str(&format!("[Rules Error] 移動先トップエンティティ {:?} に Card コンポーネントが見つかりません！", target_top_card_entity)));
+                    error!("[Rules Error] 移動先トップエンティティ {:?} に Card コンポーネントが見つかりません！", target_top_card_entity);
                     return false;
                 }
             };
@@ -47,26 +46,57 @@ pub fn can_move_to_tableau(
             let colors_different = move_color != target_color;
             let rank_is_one_less = (move_rank as usize) == (target_rank as usize).saturating_sub(1);
 
-            console::log_1(&JsValue::from_str(&format!(
+            debug!(
                 "    [Rule Check] Moving {:?}({:?}) onto {:?}({:?}). Colors different: {}. Rank is one less: {}.",
                 move_rank, move_color, target_rank, target_color, colors_different, rank_is_one_less
-            )));
+            );
 
             if !colors_different || !rank_is_one_less {
-                console::log_1(&JsValue::from_str("      -> Move invalid based on rank/color."));
+                debug!("      -> Move invalid based on rank/color.");
                 return false;
             }
-            console::log_1(&JsValue::from_str("      -> Move valid based on rank/color."));
+            debug!("      -> Move valid based on rank/color.");
             true
         }
         None => {
             let move_rank = card_to_move.rank;
             let is_king = move_rank == Rank::King;
-            console::log_1(&JsValue::from_str(&format!(
+            debug!(
                 "    [Rule Check] Moving {:?} onto empty Tableau. Is King: {}.",
                 move_rank, is_king
-            )));
+            );
             is_king
         }
     }
-} 
\ No newline at end of file
+} 
+
+/// 場札から一緒に動かすカード (`cards`、下から順) が、全部表向きで、
+/// 色違いでランクが1つずつ下がる並びになってるかチェックする。1枚だけなら並びとしては OK。
+pub fn is_alternating_run(world: &World, cards: &[Entity]) -> bool {
+    let mut run = Vec::with_capacity(cards.len());
+    for &entity in cards {
+        match world.get_component::<Card>(entity) {
+            Some(card) if card.is_face_up => run.push(card),
+            Some(_) => {
+                debug!("    [Rule Check] {:?} in the run is face down.", entity);
+                return false;
+            }
+            None => {
+                error!("[Rules Error] 一緒に動かすエンティティ {:?} に Card コンポーネントが見つかりません！", entity);
+                return false;
+            }
+        }
+    }
+    run.windows(2).all(|pair| {
+        let (lower, upper) = (pair[0], pair[1]);
+        let alternates = CardColor::from_suit(lower.suit) != CardColor::from_suit(upper.suit);
+        let descends = upper.rank as usize + 1 == lower.rank as usize;
+        if !alternates || !descends {
+            debug!(
+                "    [Rule Check] {:?} of {:?} cannot sit on {:?} of {:?} inside a run.",
+                upper.rank, upper.suit, lower.rank, lower.suit
+            );
+        }
+        alternates && descends
+    })
+}
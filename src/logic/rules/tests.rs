@@ -6,6 +6,7 @@ use crate::components::card::{Rank, Suit, Card}; // Card, Suit, Rank を使う
 use crate::ecs::world::World; // World を使う
 use crate::ecs::entity::Entity; // Entity を使う
 use crate::components::stack::{StackType, StackInfo}; // StackType, StackInfo を使う
+use crate::config::GameRules; // 山札の周回上限のテストで使う
 
 // --- テスト用ヘルパー関数 ---
 /// テストワールドにカードエンティティを追加するヘルパー関数だよ。
@@ -44,16 +45,22 @@ fn test_can_move_to_foundation_rules() {
 
 #[test]
 fn test_stock_waste_rules() {
+    let rules = GameRules::default();
     // ストックがある場合
     assert!(can_deal_from_stock(false), "ストックがあれば配れるはず");
-    assert!(!can_reset_stock_from_waste(false, false), "ストックがある場合はリセットできないはず");
-    assert!(!can_reset_stock_from_waste(false, true), "ストックがある場合はリセットできないはず");
+    assert!(!can_reset_stock_from_waste(false, false, 0, &rules), "ストックがある場合はリセットできないはず");
+    assert!(!can_reset_stock_from_waste(false, true, 0, &rules), "ストックがある場合はリセットできないはず");
 
     // ストックが空の場合
     assert!(!can_deal_from_stock(true), "ストックが空なら配れないはず");
     // ★修正: waste_is_empty が false (つまりウェストにカードがある) 場合に true を期待する
-    assert!(can_reset_stock_from_waste(true, false), "ストックが空でウェストにあればリセットできるはず");
-    assert!(!can_reset_stock_from_waste(true, true), "ストックもウェストも空ならリセットできないはず");
+    assert!(can_reset_stock_from_waste(true, false, 0, &rules), "ストックが空でウェストにあればリセットできるはず");
+    assert!(!can_reset_stock_from_waste(true, true, 0, &rules), "ストックもウェストも空ならリセットできないはず");
+
+    // 周回の上限 (2周まで = 戻せるのは1回)
+    let two_passes = GameRules::draw_three().with_max_passes(Some(2));
+    assert!(can_reset_stock_from_waste(true, false, 0, &two_passes), "1回目は戻せるはず");
+    assert!(!can_reset_stock_from_waste(true, false, 1, &two_passes), "上限に達したら戻せないはず");
     println!("Stock/Waste ルールテスト、成功！🎉");
 }
 
@@ -120,5 +127,38 @@ fn test_can_move_to_tableau_world() {
     println!("--- test_can_move_to_tableau_world 完了 ---");
 }
 
+#[test]
+fn test_can_move_run_checks_the_whole_run() {
+    let mut world = World::new();
+    world.register_component::<Card>();
+    world.register_component::<StackInfo>();
+
+    let _target = add_card_for_test(&mut world, Suit::Spade, Rank::Nine, StackType::Tableau(0), 0);
+    let eight = add_card_for_test(&mut world, Suit::Heart, Rank::Eight, StackType::Tableau(1), 0);
+    let seven = add_card_for_test(&mut world, Suit::Club, Rank::Seven, StackType::Tableau(1), 1);
+    let six_red = add_card_for_test(&mut world, Suit::Diamond, Rank::Six, StackType::Tableau(1), 2);
+    let six_black = add_card_for_test(&mut world, Suit::Spade, Rank::Six, StackType::Tableau(2), 0);
+    let ace = add_card_for_test(&mut world, Suit::Heart, Rank::Ace, StackType::Tableau(3), 0);
+    let two = add_card_for_test(&mut world, Suit::Heart, Rank::Two, StackType::Tableau(3), 1);
+
+    // 色違い・降順の並びなら、まとめて9の上に置ける
+    assert!(is_alternating_run(&world, &[eight, seven, six_red]));
+    assert!(can_move_run(&world, &[eight, seven, six_red], StackType::Tableau(0)));
+
+    // 一番下の8は置けても、並びの途中で色が揃ってたらダメ
+    assert!(!is_alternating_run(&world, &[eight, seven, six_black]));
+    assert!(!can_move_run(&world, &[eight, seven, six_black], StackType::Tableau(0)));
+
+    // 裏向きのカードが混ざっててもダメ
+    world.get_component_mut::<Card>(seven).unwrap().is_face_up = false;
+    assert!(!can_move_run(&world, &[eight, seven, six_red], StackType::Tableau(0)));
+
+    // 組札へは1枚ずつ
+    assert!(can_move_run(&world, &[ace], StackType::Foundation(0)));
+    assert!(!can_move_run(&world, &[ace, two], StackType::Foundation(0)));
+    assert!(!can_move_run(&world, &[], StackType::Tableau(0)));
+    println!("並びごとの移動ルールテスト、成功！🎉");
+}
+
 // TODO: World を使う can_move_to_foundation のテストを追加する
 // TODO: World を使う can_move_from_waste_to_tableau/foundation のテストを追加する 
\ No newline at end of file
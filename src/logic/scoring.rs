@@ -0,0 +1,141 @@
+// src/logic/scoring.rs
+//! 点数のつけ方だよ！💯 何をしたら何点か、をここにまとめてある。
+//!
+//! スタンダード (Windows のソリティアと同じ):
+//! - 捨て札 → 場札: +5
+//! - 組札へ: +10
+//! - 組札 → 場札: -15
+//! - 場札の裏向きのカードがめくれた: +5
+//! - 捨て札を山札に戻した: 1枚めくりなら -100、3枚めくりなら -20
+//! - 30秒以上かけて勝ったら、タイムボーナス 700000 / 秒数 (秒数は `GameClock` の経過時間)
+//!
+//! ベガス: 1ゲーム -$52 で始めて、組札に上げたら +$5、組札から戻したら -$5。それ以外は0点。
+//!
+//! 移動とめくりは `MoveCardSystem::process_move`、捨て札を戻すのは `stock_handler` が、手を履歴に記録した直後に呼ぶ
+//! (ブラウザもサーバーも同じ道を通る)。タイムボーナスだけは `ScoringSystem` が `GameWon` を見てつける。
+//! ついた点数は `History` の一番新しい1回分 (= 今記録した手) にも記録されて、undo すると取り消される (redo でまたつく)。
+//! なので「組札に上げる → undo → また上げる」を繰り返しても点は増えないよ。
+
+use crate::components::score::{Score, ScoringMode};
+use crate::components::{GameClock, StackType};
+use crate::config::GameRules;
+use crate::ecs::world::World;
+use crate::logic::history::History;
+use log::debug;
+
+/// ベガスで1ゲーム始めるのに払う額。
+pub const VEGAS_BUY_IN: i32 = 52;
+/// タイムボーナスがもらえるのは、この秒数以上かかった時だけ。
+const TIME_BONUS_MIN_SECONDS: f64 = 30.0;
+const TIME_BONUS_NUMERATOR: f64 = 700_000.0;
+
+/// `from` から `to` へカードを動かした時の点数。
+pub fn points_for_move(mode: ScoringMode, from: StackType, to: StackType) -> i32 {
+    match (mode, from, to) {
+        (_, StackType::Foundation(_), StackType::Foundation(_)) => 0,
+        (ScoringMode::Standard, _, StackType::Foundation(_)) => 10,
+        (ScoringMode::Standard, StackType::Foundation(_), _) => -15,
+        (ScoringMode::Standard, StackType::Waste, StackType::Tableau(_)) => 5,
+        (ScoringMode::Vegas, _, StackType::Foundation(_)) => 5,
+        (ScoringMode::Vegas, StackType::Foundation(_), _) => -5,
+        _ => 0,
+    }
+}
+
+/// 場札の裏向きのカードがめくれた時の点数。
+pub fn points_for_flip(mode: ScoringMode) -> i32 {
+    match mode {
+        ScoringMode::Standard => 5,
+        ScoringMode::Vegas => 0,
+    }
+}
+
+/// 捨て札を山札に戻した時の点数。
+pub fn points_for_recycle(mode: ScoringMode, rules: &GameRules) -> i32 {
+    match mode {
+        ScoringMode::Standard if rules.draw_count == 1 => -100,
+        ScoringMode::Standard => -20,
+        ScoringMode::Vegas => 0,
+    }
+}
+
+/// `seconds` 秒 (最初の1手から) で勝った時のタイムボーナス。
+pub fn time_bonus(mode: ScoringMode, seconds: f64) -> i32 {
+    match mode {
+        ScoringMode::Standard if seconds >= TIME_BONUS_MIN_SECONDS => (TIME_BONUS_NUMERATOR / seconds) as i32,
+        _ => 0,
+    }
+}
+
+/// World の `Score` に点数を足す。`Score` が置いてなければ何もしない (点数を数えてない World)。
+/// 実際に動いた分 (0点止まりを除いた分) は、undo で取り消せるように `History` にも記録する。
+fn add(world: &mut World, points: i32, reason: &str) {
+    if points == 0 {
+        return;
+    }
+    let Some(score) = world.resource_mut::<Score>() else { return };
+    let before = score.points;
+    score.add(points);
+    debug!("[Scoring] {:+} for {} -> {}", points, reason, score.points);
+    let awarded = score.points - before;
+    if let Some(history) = world.resource_mut::<History>() {
+        history.add_points(awarded);
+    }
+}
+
+/// カードを動かした。
+pub fn record_move(world: &mut World, from: StackType, to: StackType) {
+    let points = points_for_move(Score::from_world(world).mode, from, to);
+    add(world, points, "a move");
+}
+
+/// 場札の裏向きのカードがめくれた。
+pub fn record_flip(world: &mut World) {
+    let points = points_for_flip(Score::from_world(world).mode);
+    add(world, points, "a flip");
+}
+
+/// 捨て札を山札に戻した。
+pub fn record_recycle(world: &mut World) {
+    let points = points_for_recycle(Score::from_world(world).mode, &GameRules::from_world(world));
+    add(world, points, "recycling the waste");
+}
+
+/// 勝った。かかった時間は `GameClock` から。
+pub fn record_win(world: &mut World) {
+    let seconds = GameClock::from_world(world).elapsed_seconds;
+    let points = time_bonus(Score::from_world(world).mode, seconds);
+    add(world, points, "the time bonus");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_and_vegas_points() {
+        use ScoringMode::{Standard, Vegas};
+        assert_eq!(points_for_move(Standard, StackType::Waste, StackType::Tableau(0)), 5);
+        assert_eq!(points_for_move(Standard, StackType::Tableau(0), StackType::Foundation(1)), 10);
+        assert_eq!(points_for_move(Standard, StackType::Foundation(1), StackType::Tableau(0)), -15);
+        assert_eq!(points_for_move(Standard, StackType::Tableau(0), StackType::Tableau(1)), 0);
+        assert_eq!(points_for_move(Vegas, StackType::Waste, StackType::Tableau(0)), 0);
+        assert_eq!(points_for_move(Vegas, StackType::Waste, StackType::Foundation(0)), 5);
+        assert_eq!(points_for_recycle(Standard, &GameRules::draw_one()), -100);
+        assert_eq!(points_for_recycle(Standard, &GameRules::draw_three()), -20);
+        assert_eq!(time_bonus(Standard, 20.0), 0);
+        assert_eq!(time_bonus(Standard, 100.0), 7000);
+        assert_eq!(time_bonus(Vegas, 100.0), 0);
+
+        // スタンダードはマイナスにならない。ベガスは持ち越して、毎ゲーム $52 払う
+        let mut standard = Score::new(Standard);
+        standard.add(-100);
+        assert_eq!(standard.points, 0);
+        let mut vegas = Score::new(Vegas).new_game();
+        vegas.add(5 * 13);
+        assert_eq!(vegas.points, 13);
+        assert_eq!(vegas.new_game().points, -39);
+        assert_eq!(vegas.new_game().label(), "Vegas: -$39");
+        println!("点数のつけ方テスト、成功！💯");
+    }
+}
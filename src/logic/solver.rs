@@ -0,0 +1,492 @@
+// src/logic/solver.rs
+//! クロンダイクのソルバーだよ！🧠🃏 「この配り、クリアできる？」を調べる。
+//!
+//! World をそのまま探索すると重すぎるので、`Card` / `StackInfo` から軽い `Board` を作って、
+//! その上で深さ優先探索 (DFS) する。同じ盤面は2回調べない (場札の列の並び順は無視して同じ扱い)。
+//! ノード数に上限 (`node_budget`) があって、使い切ったら「わからない」(`Unknown`) で諦めるよ。
+//!
+//! ソルバーは裏向きのカードも全部見えてる前提 (いわゆる「全部見えてるソリティア」) なので、
+//! `Solvable` は「うまくやれば勝てる配り」って意味。プレイヤーへのヒントにそのまま使うとズルになるので注意！
+//!
+//! ルールは `logic::rules` と同じ:
+//! - 場札には色違い & ランクが1つ下のカードを、空の列にはキングだけ置ける
+//! - 組札はインデックスでスートが決まってて (0: Heart, 1: Diamond, 2: Club, 3: Spade)、エースから順番に積む
+//! - 山札は `GameRules::draw_count` 枚ずつめくって、空になったら `max_passes` の範囲で捨て札を山札に戻せる
+//!   (`stock_handler` と同じ順番で)
+
+use std::collections::HashSet;
+
+use crate::components::{Card, GameState, Rank, StackType, Suit};
+use crate::config::GameRules;
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use crate::logic::deck::{create_standard_deck, shuffle_deck_with_seed};
+use crate::logic::rules::{get_foundation_suit, CardColor};
+use crate::logic::variants::Variant;
+use log::debug;
+
+/// 場札の列の数。
+const TABLEAU_COUNT: usize = 7;
+/// 組札の数。
+const FOUNDATION_COUNT: usize = 4;
+
+/// 盤面の上のカード1枚。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoardCard {
+    pub suit: Suit,
+    pub rank: Rank,
+    pub face_up: bool,
+}
+
+impl BoardCard {
+    fn color(&self) -> CardColor {
+        CardColor::from_suit(self.suit)
+    }
+
+    /// 探索済みの盤面を覚えておくための1バイト表現。
+    fn key(&self) -> u8 {
+        (self.suit as u8) * 13 + (self.rank as u8 - 1) + if self.face_up { 64 } else { 0 }
+    }
+}
+
+/// ソルバー用の軽い盤面。山札・捨て札・場札は、どれも最後の要素が一番上だよ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    tableau: [Vec<BoardCard>; TABLEAU_COUNT],
+    /// 組札ごとに積まれている枚数 (= 一番上のカードのランク)。インデックスは `StackType::Foundation` と同じ。
+    foundation: [u8; FOUNDATION_COUNT],
+    stock: Vec<BoardCard>,
+    waste: Vec<BoardCard>,
+    /// 1回にめくる枚数 (`GameRules::draw_count`)。
+    draw_count: usize,
+    /// あと何回捨て札を山札に戻せるか。`None` なら無制限。
+    recycles_left: Option<u32>,
+}
+
+/// ソルバーが見つけた1手。`World` にそのまま適用できる形だよ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverMove {
+    /// `suit` / `rank` のカード (場札ならその上に積まれてるカードも一緒に) を `from` から `to` へ動かす。
+    /// `MoveRequested` や `MoveCardSystem::process_move` に渡す移動と同じ意味。
+    Move { suit: Suit, rank: Rank, from: StackType, to: StackType },
+    /// 山札の上から `GameRules::draw_count` 枚を捨て札にめくる。
+    DrawFromStock,
+    /// 空になった山札に捨て札を全部戻す。
+    RecycleWaste,
+}
+
+impl SolverMove {
+    /// 動かすカードのエンティティを World から探す (`Move` 以外は `None`)。
+    pub fn card_entity(&self, world: &World) -> Option<Entity> {
+        let SolverMove::Move { suit, rank, from, .. } = *self else { return None };
+        world.stacks().cards(from).iter().copied().find(|&entity| {
+            world
+                .get_component::<Card>(entity)
+                .is_some_and(|card| card.suit == suit && card.rank == rank)
+        })
+    }
+}
+
+/// 探索の結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveResult {
+    /// 勝てる！中身は勝つまでの手順。
+    Solvable(Vec<SolverMove>),
+    /// 全部の盤面を調べたけど、どうやっても勝てない。
+    Unsolvable,
+    /// ノード数の上限に達したので、わからないまま諦めた。
+    Unknown,
+}
+
+impl Board {
+    /// World の `Card` / `StackInfo` から盤面を作る。`GameRules` と、今まで捨て札を戻した回数も見るよ。
+    /// クロンダイクの場所 (場札 0-6, 組札 0-3, 山札, 捨て札) に無いカードがあったり、
+    /// クロンダイク以外の遊び方 (`Variant`) だったら `Err`。
+    pub fn from_world(world: &World) -> Result<Self, String> {
+        let variant = Variant::from_world(world);
+        if variant != Variant::Klondike {
+            return Err(format!("The solver only knows Klondike, not {}", variant.name()));
+        }
+        let stacks = world.stacks();
+        let read = |stack_type: StackType| -> Result<Vec<BoardCard>, String> {
+            stacks
+                .cards(stack_type)
+                .iter()
+                .map(|&entity| {
+                    let card = world
+                        .get_component::<Card>(entity)
+                        .ok_or_else(|| format!("Entity {:?} in {:?} is not a card", entity, stack_type))?;
+                    Ok(BoardCard { suit: card.suit, rank: card.rank, face_up: card.is_face_up })
+                })
+                .collect()
+        };
+
+        let recycles_done = world.resource::<GameState>().map_or(0, |state| state.stock_recycles);
+        let mut board = Board::empty().with_rules(&GameRules::from_world(world), recycles_done);
+        let mut placed = 0;
+        for (index, column) in board.tableau.iter_mut().enumerate() {
+            *column = read(StackType::Tableau(index as u8))?;
+            placed += column.len();
+        }
+        for (index, count) in board.foundation.iter_mut().enumerate() {
+            *count = stacks.len(StackType::Foundation(index as u8)) as u8;
+            placed += *count as usize;
+        }
+        board.stock = read(StackType::Stock)?;
+        board.waste = read(StackType::Waste)?;
+        placed += board.stock.len() + board.waste.len();
+
+        let total = world.get_all_entities_with_component::<Card>().len();
+        if placed != total {
+            return Err(format!(
+                "{} card(s) are outside the Klondike stacks and cannot be solved",
+                total - placed
+            ));
+        }
+        Ok(board)
+    }
+
+    /// `DealInitialCardsSystem` が `seed` で配るのと同じ盤面を、World を作らずに用意する。
+    /// ルールはデフォルト (1枚めくり・何周でもOK)。変えたい時は `with_rules` してね。
+    pub fn dealt(seed: u64) -> Self {
+        let mut deck = create_standard_deck();
+        shuffle_deck_with_seed(&mut deck, seed);
+        let mut cards = deck
+            .into_iter()
+            .map(|card| BoardCard { suit: card.suit, rank: card.rank, face_up: false });
+
+        let mut board = Board::empty();
+        for (index, column) in board.tableau.iter_mut().enumerate() {
+            column.extend(cards.by_ref().take(index + 1));
+            if let Some(top) = column.last_mut() {
+                top.face_up = true;
+            }
+        }
+        board.stock = cards.collect();
+        board
+    }
+
+    /// `rules` で遊ぶ盤面にする。`recycles_done` はもう捨て札を戻した回数。
+    pub fn with_rules(self, rules: &GameRules, recycles_done: u32) -> Self {
+        Self {
+            draw_count: rules.draw_count.max(1) as usize,
+            recycles_left: rules.max_passes.map(|max_passes| max_passes.saturating_sub(recycles_done + 1)),
+            ..self
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            tableau: Default::default(),
+            foundation: [0; FOUNDATION_COUNT],
+            stock: Vec::new(),
+            waste: Vec::new(),
+            draw_count: 1,
+            recycles_left: None,
+        }
+    }
+
+    /// 全部のカードが組札に上がった？
+    pub fn is_won(&self) -> bool {
+        self.stock.is_empty() && self.waste.is_empty() && self.tableau.iter().all(Vec::is_empty)
+    }
+
+    /// `card` を組札に置けるなら、その組札のインデックス。
+    fn foundation_for(&self, card: BoardCard) -> Option<usize> {
+        let index = (0..FOUNDATION_COUNT).find(|&i| get_foundation_suit(i as u8) == Some(card.suit))?;
+        (self.foundation[index] + 1 == card.rank as u8).then_some(index)
+    }
+
+    /// `card` を場札の `column` 列目に置ける？
+    fn fits_on_tableau(&self, card: BoardCard, column: usize) -> bool {
+        match self.tableau[column].last() {
+            None => card.rank == Rank::King,
+            Some(top) => top.face_up && top.color() != card.color() && top.rank as u8 == card.rank as u8 + 1,
+        }
+    }
+
+    /// 今の盤面で指せる手を全部、良さそうな順に並べて返す。
+    pub fn legal_moves(&self) -> Vec<SolverMove> {
+        // (優先度, 手)。小さいほど先に試す。
+        let mut moves: Vec<(u8, SolverMove)> = Vec::new();
+        let card_move = |card: BoardCard, from: StackType, to: StackType| SolverMove::Move {
+            suit: card.suit,
+            rank: card.rank,
+            from,
+            to,
+        };
+
+        for (column, cards) in self.tableau.iter().enumerate() {
+            let from = StackType::Tableau(column as u8);
+            if let Some(&top) = cards.last() {
+                if top.face_up {
+                    if let Some(index) = self.foundation_for(top) {
+                        moves.push((0, card_move(top, from, StackType::Foundation(index as u8))));
+                    }
+                }
+            }
+            for (index, &card) in cards.iter().enumerate().filter(|(_, card)| card.face_up) {
+                // 裏向きカードをめくれる or 列が空く手は有望
+                let reveals = index > 0 && !cards[index - 1].face_up;
+                for target in (0..TABLEAU_COUNT).filter(|&target| target != column) {
+                    if !self.fits_on_tableau(card, target) {
+                        continue;
+                    }
+                    let to_empty = self.tableau[target].is_empty();
+                    if index == 0 && to_empty {
+                        continue; // 列ごと空の列に移しても、列の番号が変わるだけ
+                    }
+                    let priority = if reveals || (index == 0 && !to_empty) { 1 } else { 4 };
+                    moves.push((priority, card_move(card, from, StackType::Tableau(target as u8))));
+                }
+            }
+        }
+
+        if let Some(&top) = self.waste.last() {
+            if let Some(index) = self.foundation_for(top) {
+                moves.push((0, card_move(top, StackType::Waste, StackType::Foundation(index as u8))));
+            }
+            for target in (0..TABLEAU_COUNT).filter(|&target| self.fits_on_tableau(top, target)) {
+                moves.push((2, card_move(top, StackType::Waste, StackType::Tableau(target as u8))));
+            }
+        }
+
+        if !self.stock.is_empty() {
+            moves.push((3, SolverMove::DrawFromStock));
+        } else if !self.waste.is_empty() && self.recycles_left != Some(0) {
+            moves.push((3, SolverMove::RecycleWaste));
+        }
+
+        for (index, &count) in self.foundation.iter().enumerate() {
+            let (Some(suit), Some(rank)) = (get_foundation_suit(index as u8), rank_from_u8(count)) else { continue };
+            let card = BoardCard { suit, rank, face_up: true };
+            for target in (0..TABLEAU_COUNT).filter(|&target| self.fits_on_tableau(card, target)) {
+                moves.push((5, card_move(card, StackType::Foundation(index as u8), StackType::Tableau(target as u8))));
+            }
+        }
+
+        moves.sort_by_key(|&(priority, _)| priority);
+        moves.into_iter().map(|(_, mv)| mv).collect()
+    }
+
+    /// 手を指した後の盤面を返す。`legal_moves` が返した手だけを渡してね。
+    pub fn apply(&self, mv: SolverMove) -> Board {
+        let mut next = self.clone();
+        match mv {
+            SolverMove::DrawFromStock => {
+                for _ in 0..next.draw_count {
+                    let Some(mut card) = next.stock.pop() else { break };
+                    card.face_up = true;
+                    next.waste.push(card);
+                }
+            }
+            SolverMove::RecycleWaste => {
+                next.recycles_left = next.recycles_left.map(|left| left.saturating_sub(1));
+                next.stock = std::mem::take(&mut next.waste);
+                for card in &mut next.stock {
+                    card.face_up = false;
+                }
+            }
+            SolverMove::Move { suit, rank, from, to } => {
+                let moved = match from {
+                    StackType::Tableau(column) => {
+                        let cards = &mut next.tableau[column as usize];
+                        let index = cards
+                            .iter()
+                            .position(|card| card.suit == suit && card.rank == rank)
+                            .unwrap_or(cards.len());
+                        let moved = cards.split_off(index);
+                        if let Some(top) = cards.last_mut() {
+                            top.face_up = true; // 残った一番上はめくる (MoveCardSystem と同じ)
+                        }
+                        moved
+                    }
+                    StackType::Waste => next.waste.pop().into_iter().collect(),
+                    StackType::Foundation(index) => {
+                        next.foundation[index as usize] -= 1;
+                        vec![BoardCard { suit, rank, face_up: true }]
+                    }
+                    StackType::Stock | StackType::FreeCell(_) | StackType::Hand => Vec::new(),
+                };
+                match to {
+                    StackType::Tableau(column) => next.tableau[column as usize]
+                        .extend(moved.into_iter().map(|card| BoardCard { face_up: true, ..card })),
+                    StackType::Foundation(index) => next.foundation[index as usize] += moved.len() as u8,
+                    StackType::Stock | StackType::Waste | StackType::FreeCell(_) | StackType::Hand => {}
+                }
+            }
+        }
+        next
+    }
+
+    /// 探索済みチェック用のキー。場札の列は並べ替えてから繋ぐので、列の番号が違うだけの盤面は同じキーになる。
+    fn key(&self) -> Vec<u8> {
+        let mut columns: Vec<Vec<u8>> = self
+            .tableau
+            .iter()
+            .map(|column| column.iter().map(BoardCard::key).collect())
+            .collect();
+        columns.sort_unstable();
+
+        let mut key = Vec::with_capacity(64);
+        key.extend_from_slice(&self.foundation);
+        key.push(self.recycles_left.map_or(u8::MAX, |left| left.min(200) as u8));
+        for pile in [&self.stock, &self.waste] {
+            key.push(u8::MAX);
+            key.extend(pile.iter().map(BoardCard::key));
+        }
+        for column in columns {
+            key.push(u8::MAX);
+            key.extend(column);
+        }
+        key
+    }
+}
+
+fn rank_from_u8(value: u8) -> Option<Rank> {
+    use Rank::*;
+    [Ace, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King]
+        .get((value as usize).checked_sub(1)?)
+        .copied()
+}
+
+/// `board` から勝てるか探す。調べる盤面が `node_budget` 個を超えたら `Unknown`。
+///
+/// 再帰だと山札を何周もした時にスタックが溢れるかもしれないので、自前のスタックで回すよ。
+pub fn solve(board: &Board, node_budget: usize) -> SolveResult {
+    if board.is_won() {
+        return SolveResult::Solvable(Vec::new());
+    }
+
+    let mut seen: HashSet<Vec<u8>> = HashSet::new();
+    seen.insert(board.key());
+    // (盤面, その盤面で指せる手, 次に試す手の番号)
+    let mut frames: Vec<(Board, Vec<SolverMove>, usize)> = vec![(board.clone(), board.legal_moves(), 0)];
+    // frames[i + 1] の盤面に来るために指した手
+    let mut path: Vec<SolverMove> = Vec::new();
+    let mut nodes = 0;
+
+    while let Some((current, moves, next)) = frames.last_mut() {
+        let Some(&mv) = moves.get(*next) else {
+            frames.pop();
+            path.pop();
+            continue;
+        };
+        *next += 1;
+
+        let after = current.apply(mv);
+        if !seen.insert(after.key()) {
+            continue;
+        }
+        nodes += 1;
+        if nodes > node_budget {
+            debug!("[Solver] Gave up after {} nodes.", node_budget);
+            return SolveResult::Unknown;
+        }
+
+        path.push(mv);
+        if after.is_won() {
+            debug!("[Solver] Solved in {} moves after {} nodes.", path.len(), nodes);
+            return SolveResult::Solvable(path);
+        }
+        let moves = after.legal_moves();
+        frames.push((after, moves, 0));
+    }
+
+    debug!("[Solver] No solution after exhausting {} nodes.", nodes);
+    SolveResult::Unsolvable
+}
+
+/// 今の World の盤面を解く。盤面が作れない (クロンダイクの場所に無いカードがある) 時は `Err`。
+pub fn solve_world(world: &World, node_budget: usize) -> Result<SolveResult, String> {
+    Board::from_world(world).map(|board| solve(&board, node_budget))
+}
+
+/// 「勝てる配りだけ」モード用。`start_seed` から順番に `attempts` 個のシードを試して、
+/// `node_budget` 以内で勝てると分かった最初のシードとその手順を返すよ。
+pub fn find_winnable_seed(start_seed: u64, attempts: u64, node_budget: usize) -> Option<(u64, Vec<SolverMove>)> {
+    (0..attempts).map(|offset| start_seed.wrapping_add(offset)).find_map(|seed| {
+        match solve(&Board::dealt(seed), node_budget) {
+            SolveResult::Solvable(moves) => Some((seed, moves)),
+            SolveResult::Unsolvable | SolveResult::Unknown => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::card_world;
+    use crate::logic::{rules, stock_handler};
+    use crate::systems::deal_system::DealInitialCardsSystem;
+    use crate::systems::move_card_system::MoveCardSystem;
+
+    fn dealt_world(seed: u64) -> World {
+        let mut world = card_world();
+        DealInitialCardsSystem.execute(&mut world, seed);
+        world
+    }
+
+    fn card(suit: Suit, rank: Rank, face_up: bool) -> BoardCard {
+        BoardCard { suit, rank, face_up }
+    }
+
+    #[test]
+    fn dealt_board_matches_the_world_deal() {
+        let world = dealt_world(20240501);
+        assert_eq!(Board::from_world(&world), Ok(Board::dealt(20240501)));
+        println!("Board::dealt と DealInitialCardsSystem が一致するテスト、成功！🎉");
+    }
+
+    #[test]
+    fn small_boards_are_solved_or_proven_stuck() {
+        // 裏向きの A♥ の上に 2♥: 2♥ はどこにも行けないので詰み
+        let mut stuck = Board::empty();
+        stuck.tableau[0] = vec![card(Suit::Heart, Rank::Ace, false), card(Suit::Heart, Rank::Two, true)];
+        assert_eq!(solve(&stuck, 1_000), SolveResult::Unsolvable);
+
+        // 山札の A♠ をめくって、場札の 2♠ を上げれば勝ち
+        let mut easy = Board::empty();
+        easy.tableau[3] = vec![card(Suit::Spade, Rank::Two, true)];
+        easy.stock = vec![card(Suit::Spade, Rank::Ace, false)];
+        let SolveResult::Solvable(moves) = solve(&easy, 1_000) else { panic!("should be solvable") };
+        assert_eq!(moves[0], SolverMove::DrawFromStock);
+        assert!(moves.iter().fold(easy, |board, &mv| board.apply(mv)).is_won());
+
+        // 3♠ が 2♠ の上に残るので、もう1周しないと勝てない
+        let mut needs_recycle = Board::empty();
+        needs_recycle.stock = [Rank::Ace, Rank::Three, Rank::Two].map(|rank| card(Suit::Spade, rank, false)).to_vec();
+        let one_pass = needs_recycle.clone().with_rules(&GameRules::draw_one().with_max_passes(Some(1)), 0);
+        assert_eq!(solve(&one_pass, 1_000), SolveResult::Unsolvable);
+        let two_passes = needs_recycle.with_rules(&GameRules::draw_one().with_max_passes(Some(2)), 0);
+        let SolveResult::Solvable(moves) = solve(&two_passes, 1_000) else { panic!("should be solvable") };
+        assert!(moves.contains(&SolverMove::RecycleWaste));
+
+        // 予算が足りなければ Unknown
+        assert_eq!(solve(&Board::dealt(1), 0), SolveResult::Unknown);
+        println!("小さい盤面のソルバーテスト、成功！🧠");
+    }
+
+    #[test]
+    fn winning_line_replays_through_the_rules() {
+        let (seed, moves) = find_winnable_seed(1, 20, 50_000).expect("one of the first seeds should be winnable");
+        let mut world = dealt_world(seed);
+        let mut mover = MoveCardSystem::new();
+
+        for mv in moves {
+            match mv {
+                SolverMove::DrawFromStock => assert!(stock_handler::deal_from_stock(&mut world)),
+                SolverMove::RecycleWaste => assert!(stock_handler::reset_waste_to_stock(&mut world)),
+                SolverMove::Move { to, .. } => {
+                    let entity = mv.card_entity(&world).expect("the solver's card should be where it said");
+                    assert_eq!(rules::validate_move(&world, entity, to), Ok(()), "{:?}", mv);
+                    mover.process_move(&mut world, entity, to);
+                }
+            }
+        }
+
+        let on_foundations: usize = (0..4).map(|i| world.stacks().len(StackType::Foundation(i))).sum();
+        assert!(rules::check_win_condition(on_foundations));
+        println!("ソルバーの手順をルール通りに再生して勝てるテスト (seed {})、成功！🏆", seed);
+    }
+}
@@ -0,0 +1,113 @@
+// src/logic/stock_handler.rs
+//! Handles logic related to clicking the Stock pile (dealing to Waste, resetting Waste).
+//! How many cards are dealt and how often the Waste may be recycled come from the `GameRules` resource.
+//! What a deal means depends on the active variant (`logic::variants`): Klondike draws to the Waste,
+//! Spider deals a card onto every Tableau column.
+
+use crate::ecs::world::World;
+use crate::components::{GameState, StackType};
+use crate::config::GameRules;
+use crate::logic::history::{self, GameCommand}; // Stock/Waste changes go through the undo history
+use crate::logic::rules::stock_waste; // Use the rule checks
+use crate::logic::scoring; // Recycling costs points
+use crate::logic::variants; // The active variant decides what dealing does
+use log::{warn, info}; // Import specific log levels
+
+/// Deals from the Stock pile the way the active variant does (`RuleSet::deal_from_stock`).
+/// Returns true if any card was dealt, false otherwise.
+pub fn deal_from_stock(world: &mut World) -> bool {
+    variants::active(world).deal_from_stock(world)
+}
+
+/// Deals `GameRules::draw_count` cards (or whatever is left) from the Stock pile to the Waste pile (Klondike).
+/// Returns true if any card was dealt, false otherwise.
+pub fn draw_to_waste(world: &mut World) -> bool {
+    info!("Attempting to deal from Stock to Waste...");
+
+    let stock_len = world.stacks().len(StackType::Stock);
+    if stock_len == 0 {
+        info!("  Stock is empty. Cannot deal.");
+        return false; // Nothing to deal
+    }
+
+    // Check rule (optional, but good practice)
+    if !stock_waste::can_deal_from_stock(false /* stock_is_empty = false */) {
+         warn!("  Rule check failed: Cannot deal from stock (logic error?).");
+         return false;
+    }
+
+    let count = (GameRules::from_world(world).draw_count as usize).clamp(1, stock_len);
+    info!("  Dealing {} card(s).", count);
+
+    // Move the top cards onto the Waste face up (recorded in History so it can be undone)
+    history::execute(world, vec![GameCommand::DrawFromStock { count }]);
+
+    info!("  {} card(s) moved to Waste.", count);
+    true // Card was dealt
+}
+
+/// Resets the Waste pile back to the Stock pile when Stock is empty
+/// and the pass limit in `GameRules::max_passes` has not been reached.
+/// Returns true if the reset was performed, false otherwise.
+pub fn reset_waste_to_stock(world: &mut World) -> bool {
+    info!("Attempting to reset Waste to Stock...");
+
+    let rules = GameRules::from_world(world);
+    let recycles_done = world.resource::<GameState>().map_or(0, |state| state.stock_recycles);
+    let stacks = world.stacks();
+    // Check rules
+    if !stock_waste::can_reset_stock_from_waste(
+        stacks.is_empty(StackType::Stock),
+        stacks.is_empty(StackType::Waste),
+        recycles_done,
+        &rules,
+    ) {
+        info!("  Cannot reset Waste to Stock (Stock not empty, Waste empty or no passes left).");
+        return false;
+    }
+
+    // Move every waste card back to Stock face down, keeping their order (recorded in History)
+    let count = stacks.len(StackType::Waste);
+    info!("  Resetting {} cards from Waste to Stock.", count);
+    history::execute(world, vec![GameCommand::RecycleWaste { count }]);
+    // Charge the recycle to the step just recorded, so undoing it gives the points back
+    scoring::record_recycle(world);
+
+    info!("  Waste pile reset to Stock complete.");
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Rank, Suit};
+    use crate::harness::{card_world, push_card};
+
+    #[test]
+    fn draw_three_with_a_single_pass() {
+        let mut world = card_world();
+        world.insert_resource(GameState::playing());
+        world.insert_resource(GameRules::draw_three().with_max_passes(Some(1)));
+        let ranks = [Rank::Ace, Rank::Two, Rank::Three, Rank::Four];
+        let cards: Vec<_> = ranks
+            .iter()
+            .map(|&rank| push_card(&mut world, Suit::Club, rank, StackType::Stock, false))
+            .collect();
+
+        // 上から3枚 (4, 3, 2) をめくる → 一番上は 2
+        assert!(deal_from_stock(&mut world));
+        assert_eq!(world.stacks().len(StackType::Waste), 3);
+        assert_eq!(world.stacks().top(StackType::Waste), Some(cards[1]));
+        // 残りは1枚だけなので1枚めくる
+        assert!(deal_from_stock(&mut world));
+        assert_eq!(world.stacks().top(StackType::Waste), Some(cards[0]));
+        assert!(!deal_from_stock(&mut world));
+
+        // 1周しかできないので、捨て札は戻せない
+        assert!(!reset_waste_to_stock(&mut world));
+        world.insert_resource(GameRules::draw_three());
+        assert!(reset_waste_to_stock(&mut world));
+        assert_eq!(world.resource::<GameState>().unwrap().stock_recycles, 1);
+        println!("3枚めくり & 周回上限のテスト、成功！🃏");
+    }
+}
@@ -0,0 +1,94 @@
+// src/logic/variants/freecell.rs
+//! フリーセルのルールだよ！ 全部表向きで配って、4つの空きマス (フリーセル) を使って解く。🧩
+//!
+//! - 場札8列 (左の4列は7枚、右の4列は6枚)。山札は無い
+//! - 場札には色違いで1つ下のカードを置ける。空の列には何でも置ける
+//! - フリーセルには1枚だけ置ける。組札はクロンダイクと同じ (スートごとにエースから)
+//! - 何枚まとめて動かせるかは空きで決まる (スーパームーブ): `(空いてるフリーセル + 1) × 2^(空いてる列)`
+
+use crate::components::{Card, StackType};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use crate::logic::rules::{self, CardColor};
+
+use super::{deal_columns, is_one_below, not_allowed, pick_up, run_cards, RuleSet, StackKinds, Variant};
+
+pub struct FreeCell;
+
+impl FreeCell {
+    /// `target` の場札に一度に動かせる枚数。移動先が空の列なら、それは空きとして数えないよ。
+    pub fn supermove_limit(&self, world: &World, target: StackType) -> usize {
+        let kinds = self.kinds();
+        let stacks = world.stacks();
+        let free_cells = kinds.free_cells().filter(|&cell| stacks.is_empty(cell)).count();
+        let empty_columns = kinds.tableaux().filter(|&column| column != target && stacks.is_empty(column)).count();
+        (free_cells + 1) << empty_columns
+    }
+}
+
+impl RuleSet for FreeCell {
+    fn variant(&self) -> Variant {
+        Variant::FreeCell
+    }
+
+    fn kinds(&self) -> StackKinds {
+        StackKinds { tableaux: 8, foundations: 4, free_cells: 4, stock: false, waste: false }
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> Vec<(StackType, Card)> {
+        let columns: Vec<(usize, usize)> = (0..8).map(|index| (0, if index < 4 { 7 } else { 6 })).collect();
+        deal_columns(&mut deck.into_iter(), &columns)
+    }
+
+    fn can_move(&self, world: &World, entity: Entity, target: StackType) -> Result<(), String> {
+        let run = pick_up(world, self.kinds(), entity, target)?;
+        match target {
+            StackType::FreeCell(_) => {
+                if run.len() != 1 {
+                    return Err("A free cell holds a single card".to_string());
+                }
+                if !world.stacks().is_empty(target) {
+                    return Err(format!("{:?} is already taken", target));
+                }
+                Ok(())
+            }
+            StackType::Foundation(index) => {
+                if run.len() != 1 {
+                    return Err("Only one card at a time can go to a foundation".to_string());
+                }
+                if rules::can_move_to_foundation(world, entity, index) {
+                    Ok(())
+                } else {
+                    Err(not_allowed(world, entity, target))
+                }
+            }
+            StackType::Tableau(_) => {
+                let cards = run_cards(world, &run);
+                let alternating = cards.windows(2).all(|pair| {
+                    CardColor::from_suit(pair[0].suit) != CardColor::from_suit(pair[1].suit)
+                        && is_one_below(pair[1].rank, pair[0].rank)
+                });
+                if !alternating {
+                    return Err("Only a run of alternating colours can be moved together".to_string());
+                }
+                let top = world.stacks().top(target).and_then(|top| world.get_component::<Card>(top));
+                if let Some(top) = top {
+                    let fits = CardColor::from_suit(cards[0].suit) != CardColor::from_suit(top.suit)
+                        && is_one_below(cards[0].rank, top.rank);
+                    if !fits {
+                        return Err(not_allowed(world, entity, target));
+                    }
+                }
+                let limit = self.supermove_limit(world, target);
+                if run.len() > limit {
+                    return Err(format!(
+                        "Only {} card(s) can be moved at once with the free cells and empty columns left",
+                        limit
+                    ));
+                }
+                Ok(())
+            }
+            _ => Err(not_allowed(world, entity, target)),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// src/logic/variants/klondike.rs
+//! クロンダイクのルールだよ！ いちばん普通のソリティア。🃏
+//!
+//! 場札7列 (i 列目は裏向き i 枚 + 表向き1枚)、残りは山札。山札は `GameRules` の枚数ずつ捨て札にめくる。
+//! 置き方のチェックは今まで通り `logic::rules` (場札: 色違いで1つ下、空の列にはキング / 組札: スートごとにエースから) を使うよ。
+//! まとめて動かす時は、並び全体も色違い・ランク降順になってないとダメ (`rules::can_move_run`)。
+
+use crate::components::{Card, StackType};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use crate::logic::rules;
+use crate::logic::stock_handler;
+
+use super::{deal_columns, deal_rest_to_stock, not_allowed, pick_up, RuleSet, StackKinds, Variant};
+
+pub struct Klondike;
+
+impl RuleSet for Klondike {
+    fn variant(&self) -> Variant {
+        Variant::Klondike
+    }
+
+    fn kinds(&self) -> StackKinds {
+        StackKinds { tableaux: 7, foundations: 4, free_cells: 0, stock: true, waste: true }
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> Vec<(StackType, Card)> {
+        let mut cards = deck.into_iter();
+        let columns: Vec<(usize, usize)> = (0..7).map(|index| (index, 1)).collect();
+        let mut dealt = deal_columns(&mut cards, &columns);
+        dealt.extend(deal_rest_to_stock(cards));
+        dealt
+    }
+
+    fn can_move(&self, world: &World, entity: Entity, target: StackType) -> Result<(), String> {
+        let run = pick_up(world, self.kinds(), entity, target)?;
+        if matches!(target, StackType::Foundation(_)) && run.len() > 1 {
+            return Err("Only one card at a time can go to a foundation".to_string());
+        }
+        if !rules::is_alternating_run(world, &run) {
+            return Err("Only a run of alternating colours in descending rank can be moved together".to_string());
+        }
+        if rules::can_move_run(world, &run, target) {
+            Ok(())
+        } else {
+            Err(not_allowed(world, entity, target))
+        }
+    }
+
+    fn deal_from_stock(&self, world: &mut World) -> bool {
+        stock_handler::draw_to_waste(world)
+    }
+
+    fn auto_move_target(&self, world: &World, entity: Entity) -> Option<StackType> {
+        // 組札のルールだけ見る (掴めるかどうかは、実際に動かす時の `can_move` でチェックされる)
+        self.kinds()
+            .foundations()
+            .find(|&to| matches!(to, StackType::Foundation(index) if rules::can_move_to_foundation(world, entity, index)))
+    }
+
+    fn allows_auto_complete(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,284 @@
+// src/logic/variants/mod.rs
+//! ソリティアの遊び方 (クロンダイク、スパイダー、フリーセル、ユーコン) を差し替えるためのモジュールだよ！🎴
+//!
+//! 遊び方ごとに違うところは `RuleSet` トレイトにまとめてある:
+//! - どんなスタックを使うか (`kinds`) と、最初の配り方 (`deck` / `deal`)
+//! - カードを動かしていいか (`can_move`) と、勝ったか (`is_won`)
+//! - 山札をクリックした時の動き (`deal_from_stock`)
+//! - 自動で動かす方針 (`auto_move_target`, `allows_auto_complete`, `follow_up`)
+//!
+//! 今どの遊び方なのかは World の `Variant` リソースで決まる (置いてなければクロンダイク)。
+//! `rules::validate_move`、`DealInitialCardsSystem`、`WinConditionSystem`、山札の処理、レイアウトは
+//! みんな `active(world)` で今のルールを引くので、1つのアプリの中で遊び方を切り替えられるよ。
+
+mod freecell;
+mod klondike;
+mod spider;
+mod yukon;
+
+pub use freecell::FreeCell;
+pub use klondike::Klondike;
+pub use spider::{Spider, SpiderSuits};
+pub use yukon::Yukon;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Card, Rank, StackType};
+use crate::ecs::entity::Entity;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+use crate::logic::deck::create_standard_deck;
+use crate::logic::history::GameCommand;
+use crate::logic::rules;
+
+/// どの遊び方で遊ぶか。World にリソースとして置いて、サーバーとも `GameStateData` でやり取りするよ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Klondike,
+    /// スパイダー (使うスートの数つき)。
+    Spider(SpiderSuits),
+    FreeCell,
+    Yukon,
+}
+
+impl Resource for Variant {}
+
+impl Variant {
+    /// 選べる遊び方の全部。
+    pub const ALL: [Variant; 6] = [
+        Variant::Klondike,
+        Variant::Spider(SpiderSuits::One),
+        Variant::Spider(SpiderSuits::Two),
+        Variant::Spider(SpiderSuits::Four),
+        Variant::FreeCell,
+        Variant::Yukon,
+    ];
+
+    /// この遊び方のルール。
+    pub fn rule_set(self) -> &'static dyn RuleSet {
+        match self {
+            Variant::Klondike => &Klondike,
+            Variant::Spider(SpiderSuits::One) => &Spider { suits: SpiderSuits::One },
+            Variant::Spider(SpiderSuits::Two) => &Spider { suits: SpiderSuits::Two },
+            Variant::Spider(SpiderSuits::Four) => &Spider { suits: SpiderSuits::Four },
+            Variant::FreeCell => &FreeCell,
+            Variant::Yukon => &Yukon,
+        }
+    }
+
+    /// JS とのやり取りに使う名前 (`"klondike"`, `"spider-2"` など)。
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Klondike => "klondike",
+            Variant::Spider(SpiderSuits::One) => "spider-1",
+            Variant::Spider(SpiderSuits::Two) => "spider-2",
+            Variant::Spider(SpiderSuits::Four) => "spider-4",
+            Variant::FreeCell => "freecell",
+            Variant::Yukon => "yukon",
+        }
+    }
+
+    /// `name` の逆。知らない名前なら `Err`。
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.name() == name)
+            .ok_or_else(|| format!("Unknown game variant {:?}", name))
+    }
+
+    /// World に置いてある遊び方 (無ければクロンダイク)。
+    pub fn from_world(world: &World) -> Self {
+        world.resource::<Variant>().copied().unwrap_or_default()
+    }
+}
+
+/// 今の World で遊んでる遊び方のルール。
+pub fn active(world: &World) -> &'static dyn RuleSet {
+    Variant::from_world(world).rule_set()
+}
+
+/// 遊び方が使うスタックの種類と数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackKinds {
+    /// 場札の列の数 (`StackType::Tableau(0..tableaux)`)。
+    pub tableaux: u8,
+    /// 組札の数 (`StackType::Foundation(0..foundations)`)。
+    pub foundations: u8,
+    /// フリーセルの数 (`StackType::FreeCell(0..free_cells)`)。
+    pub free_cells: u8,
+    /// 山札を使う？
+    pub stock: bool,
+    /// 捨て札を使う？
+    pub waste: bool,
+}
+
+impl StackKinds {
+    pub fn tableaux(&self) -> impl Iterator<Item = StackType> + Clone {
+        (0..self.tableaux).map(StackType::Tableau)
+    }
+
+    pub fn foundations(&self) -> impl Iterator<Item = StackType> + Clone {
+        (0..self.foundations).map(StackType::Foundation)
+    }
+
+    pub fn free_cells(&self) -> impl Iterator<Item = StackType> + Clone {
+        (0..self.free_cells).map(StackType::FreeCell)
+    }
+
+    /// 使うスタックを全部 (山札 → 捨て札 → フリーセル → 組札 → 場札の順で)。
+    pub fn all(&self) -> Vec<StackType> {
+        let piles = [(self.stock, StackType::Stock), (self.waste, StackType::Waste)];
+        piles
+            .into_iter()
+            .filter_map(|(used, stack)| used.then_some(stack))
+            .chain(self.free_cells())
+            .chain(self.foundations())
+            .chain(self.tableaux())
+            .collect()
+    }
+
+    /// `stack` はこの遊び方で使うスタック？
+    pub fn contains(&self, stack: StackType) -> bool {
+        match stack {
+            StackType::Tableau(index) => index < self.tableaux,
+            StackType::Foundation(index) => index < self.foundations,
+            StackType::FreeCell(index) => index < self.free_cells,
+            StackType::Stock => self.stock,
+            StackType::Waste => self.waste,
+            StackType::Hand => false,
+        }
+    }
+}
+
+/// 遊び方ごとのルール。`Variant::rule_set` か `active` で取ってきて使ってね。
+pub trait RuleSet: Sync {
+    /// どの遊び方のルールか。
+    fn variant(&self) -> Variant;
+
+    /// 使うスタックの種類と数。
+    fn kinds(&self) -> StackKinds;
+
+    /// 配る前の (シャッフルしてない) デッキ。
+    fn deck(&self) -> Vec<Card> {
+        create_standard_deck()
+    }
+
+    /// デッキの枚数。勝ち判定で「全部組札にあるか」を見るのに使う。
+    fn deck_size(&self) -> usize {
+        52
+    }
+
+    /// シャッフル済みの `deck` を配る。スタックごとに下から順に並んだ (場所, カード) を返すよ。
+    fn deal(&self, deck: Vec<Card>) -> Vec<(StackType, Card)>;
+
+    /// `entity` (場札なら上に積まれてるカードも一緒に) を `target` に動かしていい？ダメなら理由つきで `Err`。
+    fn can_move(&self, world: &World, entity: Entity, target: StackType) -> Result<(), String>;
+
+    /// 勝った？ (デフォルト: 全部のカードが組札にある)
+    fn is_won(&self, world: &World) -> bool {
+        all_on_foundations(world, self.deck_size())
+    }
+
+    /// 山札がクリックされた時の処理。何かしたら `true`。デフォルトは山札の無い遊び方用で何もしない。
+    fn deal_from_stock(&self, _world: &mut World) -> bool {
+        false
+    }
+
+    /// ダブルクリックで自動で動かす先 (デフォルト: 置ける最初の組札)。
+    fn auto_move_target(&self, world: &World, entity: Entity) -> Option<StackType> {
+        self.kinds().foundations().find(|&to| self.can_move(world, entity, to).is_ok())
+    }
+
+    /// 場札が全部表向きになったら、オートコンプリートしていい？ (必ず最後まで上げられる遊び方だけ `true`)
+    fn allows_auto_complete(&self) -> bool {
+        false
+    }
+
+    /// カードが動いた後に、同じ1手の続きとして自動でやる操作 (スパイダーの揃った列を組札へ、とか)。
+    fn follow_up(&self, _world: &World) -> Vec<GameCommand> {
+        Vec::new()
+    }
+}
+
+/// 場札の列を左から順に配る。`columns[i]` は i 列目の (裏向きの枚数, 表向きの枚数)。
+fn deal_columns(cards: &mut impl Iterator<Item = Card>, columns: &[(usize, usize)]) -> Vec<(StackType, Card)> {
+    let mut dealt = Vec::new();
+    for (index, &(face_down, face_up)) in columns.iter().enumerate() {
+        for position in 0..face_down + face_up {
+            let mut card = cards.next().expect("The deck ran out while dealing the tableau");
+            card.is_face_up = position >= face_down;
+            dealt.push((StackType::Tableau(index as u8), card));
+        }
+    }
+    dealt
+}
+
+/// 残りのカードを全部裏向きで山札に置く。
+fn deal_rest_to_stock(cards: impl Iterator<Item = Card>) -> impl Iterator<Item = (StackType, Card)> {
+    cards.map(|card| (StackType::Stock, Card { is_face_up: false, ..card }))
+}
+
+/// `deck_size` 枚のカードが全部組札にある？
+fn all_on_foundations(world: &World, deck_size: usize) -> bool {
+    let cards = world.get_all_entities_with_component::<Card>();
+    cards.len() == deck_size
+        && cards
+            .iter()
+            .all(|&entity| matches!(world.stacks().location(entity), Some((StackType::Foundation(_), _))))
+}
+
+/// 掴もうとしてるカードと一緒に運ばれるカード (自分を含む、下から順)。
+/// 共通のチェック (表向き・移動先が使われてる・山札からは動かせない・場札以外は一番上だけ・
+/// 組札から取り出していいか (`GameRules::foundation_to_tableau`)) もここでするよ。
+fn pick_up(world: &World, kinds: StackKinds, entity: Entity, target: StackType) -> Result<Vec<Entity>, String> {
+    let card = world
+        .get_component::<Card>(entity)
+        .ok_or_else(|| format!("Entity {:?} is not a card", entity))?;
+    let (from, index) = world
+        .stacks()
+        .location(entity)
+        .ok_or_else(|| format!("Card {:?} is not in any stack", entity))?;
+
+    if !card.is_face_up {
+        return Err(format!("Card {:?} is face down", entity));
+    }
+    if from == target {
+        return Err(format!("Card {:?} is already in {:?}", entity, target));
+    }
+    if !kinds.contains(target) {
+        return Err(format!("{:?} is not used in this game", target));
+    }
+    let cards = world.stacks().cards(from);
+    match from {
+        StackType::Stock => return Err("Cards in the stock can only be dealt".to_string()),
+        StackType::Tableau(_) => {}
+        StackType::Foundation(_) if !rules::can_take_from_foundation(world, entity) => {
+            return Err("Cards cannot be taken off the foundations in this game".to_string())
+        }
+        _ if index + 1 != cards.len() => return Err(format!("Only the top card of {:?} can be moved", from)),
+        _ => {}
+    }
+    Ok(cards[index..].to_vec())
+}
+
+/// 運ぶカードを下から順に。
+fn run_cards(world: &World, run: &[Entity]) -> Vec<Card> {
+    run.iter().filter_map(|&entity| world.get_component::<Card>(entity).cloned()).collect()
+}
+
+/// ルール上その場所には置けない時の理由。
+fn not_allowed(world: &World, entity: Entity, target: StackType) -> String {
+    match world.get_component::<Card>(entity) {
+        Some(card) => format!("Moving {:?} of {:?} to {:?} is not allowed by the rules", card.rank, card.suit, target),
+        None => format!("Moving {:?} to {:?} is not allowed by the rules", entity, target),
+    }
+}
+
+/// `lower` の上に `upper` を (ランクが1つ下で) 積める？
+fn is_one_below(upper: Rank, lower: Rank) -> bool {
+    upper as u8 + 1 == lower as u8
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,176 @@
+// src/logic/variants/spider.rs
+//! スパイダーのルールだよ！ 2組分 (104枚) のカードで遊ぶ。🕷️
+//!
+//! - 場札10列 (左の4列は6枚、右の6列は5枚、一番上だけ表向き)、残りの50枚は山札
+//! - 場札にはスートに関係なくランクが1つ下のカードを置ける。空の列には何でも置ける
+//! - まとめて動かせるのは同じスートで並んだカードだけ
+//! - K から A まで同じスートで揃ったら、その13枚は組札へ (`follow_up` で自動的に)
+//! - 山札をクリックすると、全部の列に1枚ずつ表向きで配る (空の列があると配れない)
+//!
+//! 使うスートの数 (1 / 2 / 4) で難しさが変わるよ。どれでも合計は104枚。
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::card::ALL_RANKS;
+use crate::components::{Card, Rank, StackType, Suit};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use crate::logic::history::{self, GameCommand};
+use log::info;
+
+use super::{deal_columns, deal_rest_to_stock, is_one_below, not_allowed, pick_up, run_cards, RuleSet, StackKinds, Variant};
+
+/// 揃った列の枚数 (K から A まで)。
+const COMPLETE_RUN: usize = 13;
+
+/// スパイダーで使うスートの数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpiderSuits {
+    /// スペードだけ (8組)。
+    One,
+    /// スペードとハート (4組ずつ)。
+    Two,
+    /// 4スート全部 (2組ずつ)。
+    Four,
+}
+
+impl SpiderSuits {
+    pub fn suits(self) -> &'static [Suit] {
+        match self {
+            SpiderSuits::One => &[Suit::Spade],
+            SpiderSuits::Two => &[Suit::Spade, Suit::Heart],
+            SpiderSuits::Four => &[Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade],
+        }
+    }
+}
+
+pub struct Spider {
+    pub suits: SpiderSuits,
+}
+
+impl RuleSet for Spider {
+    fn variant(&self) -> Variant {
+        Variant::Spider(self.suits)
+    }
+
+    fn kinds(&self) -> StackKinds {
+        StackKinds { tableaux: 10, foundations: 8, free_cells: 0, stock: true, waste: false }
+    }
+
+    fn deck(&self) -> Vec<Card> {
+        let suits = self.suits.suits();
+        let copies = 8 / suits.len();
+        (0..copies)
+            .flat_map(|_| suits.iter())
+            .flat_map(|&suit| ALL_RANKS.iter().map(move |&rank| Card::new(suit, rank)))
+            .collect()
+    }
+
+    fn deck_size(&self) -> usize {
+        104
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> Vec<(StackType, Card)> {
+        let mut cards = deck.into_iter();
+        let columns: Vec<(usize, usize)> = (0..10).map(|index| if index < 4 { (5, 1) } else { (4, 1) }).collect();
+        let mut dealt = deal_columns(&mut cards, &columns);
+        dealt.extend(deal_rest_to_stock(cards));
+        dealt
+    }
+
+    fn can_move(&self, world: &World, entity: Entity, target: StackType) -> Result<(), String> {
+        let run = pick_up(world, self.kinds(), entity, target)?;
+        if matches!(world.stacks().location(entity), Some((StackType::Foundation(_), _))) {
+            return Err("Completed runs stay on the foundation".to_string());
+        }
+        let cards = run_cards(world, &run);
+        if !is_same_suit_run(&cards) {
+            return Err("Only a run of one suit can be moved together".to_string());
+        }
+        match target {
+            StackType::Tableau(_) => {
+                let top = world.stacks().top(target).and_then(|top| world.get_component::<Card>(top));
+                match top {
+                    // 空の列には何でも置ける
+                    None => Ok(()),
+                    Some(top) if is_one_below(cards[0].rank, top.rank) => Ok(()),
+                    Some(_) => Err(not_allowed(world, entity, target)),
+                }
+            }
+            StackType::Foundation(_) => {
+                if cards.len() == COMPLETE_RUN && cards[0].rank == Rank::King && world.stacks().is_empty(target) {
+                    Ok(())
+                } else {
+                    Err("Only a complete King-to-Ace run of one suit can go to an empty foundation".to_string())
+                }
+            }
+            _ => Err(not_allowed(world, entity, target)),
+        }
+    }
+
+    fn deal_from_stock(&self, world: &mut World) -> bool {
+        let kinds = self.kinds();
+        let stacks = world.stacks();
+        let stock = stacks.cards(StackType::Stock);
+        if stock.is_empty() {
+            info!("  Stock is empty. Cannot deal.");
+            return false;
+        }
+        if kinds.tableaux().any(|column| stacks.is_empty(column)) {
+            info!("  Every column needs a card before dealing from the stock.");
+            return false;
+        }
+
+        // 山札の上から1枚ずつ、左の列から順に表向きで配る (1回の操作として履歴に残す)
+        let commands: Vec<GameCommand> = kinds
+            .tableaux()
+            .zip(stock.iter().rev())
+            .enumerate()
+            .flat_map(|(dealt, (to, &entity))| {
+                let index = stock.len() - 1 - dealt;
+                [
+                    GameCommand::MoveRun { from: StackType::Stock, index, to, count: 1 },
+                    GameCommand::Flip { entity, face_up: true },
+                ]
+            })
+            .collect();
+        history::execute(world, commands);
+        true
+    }
+
+    fn follow_up(&self, world: &World) -> Vec<GameCommand> {
+        let kinds = self.kinds();
+        let stacks = world.stacks();
+        let Some(to) = kinds.foundations().find(|&foundation| stacks.is_empty(foundation)) else {
+            return Vec::new();
+        };
+        for from in kinds.tableaux() {
+            let cards = stacks.cards(from);
+            let Some(index) = cards.len().checked_sub(COMPLETE_RUN) else { continue };
+            let run = run_cards(world, &cards[index..]);
+            let complete = run.len() == COMPLETE_RUN
+                && run[0].rank == Rank::King
+                && run.iter().all(|card| card.is_face_up)
+                && is_same_suit_run(&run);
+            if !complete {
+                continue;
+            }
+            let mut commands = vec![GameCommand::MoveRun { from, index, to, count: COMPLETE_RUN }];
+            // 揃った列の下に裏向きのカードが残ったら、それもめくる
+            if let Some(&below) = index.checked_sub(1).map(|below| &cards[below]) {
+                if world.get_component::<Card>(below).is_some_and(|card| !card.is_face_up) {
+                    commands.push(GameCommand::Flip { entity: below, face_up: true });
+                }
+            }
+            return commands;
+        }
+        Vec::new()
+    }
+}
+
+/// 同じスートで、ランクが1つずつ下がっていく並び？ (1枚だけなら `true`)
+fn is_same_suit_run(cards: &[Card]) -> bool {
+    cards
+        .windows(2)
+        .all(|pair| pair[0].suit == pair[1].suit && is_one_below(pair[1].rank, pair[0].rank))
+}
@@ -0,0 +1,197 @@
+// src/logic/variants/tests.rs
+//! variants モジュール (遊び方ごとのルール) のユニットテスト。
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::components::{StackInfo, Suit};
+use crate::harness::{card_world, push_card};
+use crate::logic::history;
+use crate::systems::deal_system::DealInitialCardsSystem;
+
+// --- テスト用ヘルパー関数 ---
+
+/// `variant` で遊ぶ空っぽの World を作る。
+fn world_for(variant: Variant) -> World {
+    let mut world = card_world();
+    world.insert_resource(variant);
+    world
+}
+
+/// K から `lowest` まで、同じスートで積む (下から順に返す)。
+fn push_run(world: &mut World, suit: Suit, lowest: Rank, stack_type: StackType) -> Vec<Entity> {
+    crate::components::card::ALL_RANKS
+        .iter()
+        .rev()
+        .filter(|&&rank| rank as u8 >= lowest as u8)
+        .map(|&rank| push_card(world, suit, rank, stack_type, true))
+        .collect()
+}
+
+/// 配った後の (スタックごとの枚数, 表向きの枚数)。
+fn dealt_counts(variant: Variant) -> HashMap<StackType, (usize, usize)> {
+    let mut world = world_for(variant);
+    DealInitialCardsSystem.execute(&mut world, 42);
+    let mut counts = HashMap::new();
+    for (stack_info, card) in world.query::<(&StackInfo, &Card)>() {
+        let count = counts.entry(stack_info.stack_type).or_insert((0, 0));
+        count.0 += 1;
+        if card.is_face_up {
+            count.1 += 1;
+        }
+    }
+    counts
+}
+
+// --- テスト ---
+
+#[test]
+fn variant_names_round_trip() {
+    for variant in Variant::ALL {
+        assert_eq!(Variant::from_name(variant.name()), Ok(variant));
+        assert_eq!(variant.rule_set().variant(), variant);
+    }
+    assert!(Variant::from_name("pyramid").is_err());
+    assert_eq!(Variant::from_world(&World::new()), Variant::Klondike);
+    println!("遊び方の名前テスト、成功！🎴");
+}
+
+#[test]
+fn each_variant_deals_its_own_layout() {
+    let klondike = dealt_counts(Variant::Klondike);
+    assert_eq!(klondike[&StackType::Stock], (24, 0));
+    assert_eq!(klondike[&StackType::Tableau(6)], (7, 1));
+
+    let spider = dealt_counts(Variant::Spider(SpiderSuits::Two));
+    assert_eq!(spider[&StackType::Stock], (50, 0));
+    assert_eq!(spider[&StackType::Tableau(0)], (6, 1));
+    assert_eq!(spider[&StackType::Tableau(9)], (5, 1));
+    assert_eq!(spider.values().map(|count| count.0).sum::<usize>(), 104);
+
+    let freecell = dealt_counts(Variant::FreeCell);
+    assert_eq!(freecell[&StackType::Tableau(0)], (7, 7));
+    assert_eq!(freecell[&StackType::Tableau(7)], (6, 6));
+    assert!(!freecell.contains_key(&StackType::Stock));
+
+    let yukon = dealt_counts(Variant::Yukon);
+    assert_eq!(yukon[&StackType::Tableau(0)], (1, 1));
+    assert_eq!(yukon[&StackType::Tableau(6)], (11, 5));
+    assert_eq!(yukon.values().map(|count| count.0).sum::<usize>(), 52);
+    println!("遊び方ごとの配り方テスト、成功！🃏");
+}
+
+#[test]
+fn spider_moves_same_suit_runs_and_clears_complete_ones() {
+    let mut world = world_for(Variant::Spider(SpiderSuits::Two));
+    let rules = active(&world);
+    let hidden = push_card(&mut world, Suit::Heart, Rank::Two, StackType::Tableau(0), false);
+    let run = push_run(&mut world, Suit::Spade, Rank::Two, StackType::Tableau(0));
+    let ace = push_card(&mut world, Suit::Spade, Rank::Ace, StackType::Tableau(1), true);
+    let mixed = push_card(&mut world, Suit::Heart, Rank::Three, StackType::Tableau(2), true);
+    push_card(&mut world, Suit::Spade, Rank::Two, StackType::Tableau(2), true);
+    push_card(&mut world, Suit::Heart, Rank::Four, StackType::Tableau(3), true);
+
+    // スートが混ざった並びはまとめて動かせない。同じスートの並びなら、スートが違っても1つ下に置ける
+    assert!(rules.can_move(&world, mixed, StackType::Tableau(4)).is_err());
+    assert!(rules.can_move(&world, run[10], StackType::Tableau(3)).is_ok());
+
+    // A を置いて K..A が揃ったら、同じ1手で組札へ行って下の裏向きのカードがめくれる
+    rules.can_move(&world, ace, StackType::Tableau(0)).unwrap();
+    history::execute(&mut world, vec![GameCommand::MoveRun { from: StackType::Tableau(1), index: 0, to: StackType::Tableau(0), count: 1 }]);
+    assert_eq!(world.stacks().len(StackType::Foundation(0)), 13);
+    assert_eq!(world.stacks().cards(StackType::Tableau(0)), &[hidden]);
+    assert!(world.get_component::<Card>(hidden).unwrap().is_face_up);
+
+    // undo すると全部まとめて戻る
+    assert!(history::undo(&mut world));
+    assert_eq!(world.stacks().len(StackType::Tableau(0)), 13);
+    assert_eq!(world.stacks().top(StackType::Tableau(1)), Some(ace));
+    assert!(!world.get_component::<Card>(hidden).unwrap().is_face_up);
+    println!("スパイダーの揃った列テスト、成功！🕷️");
+}
+
+#[test]
+fn spider_stock_needs_every_column_filled() {
+    let mut world = world_for(Variant::Spider(SpiderSuits::One));
+    for column in 0..9 {
+        push_card(&mut world, Suit::Spade, Rank::King, StackType::Tableau(column), true);
+    }
+    for _ in 0..10 {
+        push_card(&mut world, Suit::Spade, Rank::Five, StackType::Stock, false);
+    }
+    assert!(!active(&world).deal_from_stock(&mut world), "空の列があると配れない");
+
+    push_card(&mut world, Suit::Spade, Rank::King, StackType::Tableau(9), true);
+    assert!(active(&world).deal_from_stock(&mut world));
+    assert!(world.stacks().is_empty(StackType::Stock));
+    for column in 0..10 {
+        let top = world.stacks().top(StackType::Tableau(column)).unwrap();
+        assert!(world.get_component::<Card>(top).unwrap().is_face_up);
+    }
+    println!("スパイダーの山札テスト、成功！🕸️");
+}
+
+#[test]
+fn freecell_limits_supermoves_by_free_space() {
+    let mut world = world_for(Variant::FreeCell);
+    let rules = active(&world);
+    let freecell = FreeCell;
+    push_card(&mut world, Suit::Club, Rank::Nine, StackType::Tableau(0), true);
+    let eight = push_card(&mut world, Suit::Heart, Rank::Eight, StackType::Tableau(1), true);
+    push_card(&mut world, Suit::Spade, Rank::Seven, StackType::Tableau(1), true);
+    push_card(&mut world, Suit::Diamond, Rank::Six, StackType::Tableau(1), true);
+    for column in 2..8 {
+        push_card(&mut world, Suit::Club, Rank::King, StackType::Tableau(column), true);
+    }
+
+    // フリーセル4つ空き、空の列なし → 5枚まで
+    assert_eq!(freecell.supermove_limit(&world, StackType::Tableau(0)), 5);
+    assert!(rules.can_move(&world, eight, StackType::Tableau(0)).is_ok());
+
+    // フリーセルを3つ埋めると2枚まで → 3枚の並びは動かせない
+    for cell in 0..3 {
+        push_card(&mut world, Suit::Heart, Rank::King, StackType::FreeCell(cell), true);
+    }
+    assert_eq!(freecell.supermove_limit(&world, StackType::Tableau(0)), 2);
+    assert!(rules.can_move(&world, eight, StackType::Tableau(0)).is_err());
+
+    // フリーセルには1枚だけ、埋まってるセルには置けない
+    let six = world.stacks().top(StackType::Tableau(1)).unwrap();
+    assert!(rules.can_move(&world, six, StackType::FreeCell(3)).is_ok());
+    assert!(rules.can_move(&world, six, StackType::FreeCell(0)).is_err());
+    assert!(rules.can_move(&world, eight, StackType::FreeCell(3)).is_err());
+    println!("フリーセルのスーパームーブテスト、成功！🧩");
+}
+
+#[test]
+fn yukon_moves_any_face_up_group() {
+    let mut world = world_for(Variant::Yukon);
+    push_card(&mut world, Suit::Spade, Rank::Nine, StackType::Tableau(0), true);
+    let hidden = push_card(&mut world, Suit::Heart, Rank::Seven, StackType::Tableau(1), false);
+    let eight = push_card(&mut world, Suit::Diamond, Rank::Eight, StackType::Tableau(1), true);
+    push_card(&mut world, Suit::Club, Rank::Two, StackType::Tableau(1), true);
+
+    // 上の2が並んでなくても、8ごと9の上に動かせる
+    assert!(active(&world).can_move(&world, eight, StackType::Tableau(0)).is_ok());
+    // 裏向きのカードは掴めないし、山札も無い
+    assert!(active(&world).can_move(&world, hidden, StackType::Tableau(0)).is_err());
+    assert!(!active(&world).kinds().contains(StackType::Stock));
+    println!("ユーコンのグループ移動テスト、成功！🏔️");
+}
+
+#[test]
+fn win_check_counts_the_whole_deck() {
+    let mut world = world_for(Variant::Spider(SpiderSuits::One));
+    for foundation in 0..4 {
+        push_run(&mut world, Suit::Spade, Rank::Ace, StackType::Foundation(foundation));
+    }
+    // 52枚だけではスパイダーの勝ちじゃない (クロンダイクなら勝ち)
+    assert!(!active(&world).is_won(&world));
+    assert!(Variant::Klondike.rule_set().is_won(&world));
+
+    for foundation in 4..8 {
+        push_run(&mut world, Suit::Spade, Rank::Ace, StackType::Foundation(foundation));
+    }
+    assert!(active(&world).is_won(&world));
+    println!("遊び方ごとの勝ち判定テスト、成功！🏆");
+}
@@ -0,0 +1,45 @@
+// src/logic/variants/yukon.rs
+//! ユーコンのルールだよ！ 山札が無くて、最初から全部のカードが場札に配られる。🏔️
+//!
+//! 場札7列: 1列目は表向き1枚、i 列目 (i ≥ 1) は裏向き i 枚 + 表向き5枚。
+//! 置き方はクロンダイクと同じ (色違いで1つ下、空の列にはキング) だけど、
+//! 表向きのカードなら上に何が積まれてても (並んでなくても) まとめて動かせるのがユーコンの特徴。
+
+use crate::components::{Card, StackType};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+
+use crate::logic::rules;
+
+use super::{deal_columns, not_allowed, pick_up, RuleSet, StackKinds, Variant};
+
+pub struct Yukon;
+
+impl RuleSet for Yukon {
+    fn variant(&self) -> Variant {
+        Variant::Yukon
+    }
+
+    fn kinds(&self) -> StackKinds {
+        StackKinds { tableaux: 7, foundations: 4, free_cells: 0, stock: false, waste: false }
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> Vec<(StackType, Card)> {
+        let columns: Vec<(usize, usize)> =
+            (0..7).map(|index| if index == 0 { (0, 1) } else { (index, 5) }).collect();
+        deal_columns(&mut deck.into_iter(), &columns)
+    }
+
+    fn can_move(&self, world: &World, entity: Entity, target: StackType) -> Result<(), String> {
+        let run = pick_up(world, self.kinds(), entity, target)?;
+        if matches!(target, StackType::Foundation(_)) && run.len() > 1 {
+            return Err("Only one card at a time can go to a foundation".to_string());
+        }
+        // 掴んだカード (一番下) の置き方だけ見る。上に積まれてるカードの並びは問わない
+        if rules::is_move_valid(world, entity, target) {
+            Ok(())
+        } else {
+            Err(not_allowed(world, entity, target))
+        }
+    }
+}
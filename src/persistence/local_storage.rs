@@ -0,0 +1,36 @@
+// src/persistence/local_storage.rs
+//! ブラウザの `localStorage` にセーブする保存先だよ！🌐
+
+use web_sys::Storage;
+
+use super::GameStorage;
+
+/// `window.localStorage`。プライベートブラウズなどで使えない時は `new` がエラーになるよ。
+pub struct LocalStorage {
+    storage: Storage,
+}
+
+impl LocalStorage {
+    pub fn new() -> Result<Self, String> {
+        let window = web_sys::window().ok_or("There is no window")?;
+        let storage = window
+            .local_storage()
+            .map_err(|e| format!("localStorage is not available: {:?}", e))?
+            .ok_or("localStorage is not available")?;
+        Ok(Self { storage })
+    }
+}
+
+impl GameStorage for LocalStorage {
+    fn load(&self, key: &str) -> Result<Option<String>, String> {
+        self.storage.get_item(key).map_err(|e| format!("Failed to read {:?} from localStorage: {:?}", key, e))
+    }
+
+    fn store(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.storage.set_item(key, value).map_err(|e| format!("Failed to write {:?} to localStorage: {:?}", key, e))
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), String> {
+        self.storage.remove_item(key).map_err(|e| format!("Failed to remove {:?} from localStorage: {:?}", key, e))
+    }
+}
@@ -0,0 +1,112 @@
+// src/persistence/mod.rs
+//! ゲームのセーブ / ロードだよ！💾 ページを再読み込みしても続きから遊べるように。
+//!
+//! セーブデータ (`SavedGame`) はバージョン付きの JSON で、中身は:
+//! - カード (`World::snapshot`)・シード・ルール・遊び方・点数・時計 (`GameStateData`、サーバーとやり取りするのと同じ形)
+//! - 勝ち負けの状態、undo / redo の履歴、棋譜 (`GameRecord`)
+//!
+//! 保存先は `GameStorage` トレイトで差し替えられる。ブラウザでは `LocalStorage` (web feature)、
+//! テストではメモリに置くだけの `MemoryStorage` を使うよ。
+//! 読み込んだカードは `app::state_handler::restore_saved_game` がサーバーの状態と同じ道筋 (`World::restore_mapped`) で World に反映して、
+//! 残り (履歴とか) は `SavedGame::restore_resources` で戻す。
+
+#[cfg(feature = "web")]
+mod local_storage;
+mod storage;
+
+#[cfg(feature = "web")]
+pub use local_storage::LocalStorage;
+pub use storage::{GameStorage, MemoryStorage};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{GameClock, GameState, GameStatus};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use crate::logic::game_record::GameRecord;
+use crate::logic::history::History;
+use crate::protocol::GameStateData;
+
+/// 今のセーブデータの形式。形式を変えたら上げてね (古いデータを読めるかは `from_json` で決める)。
+/// 2: カードが `CardData` の一覧から `WorldSnapshot` になった。
+/// 3: 履歴の1回分ごとに、ついた点数も入るようになった。
+pub const SAVE_VERSION: u32 = 3;
+/// `GameStorage` に保存する時のキー。
+pub const SAVE_KEY: &str = "ecs_wasm_game5.saved_game";
+
+/// 1ゲーム分のセーブデータ。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    /// カード (エンティティ・中身・場所)、シード、ルール、遊び方、点数、時計。
+    pub state: GameStateData,
+    /// 勝ち負けの状態 (勝った後のゲームを読み込んでも、もう一回勝ったことにならないように)。
+    pub status: GameStatus,
+    /// undo / redo の履歴。カードのエンティティは `state` の番号のまま入ってる。
+    pub history: History,
+    /// 棋譜 (配る前のセーブだと無い)。
+    #[serde(default)]
+    pub record: Option<GameRecord>,
+}
+
+impl SavedGame {
+    /// 今の World をセーブデータにする。
+    pub fn capture(world: &World) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            state: GameStateData::from_world(world),
+            status: world.resource::<GameState>().map_or(GameStatus::Playing, |state| state.status.clone()),
+            history: world.resource::<History>().cloned().unwrap_or_default(),
+            record: world.resource::<GameRecord>().cloned(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("Failed to serialize the saved game: {}", e))
+    }
+
+    /// JSON から読む。知らない形式 (新しすぎるデータ) ならエラー。
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let saved: SavedGame = serde_json::from_str(json).map_err(|e| format!("Failed to read the saved game: {}", e))?;
+        if saved.version != SAVE_VERSION {
+            return Err(format!("Unsupported saved game version {} (expected {})", saved.version, SAVE_VERSION));
+        }
+        Ok(saved)
+    }
+
+    /// カード以外 (勝ち負け・時計・履歴・棋譜) を World に戻す。カードはもう反映してある前提で、
+    /// `local` はセーブデータのエンティティ → 今の World のエンティティ (履歴の付け替えに使う)。
+    pub fn restore_resources(&self, world: &mut World, local: impl Fn(Entity) -> Option<Entity>) {
+        if let Some(state) = world.resource_mut::<GameState>() {
+            state.status = self.status.clone();
+        }
+        // 時計は丸ごと戻す (隠れた時にセーブしてたら止まってるので、動かしてから)
+        if let Some(clock) = self.state.clock {
+            world.insert_resource(GameClock { paused: false, ..clock });
+        }
+        let mut history = self.history.clone();
+        history.remap_entities(local);
+        world.insert_resource(history);
+        if let Some(record) = &self.record {
+            world.insert_resource(record.clone());
+        }
+    }
+}
+
+/// 今の World を `storage` にセーブする。
+pub fn save(world: &World, storage: &mut dyn GameStorage) -> Result<(), String> {
+    storage.store(SAVE_KEY, &SavedGame::capture(world).to_json()?)
+}
+
+/// `storage` のセーブデータを読む (無ければ `None`)。
+pub fn load(storage: &dyn GameStorage) -> Result<Option<SavedGame>, String> {
+    storage.load(SAVE_KEY)?.map(|json| SavedGame::from_json(&json)).transpose()
+}
+
+/// セーブデータを消す。
+pub fn clear(storage: &mut dyn GameStorage) -> Result<(), String> {
+    storage.remove(SAVE_KEY)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,38 @@
+// src/persistence/storage.rs
+//! セーブデータの保存先 (文字列のキー → 文字列の値) だよ！🗄️
+
+use std::collections::HashMap;
+
+/// セーブデータの保存先。ブラウザなら `LocalStorage`、テストなら `MemoryStorage`。
+pub trait GameStorage {
+    /// `key` の値 (無ければ `None`)。
+    fn load(&self, key: &str) -> Result<Option<String>, String>;
+
+    /// `key` に `value` を書く (前の値は上書き)。
+    fn store(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// `key` を消す (無くてもOK)。
+    fn remove(&mut self, key: &str) -> Result<(), String>;
+}
+
+/// メモリに置いておくだけの保存先 (テスト用。ページを閉じたら消える)。
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStorage {
+    values: HashMap<String, String>,
+}
+
+impl GameStorage for MemoryStorage {
+    fn load(&self, key: &str) -> Result<Option<String>, String> {
+        Ok(self.values.get(key).cloned())
+    }
+
+    fn store(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.values.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), String> {
+        self.values.remove(key);
+        Ok(())
+    }
+}
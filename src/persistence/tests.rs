@@ -0,0 +1,73 @@
+// src/persistence/tests.rs
+//! persistence モジュール (セーブ / ロード) のユニットテスト。
+
+use super::*;
+use crate::components::{Card, StackInfo, StackType};
+use crate::ecs::entity_map::EntityMap;
+use crate::events::{MoveRequested, StockClicked};
+use crate::harness::GameHarness;
+use crate::logic::history;
+use crate::logic::hints::{self, HintMove};
+use crate::systems::deal_system::DealInitialCardsSystem;
+
+/// カードごとの (場所, 向き) を場所順に。2つの World が同じ盤面か比べる用。
+fn board(world: &World) -> Vec<(StackType, u8, bool)> {
+    let mut cards: Vec<_> = world
+        .query::<(&StackInfo, &Card)>()
+        .map(|(stack_info, card)| (stack_info.stack_type, stack_info.position_in_stack, card.is_face_up))
+        .collect();
+    cards.sort_by_key(|&(stack_type, position, _)| (format!("{:?}", stack_type), position));
+    cards
+}
+
+#[test]
+fn a_saved_game_resumes_with_its_history() {
+    let mut played = GameHarness::new();
+    DealInitialCardsSystem.execute(&mut played.world, 99);
+    let dealt = board(&played.world);
+    for _ in 0..15 {
+        match hints::best_hint(&played.world).map(|hint| hint.action) {
+            Some(HintMove::Move { entity, to, .. }) => played.send(MoveRequested { entity, target: to }),
+            Some(_) => played.send(StockClicked),
+            None => break,
+        }
+        played.tick();
+    }
+
+    let mut storage = MemoryStorage::default();
+    assert!(load(&storage).unwrap().is_none());
+    save(&played.world, &mut storage).unwrap();
+    let saved = load(&storage).unwrap().expect("セーブしたはず");
+    assert_eq!(saved.state.seed, Some(99));
+    assert_eq!(saved.state.world.count::<Card>(), 52);
+    assert_eq!(saved.record.as_ref().map(|record| record.moves.len()), Some(15));
+
+    // 再読み込みした後の World: エンティティの番号はずれてる
+    let mut resumed = GameHarness::new();
+    resumed.world.create_entity();
+    // `state_handler` がやるのと同じように、セーブデータのカードを新しいエンティティで作る
+    let mut local = EntityMap::default();
+    resumed.world.restore_mapped(&saved.state.world, &mut local).unwrap();
+    saved.restore_resources(&mut resumed.world, |entity| local.local(entity));
+    assert_eq!(board(&resumed.world), board(&played.world));
+    assert_eq!(GameClock::from_world(&resumed.world).moves, GameClock::from_world(&played.world).moves);
+    let plays = |world: &World| world.resource::<GameRecord>().map(|record| record.moves.iter().map(|recorded| recorded.play).collect::<Vec<_>>());
+    assert_eq!(plays(&resumed.world), plays(&played.world));
+
+    // 履歴も付け替わってるので、全部 undo すると配った直後に戻る (めくったカードも裏に戻る)
+    while history::undo(&mut resumed.world) {}
+    assert_eq!(board(&resumed.world), dealt);
+
+    clear(&mut storage).unwrap();
+    assert!(load(&storage).unwrap().is_none());
+    println!("セーブして続きから遊ぶテスト、成功！💾");
+}
+
+#[test]
+fn unknown_versions_are_rejected() {
+    let json = SavedGame::capture(&World::new()).to_json().unwrap();
+    assert!(SavedGame::from_json(&json).is_ok());
+    assert!(SavedGame::from_json(&json.replace(&format!("\"version\":{}", SAVE_VERSION), "\"version\":99")).is_err());
+    assert!(SavedGame::from_json("not json").is_err());
+    println!("セーブデータのバージョンチェックテスト、成功！🔢");
+}
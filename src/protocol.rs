@@ -11,7 +11,13 @@ use serde::{Serialize, Deserialize};
 // ゲーム内の型もメッセージで使うからインポートしておくね！
 // (TODO: もしこれらの型が Serialize/Deserialize を実装してなかったら、後で追加する必要があるよ！)
 use crate::ecs::entity::Entity; // エンティティID (どのカードかを示すためとか)
-use crate::components::card::{Suit, Rank}; // カードのスートやランク
+use crate::ecs::world::World; // 状態を集める元
+use crate::ecs::snapshot::{SnapshotDelta, WorldSnapshot}; // カードの中身と場所 (と、その差分)
+use crate::components::GameState; // シードと捨て札を戻した回数
+use crate::config::GameRules; // 山札のめくり枚数などのルール
+use crate::components::score::Score; // 点数
+use crate::components::game_state::GameClock; // 経過時間と手数
+use crate::logic::variants::Variant; // 遊び方 (クロンダイク、スパイダーなど)
 // ★修正: StackType を pub use する！★
 pub use crate::components::stack::StackType; // スタックの種類 (場札、組札、山札など)
 // ↓↓↓ Position もメッセージで使う可能性があるのでインポートしておく
@@ -42,15 +48,18 @@ pub enum ClientMessage {
         //       (例: moved_entities: Vec<Entity> とか)
     },
 
-    // TODO: 他にも必要そうなメッセージを追加していく！
-    // 例:
-    // /// 山札をクリックしてカードをめくるアクション
-    // DrawFromStock,
-    // /// Waste（めくった札置き場）から山札にカードを戻すアクション (クロンダイクのルールによる)
-    // ResetWasteToStock,
+    /// 山札 (Stock) をクリックしてカードを1枚めくるアクション。
+    DrawFromStock,
+    /// 山札が空の時に、Waste（めくった札置き場）のカードを山札に戻すアクション (クロンダイクのルール)。
+    ResetWasteToStock,
+    /// 1つ前の操作を元に戻したい。マルチプレイで許可するかどうかはサーバー次第だよ。
+    Undo,
+    /// 元に戻した操作をやり直したい。
+    Redo,
     /// ゲームの状態を要求する (接続直後とか？)
     RequestGameState,
     /// 初期ゲーム状態をサーバーに提供するためのメッセージ！
+    /// サーバーが使うのはシードと遊び方だけで、カードはサーバーが自分で配り直すよ (クライアントの盤面は信用しない)。
     ProvideInitialState { initial_state: GameStateData },
     /// 生存確認のためのメッセージ（接続が切れてないか確認）
     Ping,
@@ -68,13 +77,25 @@ pub enum ServerMessage {
         your_player_id: PlayerId,
         /// ゲームの初期状態 (もしかしたら GameStateUpdate でまとめて送られてくるかも？)
         initial_game_state: GameStateData, // GameStateData は下で定義！
+        /// この状態の通し番号 (`GameStateDelta::sequence` と同じ数え方)。次の差分はこれ + 1 から。
+        #[serde(default)]
+        sequence: u64,
     },
 
     /// ゲームの現在の状態をまるごと送ってくるよ。
-    /// 誰かがカードを動かしたり、プレイヤーが参加/退出したりした時に送られてくる想定。
+    /// 新しいゲームが始まった時、操作を断られた人を戻す時、`RequestGameState` を送った時に来る。
     GameStateUpdate {
         /// 最新のゲーム状態。
         current_game_state: GameStateData,
+        /// この状態の通し番号。次の差分はこれ + 1 から。
+        #[serde(default)]
+        sequence: u64,
+    },
+
+    /// 前の状態からの差分だよ。誰かがカードを動かした時に全員に送られてくる。
+    /// 通し番号が飛んでたら (差分を取りこぼしたら)、`RequestGameState` でまるごと送り直してもらってね。
+    GameStateDelta {
+        delta: GameStateDelta,
     },
 
     /// カード移動リクエストが不正だった場合に、サーバーが送ってくるよ。
@@ -116,54 +137,128 @@ pub type PlayerId = u32;
 /// ゲームの状態全体を表すデータ構造だよ。
 /// ServerMessage の GameJoined や GameStateUpdate で使われる。
 /// サーバーから送られてきたこの情報をもとに、クライアント側の `World` を更新する感じになる。
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct GameStateData {
     /// ゲームに参加しているプレイヤーの情報リスト。
     pub players: Vec<PlayerData>,
-    /// 現在の全てのカードの状態リスト。
-    pub cards: Vec<CardData>,
+    /// カードの中身と場所 (`World::snapshot`)。エンティティは送った側の World のものなので、
+    /// 受け取った側は `World::restore_mapped` で自分のエンティティに付け替えてね。
+    pub world: WorldSnapshot,
+    /// このゲームを配った時のシャッフルのシード (分からなければ `None`)。
+    /// 同じシードを `GameApp::deal_with_seed` に渡せば、同じ盤面を再現できるよ🎲
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// サーバーが使ってるルール (山札のめくり枚数、周回上限など)。クライアントはこれに合わせるよ。
+    /// クライアントから送る初期状態では無視される (ルールを決めるのはサーバー)。
+    #[serde(default)]
+    pub rules: Option<GameRules>,
+    /// 捨て札を山札に戻した回数 (`GameState::stock_recycles`)。周回上限の判定をクライアントと揃えるため。
+    #[serde(default)]
+    pub stock_recycles: u32,
+    /// 遊び方 (`Variant`)。配った人のものをサーバーが採用して、みんなに伝えるよ。
+    /// 古いクライアントから来て `None` なら、クロンダイク。
+    #[serde(default)]
+    pub variant: Option<Variant>,
+    /// 点数 (`Score`)。数えるのはサーバーなので、クライアントから送る初期状態では無視されるよ。
+    #[serde(default)]
+    pub score: Option<Score>,
+    /// 経過時間と手数 (`GameClock`)。サーバーが数えるのは手数だけなので、
+    /// クライアントは手数だけ受け取って、経過時間は自分の時計のままにするよ。
+    #[serde(default)]
+    pub clock: Option<GameClock>,
     // TODO: ゲームのステータス（誰かのターン、勝利/敗北状態など）も必要なら追加する。
     // pub game_status: GameStatusData, 
     // TODO: 山札 (Stock) や Waste の状態も個別に持つ必要があるかも？
 }
 
+impl GameStateData {
+    /// World の今の状態 (カードは `World::snapshot`、プレイヤーは空) を集める。
+    /// サーバーの状態配信、クライアントの初期状態、デバッグ用の状態取得、セーブデータ (`persistence`) で使うよ。
+    pub fn from_world(world: &World) -> Self {
+        let state = world.resource::<GameState>();
+        GameStateData {
+            players: Vec::new(),
+            world: world.snapshot(),
+            seed: state.and_then(|state| state.seed),
+            rules: Some(GameRules::from_world(world)),
+            stock_recycles: state.map_or(0, |state| state.stock_recycles),
+            variant: Some(Variant::from_world(world)),
+            score: Some(Score::from_world(world)),
+            clock: world.resource::<GameClock>().copied(),
+        }
+    }
+}
+
+/// 1つ前の状態 (通し番号 `sequence - 1`) からの差分。
+/// カードは変わったものだけ、プレイヤーは変わった時だけ入ってる。シードや点数みたいな小さいものは毎回まるごと。
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GameStateDelta {
+    /// この差分を重ねた後の状態の通し番号。
+    pub sequence: u64,
+    /// 変わったカード (サーバーのエンティティのまま) と、無くなったカード。
+    pub cards: SnapshotDelta,
+    /// プレイヤーが入れ替わってたら、今のプレイヤー全員。
+    #[serde(default)]
+    pub players: Option<Vec<PlayerData>>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub rules: Option<GameRules>,
+    #[serde(default)]
+    pub stock_recycles: u32,
+    #[serde(default)]
+    pub variant: Option<Variant>,
+    #[serde(default)]
+    pub score: Option<Score>,
+    #[serde(default)]
+    pub clock: Option<GameClock>,
+}
+
+impl GameStateDelta {
+    /// `previous` → `current` の差分を、通し番号 `sequence` として作る。
+    pub fn between(previous: &GameStateData, current: &GameStateData, sequence: u64) -> Self {
+        GameStateDelta {
+            sequence,
+            cards: previous.world.diff(&current.world),
+            players: (previous.players != current.players).then(|| current.players.clone()),
+            seed: current.seed,
+            rules: current.rules,
+            stock_recycles: current.stock_recycles,
+            variant: current.variant,
+            score: current.score,
+            clock: current.clock,
+        }
+    }
+
+    /// 通し番号 `sequence` の状態に、そのまま重ねられる差分か (1つ先の差分か)。
+    pub fn follows(&self, sequence: u64) -> bool {
+        self.sequence == sequence + 1
+    }
+
+    /// カード以外 (プレイヤー、シード、ルール、点数など) を `GameStateData` の形にする。
+    /// カード (`world`) は空、プレイヤーは変わってなければ空だよ。
+    pub fn session_state(&self) -> GameStateData {
+        GameStateData {
+            players: self.players.clone().unwrap_or_default(),
+            world: WorldSnapshot::default(),
+            seed: self.seed,
+            rules: self.rules,
+            stock_recycles: self.stock_recycles,
+            variant: self.variant,
+            score: self.score,
+            clock: self.clock,
+        }
+    }
+}
+
 /// プレイヤーの情報を表すデータ構造。
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PlayerData {
     pub id: PlayerId,
     pub name: String,
     // TODO: スコアとか、他のプレイヤー情報が必要なら追加！
 }
 
-/// カード1枚の状態を表すデータ構造。
-/// `GameStateData` の中でたくさん使われるよ。
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct CardData {
-    /// このカードに対応するエンティティID。
-    /// クライアント側で `World` のエンティティと紐づけるために使う。
-    pub entity: Entity,
-    /// カードのスート (マーク)。
-    pub suit: Suit,
-    /// カードのランク (数字)。
-    pub rank: Rank,
-    /// カードが表向きかどうか。
-    pub is_face_up: bool,
-    /// このカードが現在どのスタックに属しているか。
-    pub stack_type: StackType,
-    /// そのスタックの中で何番目に積まれているか (0が一番下)。
-    pub position_in_stack: u8,
-    // ↓↓↓ カードの位置情報を追加！
-    pub position: PositionData,
-}
-
-/// 位置情報 (x, y 座標) を表すデータ構造。
-/// サーバーとクライアント間で位置情報をやり取りするために使う。
-#[derive(Serialize, Deserialize, Debug, Clone)] // serde と Debug/Clone を derive！
-pub struct PositionData {
-    pub x: f32,
-    pub y: f32,
-}
-
 /*
 // TODO: 必要になったら GameStatus 用のデータ構造も定義
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -0,0 +1,774 @@
+// src/server.rs
+//! ネイティブのゲームサーバー (`src/bin/server.rs`) の中身、権威 (authoritative) ゲームセッションだよ！👑
+//!
+//! クライアントと全く同じ `protocol` のメッセージを受け取って、自分の `World` を持ち、
+//! カード移動は全部クライアントと同じ `logic::rules` でチェックするんだ。
+//! WebSocket とかの通信部分はここには一切ないよ (だからネイティブでそのままテストできる！🧪)。
+//! 送り先つきの返信 (`Outgoing`) を返すだけで、実際に送るのはバイナリ側のお仕事。
+
+use std::collections::BTreeMap;
+
+use log::{info, warn};
+
+use crate::logic::deck;
+use crate::logic::history::{self, History};
+use crate::logic::stock_handler;
+use crate::logic::variants::Variant;
+use crate::components::{Card, GameClock, Position, Score, ScoringMode, StackInfo};
+use crate::config::GameRules;
+use crate::ecs::world::World;
+use crate::logic::rules;
+use crate::protocol::{
+    ClientMessage, GameStateData, GameStateDelta, PlayerData, PlayerId, ServerMessage,
+};
+use crate::systems::deal_system::DealInitialCardsSystem;
+use crate::systems::move_card_system::MoveCardSystem;
+
+/// 返信メッセージを誰に送るか。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recipient {
+    /// 特定のプレイヤーだけ。
+    Player(PlayerId),
+    /// 指定したプレイヤー以外の全員。
+    AllExcept(PlayerId),
+    /// 接続中の全員。
+    All,
+}
+
+/// `Undo` / `Redo` を受け付けるかどうか。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UndoPolicy {
+    /// いつでも OK。マルチプレイだと、他の人の操作も戻せちゃうので注意。
+    Always,
+    /// 接続してるのが1人だけの時だけ OK (デフォルト)。
+    #[default]
+    SinglePlayerOnly,
+    /// 受け付けない。
+    Never,
+}
+
+/// `GameServer` が返す「送信予定のメッセージ」だよ。📮
+#[derive(Debug, Clone)]
+pub struct Outgoing {
+    pub recipient: Recipient,
+    pub message: ServerMessage,
+}
+
+impl Outgoing {
+    fn to(player_id: PlayerId, message: ServerMessage) -> Self {
+        Self { recipient: Recipient::Player(player_id), message }
+    }
+
+    fn broadcast(message: ServerMessage) -> Self {
+        Self { recipient: Recipient::All, message }
+    }
+}
+
+/// サーバー側のゲームセッション。ゲームの「正解の状態」はこの中の `World` だけ！
+pub struct GameServer {
+    world: World,
+    /// 接続中のプレイヤー (ID -> 名前)。BTreeMap なので ID 順に並ぶよ。
+    players: BTreeMap<PlayerId, String>,
+    next_player_id: PlayerId,
+    move_card_system: MoveCardSystem,
+    undo_policy: UndoPolicy,
+    /// 全員に配った状態の通し番号。状態を配るたびに1つ進む。
+    sequence: u64,
+    /// 最後に全員に配った状態 (次の差分はここから取る)。
+    last_broadcast: GameStateData,
+}
+
+impl GameServer {
+    /// カードを1枚も持たない空のセッションを作るよ。
+    /// 最初に `ProvideInitialState` を送ってきたクライアントのシードと遊び方で、サーバーが自分で配るよ。
+    pub fn new() -> Self {
+        let mut world = World::new();
+        // カードはクライアントとスナップショットでやり取りする📸
+        world.register_serializable_component::<Card>();
+        world.register_serializable_component::<StackInfo>();
+        world.register_serializable_component::<Position>();
+        // 操作の履歴はサーバーの World にも溜める (undo はサーバーの履歴で行う)
+        world.insert_resource(History::default());
+        world.insert_resource(GameRules::default());
+        world.insert_resource(Score::default());
+        Self {
+            world,
+            players: BTreeMap::new(),
+            next_player_id: 1,
+            move_card_system: MoveCardSystem::new(),
+            undo_policy: UndoPolicy::default(),
+            sequence: 0,
+            last_broadcast: GameStateData::default(),
+        }
+    }
+
+    /// `Undo` / `Redo` をどこまで許すか決める。
+    pub fn set_undo_policy(&mut self, policy: UndoPolicy) {
+        self.undo_policy = policy;
+    }
+
+    /// このセッションのルール (山札のめくり枚数、周回上限など) を決める。
+    /// 山札の操作はこのルールでチェックされて、次の状態更新からクライアントにも伝わるよ。
+    pub fn set_game_rules(&mut self, rules: GameRules) {
+        self.world.insert_resource(rules);
+    }
+
+    /// 点数の数え方を決める。点数は0からやり直し (ベガスの持ち越しもリセット) になるよ。
+    pub fn set_scoring_mode(&mut self, mode: ScoringMode) {
+        self.world.insert_resource(Score::new(mode));
+    }
+
+    /// サーバーが持っている World (読み取り専用)。
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// 新しい接続にプレイヤーIDを割り当てるよ。名前は `JoinGame` が来たら上書きされる。
+    pub fn connect(&mut self) -> PlayerId {
+        let player_id = self.next_player_id;
+        self.next_player_id += 1;
+        self.players.insert(player_id, format!("Player {}", player_id));
+        info!("Player {} connected.", player_id);
+        player_id
+    }
+
+    /// 接続が切れたプレイヤーを外して、残りの全員に `PlayerLeft` を知らせる。
+    pub fn disconnect(&mut self, player_id: PlayerId) -> Vec<Outgoing> {
+        if self.players.remove(&player_id).is_none() {
+            return Vec::new();
+        }
+        info!("Player {} disconnected.", player_id);
+        vec![Outgoing::broadcast(ServerMessage::PlayerLeft { player_id })]
+    }
+
+    /// クライアントから届いたメッセージを1つ処理して、送るべき返信を返すよ。
+    pub fn handle_message(&mut self, from: PlayerId, message: ClientMessage) -> Vec<Outgoing> {
+        match message {
+            ClientMessage::JoinGame { player_name } => {
+                info!("Player {} joined as {:?}.", from, player_name);
+                self.players.insert(from, player_name.clone());
+                vec![
+                    Outgoing::to(from, ServerMessage::GameJoined {
+                        your_player_id: from,
+                        initial_game_state: self.game_state_data(),
+                        sequence: self.sequence,
+                    }),
+                    Outgoing {
+                        recipient: Recipient::AllExcept(from),
+                        message: ServerMessage::PlayerJoined { player_id: from, player_name },
+                    },
+                ]
+            }
+            ClientMessage::ProvideInitialState { initial_state } => {
+                if !self.world.get_all_entities_with_component::<Card>().is_empty() {
+                    // もう配り終わってる！送ってきた人にはサーバーの状態に合わせてもらう
+                    info!("Ignoring initial state from player {}: cards are already dealt.", from);
+                    return vec![Outgoing::to(from, self.state_update())];
+                }
+                // 送られてきたカードは使わない (表向きに並べ替えた盤面とかを送られても困るので)。
+                // 同じシードと遊び方で配れば、正直なクライアントとは同じ盤面になるよ
+                let seed = initial_state.seed.unwrap_or_else(deck::random_seed);
+                let variant = initial_state.variant.unwrap_or_default();
+                info!("Dealing {} with seed {} for player {}.", variant.name(), seed, from);
+                self.deal(seed, variant);
+                // 新しいゲームなので差分じゃなくてまるごと配る
+                self.publish_state();
+                vec![Outgoing::broadcast(self.state_update())]
+            }
+            ClientMessage::MakeMove { moved_entity, target_stack } => {
+                // ★ クライアントと同じルールでチェック！ここがサーバーが権威である理由 ★
+                match rules::validate_move(&self.world, moved_entity, target_stack) {
+                    Ok(()) => {
+                        // 点数は process_move がつけてくれる
+                        self.move_card_system.process_move(&mut self.world, moved_entity, target_stack);
+                        self.count_move();
+                        vec![Outgoing::broadcast(self.state_delta())]
+                    }
+                    Err(reason) => {
+                        warn!("Rejected move from player {}: {}", from, reason);
+                        // 拒否した上で、先走って動かしちゃったクライアントを正しい状態に戻してあげる
+                        vec![
+                            Outgoing::to(from, ServerMessage::MoveRejected { entity_id: moved_entity, reason }),
+                            Outgoing::to(from, self.state_update()),
+                        ]
+                    }
+                }
+            }
+            ClientMessage::DrawFromStock => {
+                if stock_handler::deal_from_stock(&mut self.world) {
+                    self.count_move();
+                    vec![Outgoing::broadcast(self.state_delta())]
+                } else {
+                    self.reject_action(from, "The stock is empty")
+                }
+            }
+            ClientMessage::ResetWasteToStock => {
+                if stock_handler::reset_waste_to_stock(&mut self.world) {
+                    self.count_move();
+                    vec![Outgoing::broadcast(self.state_delta())]
+                } else {
+                    self.reject_action(from, "The waste can only be recycled when the stock is empty and passes are left")
+                }
+            }
+            ClientMessage::Undo => self.step_history(from, history::undo, "There is nothing to undo"),
+            ClientMessage::Redo => self.step_history(from, history::redo, "There is nothing to redo"),
+            ClientMessage::RequestGameState => vec![Outgoing::to(from, self.state_update())],
+            ClientMessage::Ping => vec![Outgoing::to(from, ServerMessage::Pong)],
+        }
+    }
+
+    /// 現在の World とプレイヤー一覧から `GameStateData` を作るよ。
+    /// カードはエンティティID順に並べる (毎回同じ順番になるように)。
+    pub fn game_state_data(&self) -> GameStateData {
+        let players = self
+            .players
+            .iter()
+            .map(|(&id, name)| PlayerData { id, name: name.clone() })
+            .collect();
+        GameStateData { players, ..GameStateData::from_world(&self.world) }
+    }
+
+    /// 手数を1つ数える (経過時間はサーバーでは数えない)。
+    fn count_move(&mut self) {
+        if let Some(clock) = self.world.resource_mut::<GameClock>() {
+            clock.record_move();
+        }
+    }
+
+    /// 今の状態をまるごと (通し番号つきで)。全員に配った最後の状態と同じはずなので、番号は進めないよ。
+    fn state_update(&self) -> ServerMessage {
+        ServerMessage::GameStateUpdate { current_game_state: self.game_state_data(), sequence: self.sequence }
+    }
+
+    /// 状態が変わったので、通し番号を進めて、最後に配った状態を今の状態にする。前に配った状態を返すよ。
+    fn publish_state(&mut self) -> GameStateData {
+        self.sequence += 1;
+        let current = self.game_state_data();
+        std::mem::replace(&mut self.last_broadcast, current)
+    }
+
+    /// 最後に配った状態からの差分 (通し番号を1つ進める)。全員に配る時に使ってね。
+    fn state_delta(&mut self) -> ServerMessage {
+        let previous = self.publish_state();
+        ServerMessage::GameStateDelta {
+            delta: GameStateDelta::between(&previous, &self.last_broadcast, self.sequence),
+        }
+    }
+
+    /// `Undo` / `Redo` を、ポリシーが許せばサーバーの履歴で実行して全員に知らせる。
+    /// 断った時は、先に戻しちゃったクライアントを正しい状態に戻してあげる。
+    fn step_history(&mut self, from: PlayerId, step: fn(&mut World) -> bool, nothing_to_do: &str) -> Vec<Outgoing> {
+        let allowed = match self.undo_policy {
+            UndoPolicy::Always => true,
+            UndoPolicy::SinglePlayerOnly => self.players.len() <= 1,
+            UndoPolicy::Never => false,
+        };
+        if !allowed {
+            return self.reject_action(from, "Undo is not allowed in this game");
+        }
+        if step(&mut self.world) {
+            vec![Outgoing::broadcast(self.state_delta())]
+        } else {
+            self.reject_action(from, nothing_to_do)
+        }
+    }
+
+    fn reject_action(&self, from: PlayerId, reason: &str) -> Vec<Outgoing> {
+        warn!("Rejected action from player {}: {}", from, reason);
+        vec![
+            Outgoing::to(from, ServerMessage::Error { message: reason.to_string() }),
+            Outgoing::to(from, self.state_update()),
+        ]
+    }
+
+    /// `seed` と `variant` で新しいゲームを配る。ゲーム状態、点数、時計、履歴も新しいゲームの分になるよ。
+    /// エンティティIDはサーバーが振るので、次の `GameStateUpdate` でクライアントに対応付けてもらう。
+    fn deal(&mut self, seed: u64, variant: Variant) {
+        self.world.insert_resource(variant);
+        DealInitialCardsSystem.execute(&mut self.world, seed);
+    }
+}
+
+impl Default for GameServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --- テスト ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::card::{ALL_RANKS, ALL_SUITS};
+    use crate::components::{GameState, Rank, StackType, Suit};
+    use crate::ecs::entity::Entity;
+    use crate::ecs::entity_map::EntityMap;
+    use crate::ecs::snapshot::WorldSnapshot;
+    use crate::harness::card_world;
+
+    /// クライアントの World にあるカード1枚 (エンティティ, 中身, 場所)。
+    type CardSpec = (Entity, Card, StackInfo);
+
+    /// クライアントから送られてくる初期状態っぽいものを作るヘルパー。
+    fn card_data(id: u32, suit: Suit, rank: Rank, is_face_up: bool, stack_type: StackType, pos: u8) -> CardSpec {
+        (Entity::new(id, 0), Card { suit, rank, is_face_up }, StackInfo::new(stack_type, pos))
+    }
+
+    /// カードたちをクライアントが送ってくるスナップショットにする。
+    fn snapshot_of(cards: &[CardSpec]) -> WorldSnapshot {
+        let mut snapshot = WorldSnapshot::default();
+        for (entity, card, stack_info) in cards {
+            snapshot.insert(*entity, card).unwrap();
+            snapshot.insert(*entity, stack_info).unwrap();
+            snapshot.insert(*entity, &Position { x: 0.0, y: 0.0 }).unwrap();
+        }
+        snapshot
+    }
+
+    /// 配る代わりに、テスト用の盤面でゲームを始める (クライアントからは盤面を受け取らないので、World に直接置く)。
+    fn start_with_cards(server: &mut GameServer, cards: &[CardSpec], variant: Variant) {
+        let world = &mut server.world;
+        world.insert_resource(variant);
+        world.restore_mapped(&snapshot_of(cards), &mut EntityMap::default()).unwrap();
+        world.insert_resource(GameState::playing_with_seed(99));
+        let score = Score::from_world(world).new_game();
+        world.insert_resource(score);
+        world.insert_resource(GameClock::default());
+        server.publish_state();
+    }
+
+    fn server_with_cards(cards: Vec<CardSpec>) -> (GameServer, PlayerId) {
+        let mut server = GameServer::new();
+        let player = server.connect();
+        start_with_cards(&mut server, &cards, Variant::Klondike);
+        (server, player)
+    }
+
+    #[test]
+    fn valid_move_is_applied_and_broadcast() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(0, Suit::Spade, Rank::Six, false, StackType::Tableau(0), 0),
+            card_data(1, Suit::Heart, Rank::Five, true, StackType::Tableau(0), 1),
+            card_data(2, Suit::Club, Rank::Six, true, StackType::Tableau(1), 0),
+        ]);
+
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(1, 0),
+            target_stack: StackType::Tableau(1),
+        });
+
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].recipient, Recipient::All);
+        // 配った時のまるごと (1番) の次の差分。動いたカードとめくれたカードだけ入ってる
+        match &out[0].message {
+            ServerMessage::GameStateDelta { delta } => {
+                assert_eq!(delta.sequence, 2);
+                let changed: Vec<Entity> = delta.cards.changed.entities.iter().map(|saved| saved.entity).collect();
+                assert_eq!(changed, vec![Entity::new(0, 0), Entity::new(1, 0)]);
+                assert!(delta.cards.removed.is_empty());
+                assert_eq!(delta.players, None, "プレイヤーは変わってない");
+            }
+            other => panic!("GameStateDelta が返るはずなのに {:?} だった", other),
+        }
+        let moved = server.world().get_component::<StackInfo>(Entity::new(1, 0)).unwrap();
+        assert_eq!(moved.stack_type, StackType::Tableau(1));
+        assert_eq!(moved.position_in_stack, 1);
+        // 残されたカードは表向きになってるはず
+        assert!(server.world().get_component::<Card>(Entity::new(0, 0)).unwrap().is_face_up);
+        println!("サーバーの正しい移動テスト、成功！🎉");
+    }
+
+    /// カードの (スート, ランク, 向き, 場所) を並べたもの。サーバーとクライアントの盤面を比べる用。
+    fn board(world: &World) -> Vec<(Suit, Rank, bool, StackType, u8)> {
+        let mut cards: Vec<_> = world
+            .query::<(&Card, &StackInfo)>()
+            .map(|(card, stack_info)| (card.suit, card.rank, card.is_face_up, stack_info.stack_type, stack_info.position_in_stack))
+            .collect();
+        cards.sort_by_key(|&(suit, rank, ..)| (suit as u8, rank));
+        cards
+    }
+
+    #[test]
+    fn deltas_rebuild_the_servers_state_and_gaps_need_a_resync() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(0, Suit::Spade, Rank::Six, false, StackType::Tableau(0), 0),
+            card_data(1, Suit::Heart, Rank::Five, true, StackType::Tableau(0), 1),
+            card_data(2, Suit::Club, Rank::Six, true, StackType::Tableau(1), 0),
+            card_data(3, Suit::Heart, Rank::Ace, true, StackType::Waste, 0),
+        ]);
+        // 最後に2人目が入ってきても Redo できるように
+        server.set_undo_policy(UndoPolicy::Always);
+        let mut client = card_world();
+        let mut map = EntityMap::default();
+        let full_state = |server: &mut GameServer| match server.handle_message(player, ClientMessage::RequestGameState).remove(0).message {
+            ServerMessage::GameStateUpdate { current_game_state, sequence } => (current_game_state, sequence),
+            other => panic!("GameStateUpdate が返るはずなのに {:?} だった", other),
+        };
+        let (state, mut sequence) = full_state(&mut server);
+        assert_eq!(sequence, 1);
+        client.restore_mapped(&state.world, &mut map).unwrap();
+
+        let delta_of = |out: Vec<Outgoing>| match out.into_iter().next().map(|outgoing| outgoing.message) {
+            Some(ServerMessage::GameStateDelta { delta }) => delta,
+            other => panic!("GameStateDelta が返るはずなのに {:?} だった", other),
+        };
+        // 動かす → 組札に上げる → 戻す、を差分だけで追いかける
+        let moves = [
+            ClientMessage::MakeMove { moved_entity: Entity::new(1, 0), target_stack: StackType::Tableau(1) },
+            ClientMessage::MakeMove { moved_entity: Entity::new(3, 0), target_stack: StackType::Foundation(0) },
+            ClientMessage::Undo,
+        ];
+        for message in moves {
+            let delta = delta_of(server.handle_message(player, message));
+            assert!(delta.follows(sequence));
+            assert!(delta.cards.changed.len() < 4, "変わったカードだけ");
+            client.apply_delta_mapped(&delta.cards, &mut map).unwrap();
+            sequence = delta.sequence;
+            assert_eq!(board(&client), board(server.world()));
+        }
+
+        // 1つ取りこぼすと次の差分は重ねられないので、まるごと送り直してもらう
+        let missed = delta_of(server.handle_message(player, ClientMessage::Redo));
+        let next = delta_of(server.handle_message(player, ClientMessage::Undo));
+        assert!(missed.follows(sequence));
+        assert!(!next.follows(sequence));
+        let (state, resynced) = full_state(&mut server);
+        assert_eq!(resynced, next.sequence, "まるごとの状態は、最後に配った差分と同じ番号");
+        client.restore_mapped(&state.world, &mut map).unwrap();
+        assert_eq!(board(&client), board(server.world()));
+
+        // 新しく入ってきたプレイヤーは差分にも入る
+        let newcomer = server.connect();
+        server.handle_message(newcomer, ClientMessage::JoinGame { player_name: "Bob".to_string() });
+        let delta = delta_of(server.handle_message(player, ClientMessage::Redo));
+        assert_eq!(delta.players.map(|players| players.len()), Some(2));
+        println!("差分配信と取りこぼし検出テスト、成功！📨");
+    }
+
+    #[test]
+    fn invalid_move_is_rejected_with_reason() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(0, Suit::Heart, Rank::Five, true, StackType::Tableau(0), 0),
+            card_data(1, Suit::Diamond, Rank::Six, true, StackType::Tableau(1), 0),
+        ]);
+
+        // 赤の上に赤は置けない！
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(0, 0),
+            target_stack: StackType::Tableau(1),
+        });
+
+        match &out[0].message {
+            ServerMessage::MoveRejected { entity_id, reason } => {
+                assert_eq!(*entity_id, Entity::new(0, 0));
+                assert!(!reason.is_empty());
+            }
+            other => panic!("MoveRejected が返るはずなのに {:?} だった", other),
+        }
+        assert_eq!(out[0].recipient, Recipient::Player(player));
+        // World は変わってないはず
+        let stack_info = server.world().get_component::<StackInfo>(Entity::new(0, 0)).unwrap();
+        assert_eq!(stack_info.stack_type, StackType::Tableau(0));
+        println!("サーバーの不正な移動拒否テスト、成功！🎉");
+    }
+
+    #[test]
+    fn broken_runs_and_group_moves_to_foundations_are_rejected() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(0, Suit::Spade, Rank::Nine, true, StackType::Tableau(0), 0),
+            card_data(1, Suit::Heart, Rank::Eight, true, StackType::Tableau(1), 0),
+            card_data(2, Suit::Spade, Rank::Two, true, StackType::Tableau(1), 1),
+            card_data(3, Suit::Club, Rank::Ace, true, StackType::Tableau(2), 0),
+            card_data(4, Suit::Heart, Rank::King, true, StackType::Tableau(2), 1),
+        ]);
+
+        // 8の上に2が乗ってる並びは、8が9に置けても動かせない
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(1, 0),
+            target_stack: StackType::Tableau(0),
+        });
+        assert!(matches!(out[0].message, ServerMessage::MoveRejected { .. }));
+        // A の上に K が乗ったまま組札には上げられない
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(3, 0),
+            target_stack: StackType::Foundation(2),
+        });
+        assert!(matches!(out[0].message, ServerMessage::MoveRejected { .. }));
+
+        assert_eq!(server.world().stacks().len(StackType::Tableau(1)), 2);
+        assert!(server.world().stacks().is_empty(StackType::Foundation(2)));
+        println!("サーバーが崩れた並びを拒否するテスト、成功！🎉");
+    }
+
+    #[test]
+    fn second_initial_state_is_ignored() {
+        let (mut server, _) = server_with_cards(vec![
+            card_data(0, Suit::Heart, Rank::Ace, true, StackType::Waste, 0),
+        ]);
+        let late_player = server.connect();
+        let out = server.handle_message(late_player, ClientMessage::ProvideInitialState {
+            initial_state: GameStateData {
+                players: Vec::new(),
+                world: snapshot_of(&[card_data(7, Suit::Club, Rank::King, true, StackType::Tableau(3), 0)]),
+                seed: Some(1),
+                ..Default::default()
+            },
+        });
+
+        assert_eq!(out[0].recipient, Recipient::Player(late_player));
+        assert!(!server.world().is_entity_alive(Entity::new(7, 0)));
+        assert_eq!(server.game_state_data().world.count::<Card>(), 1);
+        // シードも最初に配った人のまま
+        assert_eq!(server.game_state_data().seed, Some(99));
+        println!("二回目の初期状態は無視されるテスト、成功！🎉");
+    }
+
+    #[test]
+    fn server_deals_from_the_seed_and_ignores_the_clients_cards() {
+        let mut server = GameServer::new();
+        let player = server.connect();
+        // 全部表向きで組札に並べた「勝ち確」の盤面を送られても…
+        let forged: Vec<CardSpec> = ALL_SUITS
+            .iter()
+            .enumerate()
+            .flat_map(|(foundation, &suit)| {
+                ALL_RANKS.iter().enumerate().map(move |(pos, &rank)| {
+                    let id = (foundation * 13 + pos) as u32 + 100;
+                    card_data(id, suit, rank, true, StackType::Foundation(foundation as u8), pos as u8)
+                })
+            })
+            .collect();
+        let out = server.handle_message(player, ClientMessage::ProvideInitialState {
+            initial_state: GameStateData { world: snapshot_of(&forged), seed: Some(7), ..Default::default() },
+        });
+        assert!(matches!(out[0].message, ServerMessage::GameStateUpdate { sequence: 1, .. }));
+
+        // …サーバーはシード 7 で自分で配った盤面で遊ぶ
+        let mut dealt = card_world();
+        DealInitialCardsSystem.execute(&mut dealt, 7);
+        assert_eq!(board(server.world()), board(&dealt));
+        assert_eq!(server.world().stacks().len(StackType::Foundation(0)), 0);
+        assert!(!server.world().is_entity_alive(Entity::new(100, 0)));
+        assert_eq!(server.game_state_data().seed, Some(7));
+
+        // シードが無ければサーバーが決める
+        let mut server = GameServer::new();
+        let player = server.connect();
+        server.handle_message(player, ClientMessage::ProvideInitialState { initial_state: GameStateData::default() });
+        assert!(server.game_state_data().seed.is_some());
+        assert_eq!(server.game_state_data().world.count::<Card>(), 52);
+        println!("サーバーが自分で配るテスト、成功！🃏");
+    }
+
+    #[test]
+    fn stale_handles_are_rejected() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(40, Suit::Heart, Rank::Ace, true, StackType::Waste, 0),
+            card_data(90, Suit::Club, Rank::Two, true, StackType::Tableau(0), 0),
+        ]);
+
+        // 世代が違う (= 古い) ハンドルでは動かせない
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(0, 1),
+            target_stack: StackType::Foundation(0),
+        });
+        assert!(matches!(out[0].message, ServerMessage::MoveRejected { .. }));
+        println!("古いハンドルを断るテスト、成功！🎉");
+    }
+
+    #[test]
+    fn undo_follows_the_policy() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(0, Suit::Heart, Rank::Ace, true, StackType::Waste, 0),
+        ]);
+        let waste_ace = Entity::new(0, 0);
+        server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: waste_ace,
+            target_stack: StackType::Foundation(0),
+        });
+
+        // 2人目が来たら、デフォルト (1人の時だけ) では断られる
+        let other = server.connect();
+        let out = server.handle_message(player, ClientMessage::Undo);
+        assert!(matches!(out[0].message, ServerMessage::Error { .. }));
+        assert_eq!(out[0].recipient, Recipient::Player(player));
+        assert_eq!(server.world().stacks().location(waste_ace), Some((StackType::Foundation(0), 0)));
+
+        // 2人になっても許可すれば戻せて、全員に配られる
+        server.set_undo_policy(UndoPolicy::Always);
+        let out = server.handle_message(other, ClientMessage::Undo);
+        assert_eq!(out[0].recipient, Recipient::All);
+        assert_eq!(server.world().stacks().location(waste_ace), Some((StackType::Waste, 0)));
+
+        let out = server.handle_message(other, ClientMessage::Undo);
+        assert!(matches!(out[0].message, ServerMessage::Error { .. }));
+        server.handle_message(player, ClientMessage::Redo);
+        assert_eq!(server.world().stacks().location(waste_ace), Some((StackType::Foundation(0), 0)));
+
+        server.set_undo_policy(UndoPolicy::Never);
+        server.disconnect(other);
+        let out = server.handle_message(player, ClientMessage::Undo);
+        assert!(matches!(out[0].message, ServerMessage::Error { .. }));
+        println!("サーバーの undo ポリシーテスト、成功！⏪");
+    }
+
+    #[test]
+    fn stock_follows_the_game_rules() {
+        let (mut server, player) = server_with_cards(
+            [Rank::Ace, Rank::Two, Rank::Three, Rank::Four]
+                .into_iter()
+                .enumerate()
+                .map(|(i, rank)| card_data(i as u32, Suit::Club, rank, false, StackType::Stock, i as u8))
+                .collect(),
+        );
+        server.set_game_rules(GameRules::draw_three().with_max_passes(Some(1)));
+
+        // 3枚めくって、残りの1枚もめくれる
+        server.handle_message(player, ClientMessage::DrawFromStock);
+        assert_eq!(server.world().stacks().len(StackType::Waste), 3);
+        server.handle_message(player, ClientMessage::DrawFromStock);
+        assert_eq!(server.world().stacks().len(StackType::Stock), 0);
+
+        // 1周だけのルールなので、捨て札は戻せない
+        let out = server.handle_message(player, ClientMessage::ResetWasteToStock);
+        assert!(matches!(out[0].message, ServerMessage::Error { .. }));
+        assert_eq!(server.world().stacks().len(StackType::Waste), 4);
+
+        // ルールと周回数はクライアントにも伝わる
+        let state = server.game_state_data();
+        assert_eq!(state.rules, Some(GameRules::draw_three().with_max_passes(Some(1))));
+        assert_eq!(state.stock_recycles, 0);
+        println!("サーバーが山札のルールを守るテスト、成功！🃏");
+    }
+
+    #[test]
+    fn server_plays_the_dealers_variant() {
+        let mut server = GameServer::new();
+        let player = server.connect();
+        server.handle_message(player, ClientMessage::ProvideInitialState {
+            initial_state: GameStateData { seed: Some(3), variant: Some(Variant::FreeCell), ..Default::default() },
+        });
+        assert_eq!(server.game_state_data().variant, Some(Variant::FreeCell));
+        assert_eq!(server.world().stacks().len(StackType::Stock), 0, "フリーセルは全部場札に配る");
+
+        // フリーセルなので、フリーセルに1枚逃がせる
+        let top = server.world().stacks().top(StackType::Tableau(1)).unwrap();
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: top,
+            target_stack: StackType::FreeCell(0),
+        });
+        assert_eq!(out[0].recipient, Recipient::All);
+        assert_eq!(server.world().stacks().top(StackType::FreeCell(0)), Some(top));
+        println!("サーバーが配った人の遊び方で遊ぶテスト、成功！🧩");
+    }
+
+    #[test]
+    fn foundation_cards_can_go_back_unless_the_rules_say_no() {
+        let (mut server, player) = server_with_cards(vec![
+            card_data(0, Suit::Spade, Rank::Ace, true, StackType::Foundation(3), 0),
+            card_data(1, Suit::Spade, Rank::Two, true, StackType::Foundation(3), 1),
+            card_data(2, Suit::Heart, Rank::Three, true, StackType::Tableau(0), 0),
+            card_data(3, Suit::Diamond, Rank::Two, true, StackType::Tableau(1), 0),
+        ]);
+
+        // 組札の下の方のカードは取り出せない
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(0, 0),
+            target_stack: StackType::Tableau(0),
+        });
+        assert!(matches!(out[0].message, ServerMessage::MoveRejected { .. }));
+
+        // 一番上の2は赤の3の上に戻せる
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(1, 0),
+            target_stack: StackType::Tableau(0),
+        });
+        assert_eq!(out[0].recipient, Recipient::All);
+        assert_eq!(server.world().stacks().top(StackType::Tableau(0)), Some(Entity::new(1, 0)));
+
+        // ルールで禁止すると、A はもう戻せない
+        server.set_game_rules(GameRules::draw_one().with_foundation_to_tableau(false));
+        let out = server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(0, 0),
+            target_stack: StackType::Tableau(1),
+        });
+        match &out[0].message {
+            ServerMessage::MoveRejected { reason, .. } => assert!(reason.contains("foundation")),
+            other => panic!("MoveRejected が返るはずなのに {:?} だった", other),
+        }
+        assert_eq!(server.world().stacks().len(StackType::Foundation(3)), 1);
+        println!("組札から場札に戻すテスト、成功！↩️");
+    }
+
+    #[test]
+    fn server_keeps_the_score() {
+        let cards = vec![
+            card_data(0, Suit::Club, Rank::Nine, false, StackType::Tableau(0), 0),
+            card_data(1, Suit::Heart, Rank::Ace, true, StackType::Tableau(0), 1),
+        ];
+        let (mut server, player) = server_with_cards(cards.clone());
+        server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(1, 0),
+            target_stack: StackType::Foundation(0),
+        });
+        // 組札へ +10、裏向きのカードがめくれて +5
+        assert_eq!(server.game_state_data().score.map(|score| score.points), Some(15));
+        assert_eq!(server.game_state_data().clock.map(|clock| clock.moves), Some(1));
+
+        // ベガスは $52 払って始めて、組札に上げると +$5
+        let mut server = GameServer::new();
+        server.set_scoring_mode(ScoringMode::Vegas);
+        let player = server.connect();
+        start_with_cards(&mut server, &cards, Variant::Klondike);
+        server.handle_message(player, ClientMessage::MakeMove {
+            moved_entity: Entity::new(1, 0),
+            target_stack: StackType::Foundation(0),
+        });
+        assert_eq!(Score::from_world(server.world()).points, -47);
+        println!("サーバーの点数テスト、成功！💯");
+    }
+
+    #[test]
+    fn undo_takes_back_the_points_on_the_server() {
+        let cards = vec![
+            card_data(0, Suit::Club, Rank::Nine, false, StackType::Tableau(0), 0),
+            card_data(1, Suit::Heart, Rank::Ace, true, StackType::Tableau(0), 1),
+        ];
+        let mut server = GameServer::new();
+        server.set_scoring_mode(ScoringMode::Vegas);
+        let player = server.connect();
+        start_with_cards(&mut server, &cards, Variant::Klondike);
+        let to_foundation = ClientMessage::MakeMove { moved_entity: Entity::new(1, 0), target_stack: StackType::Foundation(0) };
+        let points = |server: &GameServer| Score::from_world(server.world()).points;
+
+        // 上げる → undo → また上げる、を繰り返しても $5 のまま
+        for _ in 0..3 {
+            server.handle_message(player, to_foundation.clone());
+            assert_eq!(points(&server), -47);
+            server.handle_message(player, ClientMessage::Undo);
+            assert_eq!(points(&server), -52);
+        }
+        server.handle_message(player, ClientMessage::Redo);
+        assert_eq!(points(&server), -47);
+        println!("サーバーの undo で点数が戻るテスト、成功！💯");
+    }
+
+    #[test]
+    fn join_and_ping() {
+        let mut server = GameServer::new();
+        let player = server.connect();
+        let out = server.handle_message(player, ClientMessage::JoinGame { player_name: "Alice".to_string() });
+        match &out[0].message {
+            ServerMessage::GameJoined { your_player_id, initial_game_state, .. } => {
+                assert_eq!(*your_player_id, player);
+                assert_eq!(initial_game_state.players[0].name, "Alice");
+            }
+            other => panic!("GameJoined が返るはずなのに {:?} だった", other),
+        }
+        assert_eq!(out[1].recipient, Recipient::AllExcept(player));
+
+        let out = server.handle_message(player, ClientMessage::Ping);
+        assert!(matches!(out[0].message, ServerMessage::Pong));
+        println!("参加と Ping テスト、成功！🎉");
+    }
+}
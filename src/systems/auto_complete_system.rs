@@ -0,0 +1,123 @@
+// src/systems/auto_complete_system.rs
+//! オートコンプリート (`AutoCompleteRequested`) を処理するシステムだよ！🪄
+//!
+//! 山札も捨て札も空で、場札が全部表向きになったら、あとは組札に上げるだけ。
+//! リクエストが来たら、`STEP_SECONDS` ごとに1枚ずつ `MoveRequested` を送っていく。
+//! 1手ずつ普通の移動として `MoveCardSystem` を通るので、Layout ステージで1枚ずつ動いて見えるし、
+//! `MoveApplied` を見てる `NetworkOutboxSystem` がサーバーにも1手ずつ伝えてくれるよ。
+
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::system::System;
+use crate::ecs::time::Time;
+use crate::ecs::world::World;
+use crate::events::{AutoCompleteRequested, MoveRequested};
+use crate::logic::auto_move;
+use log::debug;
+
+/// 1枚動かしてから次の1枚を動かすまでの秒数。
+pub const STEP_SECONDS: f64 = 0.08;
+
+/// `MoveCardSystem` より前に置いてね (同じフレームの中で移動まで終わるように)。
+#[derive(Default)]
+pub struct AutoCompleteSystem {
+    requests: EventCursor<AutoCompleteRequested>,
+    /// オートコンプリート中？
+    running: bool,
+    /// 次の1枚を動かすまでの残り秒数。
+    cooldown: f64,
+}
+
+impl AutoCompleteSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for AutoCompleteSystem {
+    fn run(&mut self, world: &mut World) {
+        let requested = world
+            .resource::<Events<AutoCompleteRequested>>()
+            .is_some_and(|events| self.requests.read(events).count() > 0);
+        if requested && !self.running {
+            if auto_move::can_auto_complete(world) {
+                debug!("[AutoCompleteSystem] Starting auto-complete.");
+                self.running = true;
+                self.cooldown = 0.0;
+            } else {
+                debug!("[AutoCompleteSystem] The board is not ready for auto-complete.");
+            }
+        }
+        if !self.running {
+            return;
+        }
+
+        self.cooldown -= world.resource::<Time>().map_or(0.0, |time| time.delta_seconds);
+        if self.cooldown > 0.0 {
+            return;
+        }
+        // 途中で undo されたりして、もう自動で上げられない盤面になってたら止める
+        match auto_move::next_auto_complete_move(world).filter(|_| auto_move::can_auto_complete(world)) {
+            Some((entity, target)) => {
+                world.send_event(MoveRequested { entity, target });
+                self.cooldown = STEP_SECONDS;
+            }
+            None => {
+                debug!("[AutoCompleteSystem] Auto-complete finished.");
+                self.running = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::{Card, Position, Rank, StackInfo, StackType, Suit};
+    use crate::events::AutoCompleteRequested;
+    use crate::harness::GameHarness;
+    use crate::protocol::ClientMessage;
+
+    #[test]
+    fn auto_complete_moves_one_card_per_step_until_the_board_is_done() {
+        let mut harness = GameHarness::new();
+        // 場札に A♥-2♥ と A♠ (全部表向き)、山札と捨て札は空
+        let layout = [
+            (Suit::Heart, Rank::Two, StackType::Tableau(0), 0),
+            (Suit::Heart, Rank::Ace, StackType::Tableau(1), 0),
+            (Suit::Spade, Rank::Ace, StackType::Tableau(2), 0),
+        ];
+        for (suit, rank, stack_type, index) in layout {
+            let entity = harness.world.create_entity();
+            harness.world.add_component(entity, Card { suit, rank, is_face_up: true });
+            harness.world.add_component(entity, StackInfo::new(stack_type, index));
+            harness.world.add_component(entity, Position { x: 0.0, y: 0.0 });
+        }
+
+        harness.send(AutoCompleteRequested);
+        harness.tick();
+        // 最初のフレームで1枚だけ動く
+        assert_eq!(harness.take_outgoing().len(), 1);
+
+        harness.tick_frames(30);
+        let moves = harness.take_outgoing();
+        assert_eq!(moves.len(), 2);
+        assert!(moves.iter().all(|message| matches!(message, ClientMessage::MakeMove { .. })));
+        assert_eq!(harness.world.stacks().len(StackType::Foundation(0)), 2);
+        assert_eq!(harness.world.stacks().len(StackType::Foundation(3)), 1);
+        println!("オートコンプリートが1枚ずつ動かすテスト、成功！🪄");
+    }
+
+    #[test]
+    fn auto_complete_is_ignored_while_cards_are_hidden() {
+        let mut harness = GameHarness::new();
+        let entity = harness.world.create_entity();
+        harness.world.add_component(entity, Card { suit: Suit::Club, rank: Rank::Ace, is_face_up: false });
+        harness.world.add_component(entity, StackInfo::new(StackType::Stock, 0));
+        harness.world.add_component(entity, Position { x: 0.0, y: 0.0 });
+
+        harness.send(AutoCompleteRequested);
+        harness.tick_frames(10);
+        assert!(harness.take_outgoing().is_empty());
+        assert_eq!(harness.world.stacks().location(entity), Some((StackType::Stock, 0)));
+        println!("山札が残ってるとオートコンプリートしないテスト、成功！🎉");
+    }
+}
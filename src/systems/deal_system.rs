@@ -11,7 +11,14 @@ use crate::ecs::world::World;
 // Position: エンティティの画面上の座標 (x, y) を持つデータ部品。
 // StackInfo: カードがどの場所 (山札？場札？) の何番目にあるかの情報を持つデータ部品。
 // StackType: `StackInfo` の中で使う、場所の種類 (山札、場札、組札、捨て札) を表すマーカー。
-use crate::components::{card::{/*self, */Card, /*Suit, Rank*/}, position::Position, stack::{StackInfo, StackType}}; // self, Suit, Rank は削除 (Card は使ってる)
+use crate::components::{card::{/*self, */Card, /*Suit, Rank*/}, position::Position, stack::StackInfo}; // self, Suit, Rank は削除 (Card は使ってる)
+// GameState: ゲームが進行中か、勝ったかなどを持つリソース。配り終わったら Playing にする。
+use crate::components::game_state::GameState;
+use crate::logic::history::History;
+use crate::logic::game_record::GameRecord;
+use crate::config::GameRules;
+use crate::components::score::Score;
+use crate::components::game_state::GameClock;
 // Entity: ゲーム世界のモノ (カードとかプレイヤーとか) を識別するためのユニークなID。
 use crate::ecs::entity::Entity;
 // rand クレート (外部ライブラリ) から、ランダム系の機能をもらうよ！
@@ -19,10 +26,12 @@ use crate::ecs::entity::Entity;
 // use rand::seq::SliceRandom; // logic/deck.rs の shuffle_deck を使うため不要
 // thread_rng: OSが提供する、暗号学的に安全な乱数生成器を使うための関数。
 // use rand::thread_rng; // logic/deck.rs の shuffle_deck を使うため不要
-// config::layout モジュールから、カード配置の座標とかオフセットの定数をもらうよ！レイアウト調整はこっちでやるのがスマート！✨
-use crate::config::layout::*;
-// logic::deck モジュールから、デッキ作成とシャッフルのヘルパー関数をもらうよ！ロジックは別ファイルに分けるのがお作法！👍
-use crate::logic::deck::{create_standard_deck, shuffle_deck};
+// logic::layout_calculator から、カードの座標計算をもらうよ！ 配った直後の位置も、遊んでる時と同じ計算で決める✨
+use crate::logic::layout_calculator;
+// logic::deck モジュールから、シャッフルのヘルパー関数をもらうよ！ロジックは別ファイルに分けるのがお作法！👍
+use crate::logic::deck::shuffle_deck_with_seed;
+// logic::variants から、今の遊び方のルール (どんなデッキを、どう配るか) をもらうよ！🎴
+use crate::logic::variants;
 
 // === 初期カード配置システム (DealInitialCardsSystem) ===
 // これが今回の主役！✨ ゲームが始まった時に、カードをシャッフルして場に配るっていう大事な役目を持ってる「システム」だよ！
@@ -43,29 +52,32 @@ impl DealInitialCardsSystem {
     /// - `world: &mut World`: ゲーム世界の管理人 `World` さんの **可変参照** (`&mut`) を受け取るよ。
     ///   可変参照っていうのは、「`world` の中身を読み書きする権利をもらいますよ！」っていう意味。
     ///   カードエンティティを作ったり、コンポーネントを追加したり、`world` の状態を変えるから `&mut` が必須なんだ！🔥
+    /// - `seed: u64`: シャッフルのシード。同じシードなら、どの環境でも同じ盤面になるよ🎲
+    ///   (`GameState` の `seed` に覚えておくので、バグ報告やデイリーチャレンジで再現できる！)
+    ///   ランダムに配りたいなら `logic::deck::random_seed()` を渡してね。
     ///
     /// # 処理の流れ (ざっくり！)
-    /// 1. **デッキ準備！**: まずは新品の52枚のカードデッキを用意して、よーくシャッフル！섞어섞어！🔀
+    /// 1. **デッキ準備！**: 今の遊び方 (`Variant` リソース、無ければクロンダイク) のデッキを用意して、よーくシャッフル！섞어섞어！🔀
+    ///    (クロンダイクなら52枚、スパイダーなら104枚！)
     /// 2. **お掃除タイム！**: もし前にプレイした時のカードが残ってたら大変だから、先に全部キレイにするよ！🧹
-    /// 3. **カード配置！**: シャッフルしたデッキからカードを一枚ずつ取り出して、ソリティア (クロンダイク) のルール通りに配置していくよ！
-    ///    - 場札 (Tableau): 7つの列に、1枚、2枚、...7枚って感じで配って、各列の一番上だけ表向きにする！👀
-    ///    - 山札 (Stock): 残ったカードは全部、山札に裏向きで積む！⛰️
+    /// 3. **カード配置！**: シャッフルしたデッキを、遊び方のルール (`RuleSet::deal`) 通りに配置していくよ！
+    ///    - クロンダイクなら場札 (Tableau) の7つの列に、1枚、2枚、...7枚って感じで配って、各列の一番上だけ表向きにする！👀
+    ///    - 残ったカードは全部、山札 (Stock) に裏向きで積む！⛰️ (山札が無い遊び方なら全部場札に配る)
     /// 4. **情報付与！**: 配置したカード一枚一枚に、「私はスペードのエースだよ！」(`Card` コンポーネント)、「私は場札の3列目の2番目だよ！」(`StackInfo` コンポーネント)、「私の画面上の位置はここだよ！」(`Position` コンポーネント) っていう情報を `world` に登録していく！✍️
-    pub fn execute(&self, world: &mut World) {
+    pub fn execute(&self, world: &mut World, seed: u64) {
         println!("🚀 DealInitialCardsSystem: 実行開始！ 初期カード配置を始めます！");
 
         // --- 1. デッキの準備 --- ✨♠️♥️♦️♣️✨
-        // `logic/deck.rs` にある `create_standard_deck` 関数を呼び出して、52枚のカードデータ (Card 構造体のリスト) を作るよ。
+        // 今の遊び方のルールを World から引く (`Variant` リソースが無ければクロンダイク)。
+        let rule_set = variants::active(world);
+        // 遊び方ごとのデッキ (Card 構造体のリスト) を作るよ。クロンダイクなら `create_standard_deck` の52枚。
         // `let mut deck_cards` の `mut` は、「この変数 `deck_cards` の中身は後で変えるかもよ！」っていう印。
         // シャッフルで順番を変えるから `mut` が必要！🔥
-        let mut deck_cards = create_standard_deck();
-        // 同じく `logic/deck.rs` の `shuffle_deck` 関数で、デッキをごちゃ混ぜにする！ランダム大事！🎲
+        let mut deck_cards = rule_set.deck();
+        // `logic/deck.rs` の `shuffle_deck_with_seed` 関数で、デッキをごちゃ混ぜにする！シードで決まるランダム！🎲
         // `&mut deck_cards` で、`deck_cards` の可変参照を渡してるよ。
-        shuffle_deck(&mut deck_cards); // <- こっちを使う！便利関数！
-        // もし `logic/deck.rs` に `shuffle_deck` がなかったら、こっちの rand クレートの直接的なやり方でもOK！👍
-        // let mut rng = thread_rng(); // 乱数生成器を用意して…
-        // deck_cards.shuffle(&mut rng); // shuffle メソッドで直接シャッフル！
-        println!("  🃏 デッキ作成 & シャッフル完了！ ({}枚)", deck_cards.len());
+        shuffle_deck_with_seed(&mut deck_cards, seed);
+        println!("  🃏 {:?} のデッキ作成 & シャッフル完了！ ({}枚, seed: {})", rule_set.variant(), deck_cards.len(), seed);
 
         // --- 2. 既存カードのお掃除タイム！ --- 🧹💨
         // もし前のゲームのカードが残ってたら、新しいゲームを始める前にお掃除しとかないとね！
@@ -93,95 +105,30 @@ impl DealInitialCardsSystem {
         }
 
         // --- 3. カードを配るよ！ --- 🃏💨
-        // `deck_cards.into_iter()` で、シャッフル済みのデッキからカードを1枚ずつ順番に取り出せるようにする「イテレータ」を作るよ。
-        // `into_iter()` は元の `deck_cards` の所有権を完全に持っていくから、この後 `deck_cards` を直接使うことはできなくなる！注意！⚠️
-        // (データを効率よく移動させるためのRustの仕組みだよ！)
-        let mut card_iterator = deck_cards.into_iter();
-
-        // --- 3a. 場札 (Tableau) に配る！ (7列あるやつね！) ---
-        println!("  ⏳ 場札 (Tableau) にカードを配置中...");
-        // `0..7` は、0から6までの連続した数字を表す「範囲 (Range)」だよ。`for` ループで使うと、0, 1, 2, 3, 4, 5, 6 って順番に処理できる！
-        let mut total_tableau_cards = 0;
-        for tableau_index in 0..7 { // 7つの列 (index 0 から 6) に対してループ
-            // 各列に配るカードの枚数は `tableau_index + 1` 枚 (1列目は1枚, 2列目は2枚...)
-            // 列ごとのカードのY座標を計算するために、その列でどれだけ下にずらすかのオフセット値を覚えておく変数。
-            let mut current_y_offset = 0.0;
-            for card_in_tableau_index in 0..(tableau_index + 1) { // 各列に必要な枚数だけループ
-                // デッキ (イテレータ) からカードを1枚取り出す！ `.next()` はカードがあれば `Some(Card)`、もう無ければ `None` を返す `Option` 型。
-                // `.expect()` は、もし `None` だったらカッコ内のメッセージを表示してプログラムを強制終了させる！💥
-                // ここでは、デッキの枚数は足りてるはずだから、もし None が来たらそれはプログラムのバグ！ってことで `expect` を使ってるよ。
-                let mut card: Card = card_iterator.next().expect("デッキにカードが足りません！(場札配置バグ！)");
-
-                // 新しいエンティティ (このカードの実体) を World に誕生させる！ ✨🐣✨
-                let entity: Entity = world.create_entity();
-
-                // --- カードの状態と位置を決める！ ---
-                // このカードが、その列の一番上 (手前) に置かれるカードかどうかをチェック。
-                let is_top_card_in_pile = card_in_tableau_index == tableau_index;
-                // 一番上のカードだけ表向き！👀 それ以外は裏向きのまま。
-                if is_top_card_in_pile {
-                    card.is_face_up = true; // Card 構造体の中身を書き換える！
-                }
-
-                // カードの画面上の位置 (Position) を計算するよ！ 座標は `config/layout.rs` の定数を使う！
-                let pos_x = TABLEAU_START_X + tableau_index as f32 * TABLEAU_X_OFFSET;
-                // Y座標は、同じ列の前のカードのオフセットに基づいて決まる。
-                let pos_y = TABLEAU_START_Y + current_y_offset;
-                // 次のカードのために、Y座標のずれ (オフセット) を更新する。
-                // 表向きのカードは、下に積むときに大きくずらす (カードが見えるように)。裏向きは小さくずらす。
-                current_y_offset += if is_top_card_in_pile { TABLEAU_Y_OFFSET_FACE_UP } else { TABLEAU_Y_OFFSET_FACE_DOWN };
-                // 計算した座標で Position コンポーネントを作る！
-                let position_component = Position { x: pos_x, y: pos_y };
-
-                // --- コンポーネントをエンティティに追加！ ---✍️
-                // これで、この `entity` がどんなカードで、どこにあって、どの位置に表示されるかが `World` に記録される！
-                world.add_component(entity, card); // Card コンポーネントを追加 (さっき is_face_up を更新したやつ！)
-                world.add_component(entity, StackInfo {
-                    stack_type: StackType::Tableau(tableau_index), // 「場札の tableau_index 列目だよ！」
-                    position_in_stack: card_in_tableau_index,      // 「その列の中で card_in_tableau_index 番目 (0が奥) だよ！」
-                });
-                world.add_component(entity, position_component); // Position コンポーネントを追加！
-
-                // println!("    配置: {:?} を 場札[{}] の {}番目 に (表向き: {}, Pos: {:?})", world.get_component::<Card>(entity).unwrap(), tableau_index, card_in_tableau_index, is_top_card_in_pile, position_component);
-                total_tableau_cards += 1;
-            }
-        }
-        println!("  ✅ 場札への配置完了！ ({}枚配置)", total_tableau_cards);
-
-        // --- 3b. 残りのカードを山札 (Stock) へ！ --- ⛰️
-        println!("  ⏳ 山札 (Stock) にカードを配置中...");
-        let mut stock_card_count = 0; // 山札に何枚入れたかカウント
-        // `card_iterator` には、場札に配らなかった残りのカードが入ってるはず。
-        // `enumerate()` を使うと、(インデックス, カード) のペアでループできるから、山札内の順番 (position_in_stack) を付けるのに便利！✨
-        for (index_in_stock, card) in card_iterator.enumerate() {
-            // 新しいエンティティを作成！
-            let entity = world.create_entity();
-
-            // 山札のカードは全部同じ位置に表示する想定。座標は `config/layout.rs` から。
-            let position_component = Position { x: STOCK_POS_X, y: STOCK_POS_Y };
-
-            // コンポーネントを追加！✍️
-            // カード情報はそのまま (全部裏向きのはず！)
+        // どのカードをどこに、表向きか裏向きかで置くかは遊び方のルール (`RuleSet::deal`) が決めてくれる。
+        // 返ってくるのは (場所, カード) のリストで、同じ場所のカードは下から順に並んでるよ。
+        println!("  ⏳ カードを配置中...");
+        let dealt = rule_set.deal(deck_cards);
+        let total_cards_placed = dealt.len();
+        for (stack_type, card) in dealt {
+            // 新しいエンティティ (このカードの実体) を World に誕生させる！ ✨🐣✨
+            let entity: Entity = world.create_entity();
+            // その場所に今何枚あるか = このカードの順番 (0 が一番下)
+            let position_in_stack = world.stacks().len(stack_type) as u8;
+
+            // --- コンポーネントをエンティティに追加！ ---✍️
             world.add_component(entity, card);
-            // 場所情報は「山札だよ！」って設定。
-            world.add_component(entity, StackInfo {
-                stack_type: StackType::Stock,
-                position_in_stack: index_in_stock as u8, // enumerate のインデックスを順番として使う (u8に変換！)
-            });
-            // 位置情報も追加！
-            world.add_component(entity, position_component);
-
-            // println!("    配置: {:?} を 山札 の {}番目 に (Pos: {:?})", world.get_component::<Card>(entity).unwrap(), index_in_stock, position_component);
-            stock_card_count += 1;
+            world.add_component(entity, StackInfo { stack_type, position_in_stack });
+            // 画面上の位置は、下のカードが置かれた後で計算する (場札は下のカードの向きでずらし方が変わるからね！)
+            let position: Position = layout_calculator::calculate_card_position(stack_type, position_in_stack, world);
+            world.add_component(entity, position);
         }
-        println!("  ✅ 山札への配置完了！ ({}枚配置)", stock_card_count);
 
-        let total_cards_placed = total_tableau_cards + stock_card_count;
-        if total_cards_placed == 52 {
+        if total_cards_placed == rule_set.deck_size() {
             println!("🎉 合計 {} 枚のカードを正しく配置しました！ゲーム開始準備OK！", total_cards_placed);
         } else {
-            // もし52枚じゃなかったら、何かロジックがおかしい！😱
-            eprintln!("🚨 エラー！配置されたカードの合計が52枚ではありません！({})", total_cards_placed);
+            // もしデッキの枚数と違ったら、何かロジックがおかしい！😱
+            eprintln!("🚨 エラー！配置されたカードの合計が{}枚ではありません！({})", rule_set.deck_size(), total_cards_placed);
         }
 
         // --- 4. 空の組札 (Foundation) や捨て札 (Waste) の場所について --- 🤔
@@ -190,6 +137,21 @@ impl DealInitialCardsSystem {
         // 今回はシンプルに、カードが無い場所はエンティティも無い、っていう状態にしておくね！
         // カード移動のルール (System) を作る時に、「移動先が Foundation で、そこにカードが無い場合は…」みたいに条件分岐すればOK！👍
 
+        // --- 5. ゲーム状態を「プレイ中」にする --- 🎮
+        // 新しく配り直したので、前のゲームで勝ってても Playing に戻すよ (GameState は World のリソース)。
+        // どのシードで配ったかも一緒に覚えておく！
+        world.insert_resource(GameState::playing_with_seed(seed));
+        // 点数も新しいゲームの分に (スタンダードは0点から、ベガスは持ち越しから $52 払う)、時計と手数も0から
+        let score = Score::from_world(world).new_game();
+        world.insert_resource(score);
+        world.insert_resource(GameClock::default());
+        // 棋譜も新しく (このシードとルールで配り直せば、記録した手で同じゲームが再現できる)
+        world.insert_resource(GameRecord::new(seed, rule_set.variant(), GameRules::from_world(world), score.mode));
+        // 前のゲームの操作は undo できないように、履歴もまっさらにする
+        if let Some(history) = world.resource_mut::<History>() {
+            history.clear();
+        }
+
         println!("✅ DealInitialCardsSystem: 実行完了！");
     }
 }
@@ -202,7 +164,7 @@ mod tests {
     // 親モジュール (このファイルの上部) のアイテム (`*`) と、テストで使う他のモジュールをインポート！
     use super::*;
     use crate::components::position::Position;
-    use crate::components::card::{Rank, Suit};
+    use crate::components::stack::StackType;
     use std::collections::HashMap; // テスト結果の集計とかに使うかも？
 
     // `#[test]` アトリビュートが付いた関数が、個別のテストケースになるよ！
@@ -219,11 +181,11 @@ mod tests {
         world.register_component::<Position>();
 
         // 3. テスト対象のシステム (DealInitialCardsSystem) のインスタンスを作成！
-        let deal_system = DealInitialCardsSystem::default();
+        let deal_system = DealInitialCardsSystem;
 
         // --- 実行 (Act) ---
         // 4. システムを実行して、カードを配ってもらう！
-        deal_system.execute(&mut world);
+        deal_system.execute(&mut world, 12345);
         println!("--- deal_system.execute() 完了、検証開始！---🔬");
 
         // --- 検証 (Assert) ---
@@ -240,6 +202,10 @@ mod tests {
         assert_eq!(position_count, 52, "Positionを持つカードエンティティが52個であるべきですが、{}個でした", position_count);
         println!("✔️ Position保有数 (52): OK");
 
+        // GameState リソースが Playing になってるか！
+        assert_eq!(world.resource::<GameState>(), Some(&GameState::playing_with_seed(12345)), "配り終わったら GameState は Playing (シード付き) のはず");
+        println!("✔️ GameState (Playing): OK");
+
 
         // 7. 各スタックタイプのカード枚数を数える！
         let mut counts: HashMap<StackType, usize> = HashMap::new();
@@ -308,12 +274,32 @@ mod tests {
         println!("✔️ 場札 (Tableau) 枚数 (計28) と各列の状態 (枚数/向き/順序): OK");
 
         // 10. Foundation と Waste のカードが存在しないことを確認
-        assert!(counts.get(&StackType::Foundation(0)).is_none(), "Foundation(0) にカードがあってはいけません");
-        assert!(counts.get(&StackType::Waste).is_none(), "Waste にカードがあってはいけません");
+        assert!(!counts.contains_key(&StackType::Foundation(0)), "Foundation(0) にカードがあってはいけません");
+        assert!(!counts.contains_key(&StackType::Waste), "Waste にカードがあってはいけません");
         println!("✔️ 組札 (Foundation) と捨て札 (Waste) が空: OK");
 
         println!("--- test_initial_deal_creates_correct_setup 完了 ---✅✨");
     }
 
     // TODO: エッジケースのテスト (World に既に変なデータがある場合とか？) も追加すると、もっと頑丈になるかも！
-} 
\ No newline at end of file
+
+    #[test]
+    fn same_seed_deals_the_same_layout() {
+        fn layout(seed: u64) -> Vec<(StackType, u8, Card)> {
+            let mut world = World::new();
+            world.register_component::<Card>();
+            world.register_component::<StackInfo>();
+            world.register_component::<Position>();
+            DealInitialCardsSystem.execute(&mut world, seed);
+            // 配った順 (= エンティティの番号順) に並ぶ
+            world
+                .query::<(&StackInfo, &Card)>()
+                .map(|(stack_info, card)| (stack_info.stack_type, stack_info.position_in_stack, card.clone()))
+                .collect()
+        }
+
+        assert_eq!(layout(7), layout(7));
+        assert_ne!(layout(7), layout(8));
+        println!("同じシードなら同じ盤面テスト、成功！🎲");
+    }
+}
@@ -0,0 +1,82 @@
+// src/systems/game_clock_system.rs
+//! 経過時間と手数を数えるシステムだよ！⏱️
+//!
+//! `MoveApplied` と `StockCycled` を1手として数えて (最初の1手で時計が動き出す)、
+//! 時計が動いてる間は毎フレーム `Time` の dt を `GameClock` に足していく。
+//! `GameWon` が来たら時計を止める。タブが隠れた時の一時停止は `GameApp::set_page_visible` がやるよ。
+
+use crate::components::GameClock;
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::system::System;
+use crate::ecs::time::Time;
+use crate::ecs::world::World;
+use crate::events::{GameWon, MoveApplied, StockCycled};
+
+#[derive(Default)]
+pub struct GameClockSystem {
+    moves: EventCursor<MoveApplied>,
+    stock: EventCursor<StockCycled>,
+    wins: EventCursor<GameWon>,
+}
+
+impl GameClockSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for GameClockSystem {
+    fn run(&mut self, world: &mut World) {
+        let moves = world.resource::<Events<MoveApplied>>().map_or(0, |events| self.moves.read(events).count())
+            + world.resource::<Events<StockCycled>>().map_or(0, |events| self.stock.read(events).count());
+        let won = world.resource::<Events<GameWon>>().is_some_and(|events| self.wins.read(events).count() > 0);
+        let dt = world.resource::<Time>().map_or(0.0, |time| time.delta_seconds);
+
+        // 配る前 (時計が無い) なら何もしない
+        let Some(clock) = world.resource_mut::<GameClock>() else { return };
+        // このフレームの時間は、今までの状態で足す (最初の1手のフレームはまだ0秒)
+        clock.advance(dt);
+        for _ in 0..moves {
+            clock.record_move();
+        }
+        if won {
+            clock.finished = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::StackType;
+    use crate::ecs::entity::Entity;
+    use crate::events::register_game_events;
+
+    #[test]
+    fn clock_starts_on_the_first_move_and_stops_on_win() {
+        let mut world = World::new();
+        register_game_events(&mut world);
+        world.insert_resource(GameClock::default());
+        let mut system = GameClockSystem::new();
+        let mut frame = |world: &mut World, dt: f64| {
+            world.update_events();
+            world.insert_resource(Time { delta_seconds: dt, ..Time::default() });
+            system.run(world);
+        };
+
+        frame(&mut world, 3.0);
+        assert_eq!(GameClock::from_world(&world), GameClock::default(), "1手目まで時計は止まってる");
+
+        world.send_event(MoveApplied { entity: Entity::new(0, 0), from: StackType::Waste, to: StackType::Tableau(0), count: 1 });
+        frame(&mut world, 1.0);
+        world.send_event(StockCycled::Reset);
+        frame(&mut world, 2.0);
+        world.send_event(GameWon);
+        frame(&mut world, 4.0);
+        frame(&mut world, 5.0);
+
+        let clock = GameClock::from_world(&world);
+        assert_eq!((clock.moves, clock.elapsed_seconds, clock.finished), (2, 6.0, true));
+        println!("GameClockSystem のテスト、成功！⏱️");
+    }
+}
@@ -0,0 +1,53 @@
+// src/systems/game_record_system.rs
+//! 指した手を棋譜 (`GameRecord`) に書いていくシステムだよ！📜
+//!
+//! `StockCycled` → `S`、`MoveApplied` → `T3->F1` など、`UndoRequested` / `RedoRequested` → `undo` / `redo`。
+//! 1フレームの中では「山札 → カードの移動 → undo → redo」の順に処理されるので (スケジュールの順番)、
+//! 同じ順番で書けば、1手ずつ入力し直すだけで同じ盤面になるよ。
+//! 時間は `GameClock` の経過時間。`GameRecord` が置いてなければ (配る前) 何もしない。
+
+use crate::components::GameClock;
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::system::System;
+use crate::ecs::world::World;
+use crate::events::{MoveApplied, RedoRequested, StockCycled, UndoRequested};
+use crate::logic::game_record::GameRecord;
+use crate::logic::notation::Move;
+
+#[derive(Default)]
+pub struct GameRecordSystem {
+    stock: EventCursor<StockCycled>,
+    moves: EventCursor<MoveApplied>,
+    undos: EventCursor<UndoRequested>,
+    redos: EventCursor<RedoRequested>,
+}
+
+impl GameRecordSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for GameRecordSystem {
+    fn run(&mut self, world: &mut World) {
+        let mut plays: Vec<Move> = Vec::new();
+        if let Some(events) = world.resource::<Events<StockCycled>>() {
+            plays.extend(self.stock.read(events).map(|_| Move::Stock));
+        }
+        if let Some(events) = world.resource::<Events<MoveApplied>>() {
+            plays.extend(self.moves.read(events).map(|&MoveApplied { from, to, count, .. }| Move::Cards { from, to, count }));
+        }
+        if let Some(events) = world.resource::<Events<UndoRequested>>() {
+            plays.extend(self.undos.read(events).map(|_| Move::Undo));
+        }
+        if let Some(events) = world.resource::<Events<RedoRequested>>() {
+            plays.extend(self.redos.read(events).map(|_| Move::Redo));
+        }
+
+        let at = GameClock::from_world(world).elapsed_seconds;
+        let Some(record) = world.resource_mut::<GameRecord>() else { return };
+        for play in plays {
+            record.push(at, play);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// src/systems/history_system.rs
+//! undo / redo の入力 (`UndoRequested` / `RedoRequested`) を処理するシステムだよ！⏪⏩
+
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::system::System;
+use crate::ecs::world::World;
+use crate::events::{RedoRequested, UndoRequested};
+use crate::logic::history;
+use log::debug;
+
+/// World の `History` リソースを使って、操作を1回分ずつ元に戻す / やり直す。
+/// マルチプレイだとサーバーが許可しないこともあるけど、その時はサーバーから正しい状態が届いて上書きされるよ。
+#[derive(Default)]
+pub struct HistorySystem {
+    undos: EventCursor<UndoRequested>,
+    redos: EventCursor<RedoRequested>,
+}
+
+impl HistorySystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for HistorySystem {
+    fn run(&mut self, world: &mut World) {
+        let undos = world.resource::<Events<UndoRequested>>().map_or(0, |events| self.undos.read(events).count());
+        let redos = world.resource::<Events<RedoRequested>>().map_or(0, |events| self.redos.read(events).count());
+
+        for _ in 0..undos {
+            if !history::undo(world) {
+                debug!("[HistorySystem] Nothing to undo.");
+            }
+        }
+        for _ in 0..redos {
+            if !history::redo(world) {
+                debug!("[HistorySystem] Nothing to redo.");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::score::{Score, ScoringMode};
+    use crate::components::{Card, Rank, StackType, Suit};
+    use crate::events::{register_game_events, MoveRequested, StockClicked};
+    use crate::harness::{card_world, push_card, GameHarness};
+    use crate::systems::stock_system::StockSystem;
+
+    #[test]
+    fn undo_and_redo_requests_step_through_history() {
+        let mut world = card_world();
+        register_game_events(&mut world);
+        let card = push_card(&mut world, Suit::Club, Rank::Seven, StackType::Stock, false);
+
+        let mut stock = StockSystem::new();
+        let mut system = HistorySystem::new();
+        world.send_event(StockClicked);
+        world.update_events();
+        stock.run(&mut world);
+        assert_eq!(world.stacks().location(card), Some((StackType::Waste, 0)));
+
+        world.send_event(UndoRequested);
+        world.update_events();
+        system.run(&mut world);
+        assert_eq!(world.stacks().location(card), Some((StackType::Stock, 0)));
+        assert!(!world.get_component::<Card>(card).unwrap().is_face_up);
+
+        world.send_event(RedoRequested);
+        world.update_events();
+        system.run(&mut world);
+        assert_eq!(world.stacks().location(card), Some((StackType::Waste, 0)));
+        assert!(world.get_component::<Card>(card).unwrap().is_face_up);
+        println!("HistorySystem の undo/redo テスト、成功！⏪");
+    }
+
+    #[test]
+    fn undo_takes_back_the_points_of_the_move() {
+        let mut harness = GameHarness::new();
+        harness.world.insert_resource(Score::new(ScoringMode::Standard).new_game());
+        push_card(&mut harness.world, Suit::Club, Rank::Nine, StackType::Tableau(0), false);
+        let ace = push_card(&mut harness.world, Suit::Heart, Rank::Ace, StackType::Tableau(0), true);
+        let points = |harness: &GameHarness| Score::from_world(&harness.world).points;
+
+        // 組札へ +10、裏向きのカードがめくれて +5
+        harness.send(MoveRequested { entity: ace, target: StackType::Foundation(0) });
+        harness.tick();
+        assert_eq!(points(&harness), 15);
+
+        // undo で取り消して、同じ手をもう一回指しても点は増えない
+        harness.send(UndoRequested);
+        harness.tick();
+        assert_eq!(points(&harness), 0);
+        harness.send(MoveRequested { entity: ace, target: StackType::Foundation(0) });
+        harness.tick();
+        assert_eq!(points(&harness), 15);
+
+        // redo ならもう一度つく
+        harness.send(UndoRequested);
+        harness.tick();
+        harness.send(RedoRequested);
+        harness.tick();
+        assert_eq!(points(&harness), 15);
+        assert_eq!(harness.world.stacks().location(ace), Some((StackType::Foundation(0), 0)));
+        println!("undo で点数が戻るテスト、成功！💯");
+    }
+
+    #[test]
+    fn two_moves_in_one_tick_are_undone_with_their_own_points() {
+        let mut harness = GameHarness::new();
+        harness.world.insert_resource(Score::new(ScoringMode::Standard).new_game());
+        push_card(&mut harness.world, Suit::Club, Rank::Nine, StackType::Tableau(0), false);
+        let heart = push_card(&mut harness.world, Suit::Heart, Rank::Ace, StackType::Tableau(0), true);
+        let spade = push_card(&mut harness.world, Suit::Spade, Rank::Ace, StackType::Tableau(1), true);
+        let points = |harness: &GameHarness| Score::from_world(&harness.world).points;
+
+        // 1手目は組札へ +10 とめくり +5、2手目は組札へ +10 だけ
+        harness.send(MoveRequested { entity: heart, target: StackType::Foundation(0) });
+        harness.send(MoveRequested { entity: spade, target: StackType::Foundation(3) });
+        harness.tick();
+        assert_eq!(points(&harness), 25);
+
+        harness.send(UndoRequested);
+        harness.tick();
+        assert_eq!(harness.world.stacks().location(spade), Some((StackType::Tableau(1), 0)));
+        assert_eq!(points(&harness), 15);
+        harness.send(UndoRequested);
+        harness.tick();
+        assert_eq!(harness.world.stacks().location(heart), Some((StackType::Tableau(0), 1)));
+        assert_eq!(points(&harness), 0);
+        println!("同じフレームの2手を1手ずつ undo するテスト、成功！⏪");
+    }
+}
@@ -0,0 +1,139 @@
+// src/systems/layout_system.rs
+//! カードの表示位置 (Position) を、今いるスタックと順番から計算し直すシステムだよ！📐
+//!
+//! 移動処理やサーバーからの状態同期で StackInfo が変わっても、
+//! Layout ステージでこれが走るので、画面上の位置はいつもスタックの並びと一致する。
+
+use std::collections::HashSet;
+
+use crate::components::{DraggingInfo, Position, StackType};
+use crate::ecs::{entity::Entity, query::With, system::System, world::World};
+use crate::logic::layout_calculator;
+
+/// 全カードの Position を layout_calculator の結果に合わせるシステム。
+/// ドラッグ中のカードはマウスについて行ってほしいので触らないよ🖱️
+#[derive(Default)]
+pub struct LayoutSystem;
+
+impl LayoutSystem {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl System for LayoutSystem {
+    fn run(&mut self, world: &mut World) {
+        // 掴まれてるカード (掴んだ本人 + 一緒に運ばれてるカード) は除外
+        let dragged: HashSet<Entity> = world
+            .query::<(Entity, &DraggingInfo)>()
+            .flat_map(|(entity, info)| std::iter::once(entity).chain(info.dragged_group.iter().copied()))
+            .collect();
+
+        // 先に全部の目標位置を計算してから書き込む (計算中は World を読むだけにしたいので)
+        let targets: Vec<(Entity, Position)> = world
+            .query_filtered::<Entity, With<Position>>()
+            .filter(|entity| !dragged.contains(entity))
+            .filter_map(|entity| {
+                let (stack_type, index) = world.stacks().location(entity)?;
+                if stack_type == StackType::Hand {
+                    return None; // 手札のレイアウトはまだ決まってない
+                }
+                Some((entity, layout_calculator::calculate_card_position(stack_type, index as u8, world)))
+            })
+            .collect();
+
+        // 書き換えるのは位置が違うカードだけ (get_component_mut すると changed 扱いになっちゃうので)
+        for (entity, target) in targets {
+            if world.get_component::<Position>(entity).is_some_and(|position| *position != target) {
+                if let Some(position) = world.get_component_mut::<Position>(entity) {
+                    *position = target;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Card, Rank, StackInfo, Suit};
+    use crate::config::layout::{WASTE_FAN_X_OFFSET, WASTE_POS_X};
+
+    fn add_card(world: &mut World, stack_type: StackType, index: u8, is_face_up: bool) -> Entity {
+        let entity = world.create_entity();
+        world.add_component(entity, Card { suit: Suit::Spade, rank: Rank::Ace, is_face_up });
+        world.add_component(entity, StackInfo::new(stack_type, index));
+        world.add_component(entity, Position { x: -1.0, y: -1.0 });
+        entity
+    }
+
+    #[test]
+    fn snaps_cards_to_their_stack_but_leaves_dragged_cards_alone() {
+        let mut world = World::new();
+        world.register_component::<Card>();
+        world.register_component::<StackInfo>();
+        world.register_component::<Position>();
+        world.register_component::<DraggingInfo>();
+
+        let bottom = add_card(&mut world, StackType::Tableau(2), 0, false);
+        let top = add_card(&mut world, StackType::Tableau(2), 1, true);
+        let held = add_card(&mut world, StackType::Waste, 0, true);
+        world.add_component(held, DraggingInfo {
+            original_stack_type: StackType::Waste,
+            original_group_positions: vec![(held, 0)],
+            original_x: 0.0,
+            original_y: 0.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            dragged_group: vec![held],
+        });
+
+        LayoutSystem::new().run(&mut world);
+
+        assert_eq!(world.get_component::<Position>(bottom), Some(&layout_calculator::calculate_card_position(StackType::Tableau(2), 0, &world)));
+        assert_eq!(world.get_component::<Position>(top), Some(&layout_calculator::calculate_card_position(StackType::Tableau(2), 1, &world)));
+        assert_eq!(world.get_component::<Position>(held), Some(&Position { x: -1.0, y: -1.0 }));
+        println!("LayoutSystem のテスト、成功！🎉");
+    }
+
+    #[test]
+    fn waste_fans_out_the_top_cards() {
+        let mut world = World::new();
+        world.register_component::<Card>();
+        world.register_component::<StackInfo>();
+        world.register_component::<Position>();
+        world.insert_resource(crate::config::GameRules::draw_three());
+        let waste: Vec<Entity> = (0..5).map(|index| add_card(&mut world, StackType::Waste, index, true)).collect();
+
+        LayoutSystem::new().run(&mut world);
+
+        // 下の2枚は重ねて、上の3枚だけ右にずらす
+        let xs: Vec<f32> = waste.iter().map(|&entity| world.get_component::<Position>(entity).unwrap().x).collect();
+        assert_eq!(xs, vec![
+            WASTE_POS_X,
+            WASTE_POS_X,
+            WASTE_POS_X,
+            WASTE_POS_X + WASTE_FAN_X_OFFSET,
+            WASTE_POS_X + 2.0 * WASTE_FAN_X_OFFSET,
+        ]);
+        println!("捨て札をずらして並べるテスト、成功！🃏");
+    }
+
+    #[test]
+    fn cards_already_in_place_are_not_marked_changed() {
+        use crate::harness::{push_card, GameHarness};
+
+        let mut harness = GameHarness::new();
+        push_card(&mut harness.world, Suit::Spade, Rank::Two, StackType::Tableau(0), false);
+        push_card(&mut harness.world, Suit::Heart, Rank::Ace, StackType::Tableau(0), true);
+        push_card(&mut harness.world, Suit::Club, Rank::King, StackType::Stock, false);
+        harness.tick();
+
+        // 何も起きてないフレームでは、Position は1枚も書き換わらない
+        let since = harness.world.change_tick();
+        harness.tick();
+        harness.world.set_last_change_tick(since);
+        assert!(harness.world.changed::<Position>().is_empty());
+        println!("動いてないカードは changed にならないテスト、成功！🎉");
+    }
+}
@@ -1,3 +1,40 @@
+pub mod auto_complete_system;
 pub mod deal_system;
+pub mod game_clock_system;
+pub mod game_record_system;
+pub mod history_system;
+pub mod layout_system;
 pub mod move_card_system;
-pub mod win_condition_system; 
\ No newline at end of file
+pub mod network_outbox_system;
+pub mod replay_system;
+pub mod scoring_system;
+pub mod stock_system;
+pub mod win_condition_system; // src/systems/win_condition_system.rs
+
+use crate::ecs::schedule::{Schedule, Stage};
+
+/// ゲームで毎フレーム回す標準のスケジュールを作るよ！📅
+/// ブラウザ (`GameApp::tick`) もテスト用ハーネスも同じものを使うので、実行されるシステムと順番はいつも一緒。
+pub fn build_schedule() -> Schedule {
+    let mut schedule = build_replay_schedule();
+    schedule.add_system(Stage::Network, network_outbox_system::NetworkOutboxSystem::new());
+    schedule
+}
+
+/// `build_schedule` からサーバーへの送信 (Network ステージ) を抜いたもの。
+/// 棋譜の再生 (`ReplaySystem`) で使うよ。サーバーに同じ手をもう一回送っちゃわないように。
+pub fn build_replay_schedule() -> Schedule {
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(Stage::Logic, stock_system::StockSystem::new())
+        .add_system(Stage::Logic, auto_complete_system::AutoCompleteSystem::new())
+        .add_system(Stage::Logic, move_card_system::MoveCardSystem::new())
+        .add_system(Stage::Logic, game_record_system::GameRecordSystem::new())
+        .add_system(Stage::Logic, win_condition_system::WinConditionSystem::new())
+        .add_system(Stage::Logic, game_clock_system::GameClockSystem::new())
+        .add_system(Stage::Logic, scoring_system::ScoringSystem::new())
+        // 点数は履歴の一番新しい1回分に付くので、undo / redo は点数をつけ終わってから
+        .add_system(Stage::Logic, history_system::HistorySystem::new())
+        .add_system(Stage::Layout, layout_system::LayoutSystem::new());
+    schedule
+}
@@ -1,17 +1,18 @@
 use crate::components::{ // components はそのまま
     card::Card,
-    position::Position,
-    stack::{StackInfo, StackType}
+    stack::StackType
 };
 use crate::ecs::{ // ★修正: crate:: を crate::ecs:: に変更！
     entity::Entity,
     system::System,
     world::World,
 };
+use crate::ecs::event::{EventCursor, Events};
+use crate::events::{CardFlipped, MoveApplied, MoveRejected, MoveRequested};
+use crate::logic::history::{self, GameCommand};
 use crate::logic::rules;
-use crate::app::layout_calculator;
-use web_sys::console;
-use wasm_bindgen::JsValue;
+use crate::logic::scoring;
+use log::{debug, error};
 
 // --- StackType Enum (移動元・移動先の種類を示す) ---
 // TODO: この enum をどこか適切な場所 (e.g., components/mod.rs や components/stack.rs?) に定義する
@@ -28,162 +29,180 @@ use wasm_bindgen::JsValue;
 
 /// カード移動のロジックを実行するシステムだよ！🖱️💨
 ///
-/// プレイヤーからの入力（「どのカードをどこに動かすか」）を受け取って、
+/// プレイヤーからの入力（`MoveRequested` イベント）を受け取って、
 /// それがソリティアのルール上可能かチェックし、可能なら World の状態を更新するよ。
+/// 結果は `MoveApplied` / `MoveRejected` イベントで知らせる。
 pub struct MoveCardSystem {
-    // 今回は状態を持たないシステムとする
+    /// `MoveRequested` をどこまで読んだか。
+    requests: EventCursor<MoveRequested>,
 }
 
 impl MoveCardSystem {
     /// 新しい MoveCardSystem を作るよ。
     pub fn new() -> Self {
-        Self {}
+        Self { requests: EventCursor::default() }
     }
 
     /// 指定されたカードを指定されたスタックに移動させる処理を実行する。
-    /// ルールチェック (is_move_valid) は呼び出し元 (System::run など) で行う前提。
+    /// ルールチェック (is_move_valid) は呼び出し元 (System::run やサーバーなど) で行う前提。
+    ///
+    /// 場札 (Tableau) から動かす場合は、そのカードの上に積まれてるカードも一緒に運ぶよ！🚚
+    /// 移動元の場札で下に裏向きのカードが残ったら、それも表向きにする (クロンダイクのルール)。
+    /// 全部ひとまとめの `GameCommand` として実行するので、1回の undo で丸ごと元に戻せる⏪
+    /// 点数 (`logic::scoring`) もここでつけるので、undo すればその分だけ取り消されるよ。
     ///
     /// # 引数
     /// * `world` - World への可変参照。
-    /// * `moved_entity` - 移動させるカードのエンティティ。
+    /// * `moved_entity` - 移動させるカード (まとめて運ぶ場合は一番下のカード) のエンティティ。
     /// * `target_stack` - 移動先のスタックタイプ。
-    fn process_move(
+    pub fn process_move(
         &mut self,
         world: &mut World,
         moved_entity: Entity,
         target_stack: StackType,
     ) {
-        console::log_1(&JsValue::from_str(&format!(
+        debug!(
             "[MoveCardSystem] Processing move for {:?} to {:?}...",
             moved_entity,
             target_stack
-        )));
-
-        // --- 1. 移動させるカードの StackInfo を更新 --- 
-        let new_position_in_stack = {
-            // 移動先のスタックに既に存在するカードの数を数える
-            let target_entities = world.get_all_entities_with_component::<StackInfo>();
-            target_entities
-                .iter()
-                .filter(|&&e| {
-                    world.get_component::<StackInfo>(e)
-                        .map_or(false, |si| si.stack_type == target_stack)
-                })
-                .count() as u8 // 新しいカードは一番上に追加されるので、既存の数がそのまま position になる
-        };
-
-        if let Some(stack_info) = world.get_component_mut::<StackInfo>(moved_entity) {
-            console::log_1(&JsValue::from_str(&format!(
-                "  Updating StackInfo for {:?}: {:?} -> {:?}, pos: {} -> {}",
-                moved_entity,
-                stack_info.stack_type,
-                target_stack,
-                stack_info.position_in_stack,
-                new_position_in_stack
-            )));
-            stack_info.stack_type = target_stack;
-            stack_info.position_in_stack = new_position_in_stack;
-        } else {
-            console::log_1(&JsValue::from_str(&format!(
-                "[MoveCardSystem Error] Failed to get StackInfo for moved entity {:?}!",
-                moved_entity
-            )));
-            return; // StackInfo がないと位置計算などができないので中断
-        }
-
-        // --- 2. 移動させるカードの Position を更新 --- 
-        // layout_calculator を使って新しい座標を計算する
-        let new_position = layout_calculator::calculate_card_position(
-            target_stack,           // 新しいスタックタイプ
-            new_position_in_stack, // 新しいスタック内位置
-            world,                 // World の現在の状態を参照して計算
         );
 
-        if let Some(position) = world.get_component_mut::<Position>(moved_entity) {
-            console::log_1(&JsValue::from_str(&format!(
-                "  Updating Position for {:?}: ({}, {}) -> ({}, {})",
-                moved_entity,
-                position.x, position.y,
-                new_position.x, new_position.y
-            )));
-            position.x = new_position.x;
-            position.y = new_position.y;
-        } else {
-             console::log_1(&JsValue::from_str(&format!(
-                "[MoveCardSystem Error] Failed to get Position for moved entity {:?}!",
-                moved_entity
-            )));
-             // Position がなくても処理は続けられるかもしれないが、一応ログは出す
-        }
-
-        // --- 3. 移動させるカードの Card 状態を更新 (必要なら) ---
+        // --- 0. 移動元の場所を調べる (後で下のカードをめくるのに使う) ---
+        let (original_stack, index_in_stack) = match world.stacks().location(moved_entity) {
+            Some(location) => location,
+            None => {
+                error!(
+                    "[MoveCardSystem Error] Failed to get StackInfo for moved entity {:?}!",
+                    moved_entity
+                );
+                return; // StackInfo がないと位置計算などができないので中断
+            }
+        };
+        let source_cards = world.stacks().cards(original_stack).to_vec();
+        let moving_group = &source_cards[index_in_stack..];
+
+        // --- 1. moved_entity から上をまとめて移動先へ (StackInfo と Position の更新は GameCommand がやってくれる) ---
+        // 場札以外は一番上のカードしか動かせないルールなので、実質1枚だけ動くよ
+        let mut commands = vec![GameCommand::MoveRun {
+            from: original_stack,
+            index: index_in_stack,
+            to: target_stack,
+            count: moving_group.len(),
+        }];
+
+        // --- 2. 移動させるカードの Card 状態を更新 (必要なら) ---
         // 例: Tableau に移動したら表向きにする、など (クロンダイク固有のルール)
-        if let Some(card) = world.get_component_mut::<Card>(moved_entity) {
-            if matches!(target_stack, StackType::Tableau(_)) {
-                if !card.is_face_up {
-                    console::log_1(&JsValue::from_str(&format!(
-                        "  Flipping card {:?} face up.",
-                        moved_entity
-                    )));
-                    card.is_face_up = true;
+        if matches!(target_stack, StackType::Tableau(_)) {
+            for &entity in moving_group {
+                if world.get_component::<Card>(entity).is_some_and(|card| !card.is_face_up) {
+                    debug!("  Flipping card {:?} face up.", entity);
+                    commands.push(GameCommand::Flip { entity, face_up: true });
                 }
             }
-            // 他のスタックタイプでのルール (例: Stock に戻ったら裏向きとか) があればここに追加
         }
 
-        // --- 4. 移動元のスタックの状態更新 (必要なら) ---
-        // 例: 移動元が Tableau で、その下に裏向きカードがあったら表向きにする (クロンダイク)
-        // これは、移動するカードの元の StackInfo が必要になるので、
-        // この関数の最初で保存しておく必要がある。
-        // TODO: 元のスタック情報を使った処理を追加する
-
-        console::log_1(&JsValue::from_str(&format!(
-            "[MoveCardSystem] Move processed successfully for {:?}.
-",
-            moved_entity
-        )));
+        // --- 3. 移動元のスタックの状態更新 ---
+        // 移動元が Tableau で、残った一番上のカードが裏向きなら表向きにする (クロンダイク)
+        let mut revealed = None;
+        if let (StackType::Tableau(_), Some(below)) = (original_stack, index_in_stack.checked_sub(1)) {
+            let card_below_entity = source_cards[below];
+            if world.get_component::<Card>(card_below_entity).is_some_and(|card| !card.is_face_up) {
+                debug!("  Revealing card {:?} left behind in {:?}.", card_below_entity, original_stack);
+                commands.push(GameCommand::Flip { entity: card_below_entity, face_up: true });
+                revealed = Some(card_below_entity);
+            }
+        }
+
+        let moved_count = moving_group.len();
+        history::execute(world, commands);
+        // 点数は今記録した1回分につける (同じフレームに何手あっても、それぞれの手の分だけ)
+        scoring::record_move(world, original_stack, target_stack);
+        if let Some(entity) = revealed {
+            scoring::record_flip(world);
+            world.send_event(CardFlipped { entity });
+        }
+
+        debug!(
+            "[MoveCardSystem] Move processed successfully for {:?} ({} card(s)).",
+            moved_entity,
+            moved_count
+        );
+    }
+}
+
+impl Default for MoveCardSystem {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl System for MoveCardSystem {
-    /// カード移動のロジックを実行するよ！(リファクタリング後)
+    /// 溜まってる `MoveRequested` を古い順に処理するよ！
     fn run(&mut self, world: &mut World) {
-        // ここに、移動リクエスト (例: イベントキューやネットワークメッセージから) を取得し、
-        // logic::rules::is_move_valid でチェックし、
-        // 問題なければ self.process_move(world, moved_entity, target_stack) を呼び出す
-        // ロジックを実装する。
-
-        // --- ダミー実装 (テスト用) ---
-        // 仮の移動リクエストを処理する例
-        let move_requests: Vec<(Entity, StackType)> = Vec::new(); // 本来はどこかから取得
-
-        if !move_requests.is_empty() {
-            console::log_1(&JsValue::from_str(&format!(
-                "[MoveCardSystem] Running... Processing {} move requests.",
-                move_requests.len()
-            )));
-
-            for (moved_entity, target_stack) in move_requests {
-                console::log_1(&JsValue::from_str(&format!(
-                    "  Checking move validity for {:?} -> {:?}...",
-                    moved_entity, target_stack
-                )));
-                // ルールチェック！
-                if rules::is_move_valid(world, moved_entity, target_stack) {
-                     console::log_1(&JsValue::from_str("  Move is valid! Processing..."));
-                    // 有効なら移動処理を実行！
-                    self.process_move(world, moved_entity, target_stack);
-                } else {
-                    console::log_1(&JsValue::from_str("  Move is invalid!"));
-                    // 無効な場合は何もしないか、エラー通知などを行う
+        let requests: Vec<MoveRequested> = match world.resource::<Events<MoveRequested>>() {
+            Some(events) => self.requests.read(events).copied().collect(),
+            None => return, // イベントが登録されてない World (サーバーなど) では何もしない
+        };
+
+        for MoveRequested { entity, target } in requests {
+            debug!("[MoveCardSystem] Checking move validity for {:?} -> {:?}...", entity, target);
+            let from = world.stacks().location(entity);
+            // ルールチェック！
+            match (rules::validate_move(world, entity, target), from) {
+                (Ok(()), Some((from, index))) => {
+                    let count = world.stacks().len(from) - index;
+                    self.process_move(world, entity, target);
+                    world.send_event(MoveApplied { entity, from, to: target, count });
+                }
+                (Err(reason), _) => {
+                    debug!("  Move is invalid: {}", reason);
+                    world.send_event(MoveRejected { entity, reason });
+                }
+                (Ok(()), None) => {
+                    error!("[MoveCardSystem Error] {:?} passed validation but is not in any stack!", entity);
                 }
             }
-            console::log_1(&JsValue::from_str("[MoveCardSystem] Finished processing requests."));
-        } else {
-            // console::log_1(&JsValue::from_str("[MoveCardSystem] Running... No move requests to process."));
         }
-        // --- ダミー実装ここまで ---
     }
 }
 
-// --- 削除: テストコード (rules 側に移動したため) --- 
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Card, Rank, Suit};
+    use crate::events::register_game_events;
+    use crate::harness::{card_world, push_card};
+
+    #[test]
+    fn move_requests_are_applied_or_rejected_through_events() {
+        let mut world = card_world();
+        register_game_events(&mut world);
+
+        let hidden = push_card(&mut world, Suit::Club, Rank::Two, StackType::Tableau(0), false);
+        let ace = push_card(&mut world, Suit::Heart, Rank::Ace, StackType::Tableau(0), true);
+        let five = push_card(&mut world, Suit::Spade, Rank::Five, StackType::Tableau(1), true);
+
+        world.send_event(MoveRequested { entity: five, target: StackType::Foundation(0) });
+        world.send_event(MoveRequested { entity: ace, target: StackType::Foundation(0) });
+        world.update_events();
+
+        let mut system = MoveCardSystem::new();
+        system.run(&mut world);
+
+        assert_eq!(world.stacks().location(ace), Some((StackType::Foundation(0), 0)));
+        assert_eq!(world.stacks().location(five), Some((StackType::Tableau(1), 0)));
+        assert!(world.get_component::<Card>(hidden).unwrap().is_face_up);
+
+        let applied: Vec<_> = world.resource::<Events<MoveApplied>>().unwrap().iter().copied().collect();
+        assert_eq!(applied, vec![MoveApplied { entity: ace, from: StackType::Tableau(0), to: StackType::Foundation(0), count: 1 }]);
+        let rejected: Vec<Entity> = world.resource::<Events<MoveRejected>>().unwrap().iter().map(|r| r.entity).collect();
+        assert_eq!(rejected, vec![five]);
+        let flipped: Vec<_> = world.resource::<Events<CardFlipped>>().unwrap().iter().copied().collect();
+        assert_eq!(flipped, vec![CardFlipped { entity: hidden }]);
+
+        // 同じリクエストを2回処理しない
+        system.run(&mut world);
+        assert_eq!(world.resource::<Events<MoveApplied>>().unwrap().iter().count(), 1);
+        println!("MoveCardSystem のイベント処理テスト、成功！🎉");
+    }
+}
@@ -0,0 +1,110 @@
+// src/systems/network_outbox_system.rs
+//! World で起きたこと (イベント) を、サーバーに送るメッセージに変換するシステムだよ！📮
+//!
+//! 実際の送信はブラウザの WebSocket (NetworkManager) が必要なので、ここでは `Outbox` に積むだけ。
+//! `GameApp::tick` がスケジュールの後に `Outbox` を空にして送ってくれるよ。
+//!
+//! World に `EntityMap` があれば、エンティティはサーバー側の ID に変換してから積む。
+//! サーバーがまだ知らないカード (対応表に無いカード) の移動は送れないので、警告を出して捨てるよ。
+
+use crate::ecs::entity::Entity;
+use crate::ecs::entity_map::EntityMap;
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::resource::Resource;
+use crate::ecs::system::System;
+use crate::ecs::world::World;
+use crate::events::{MoveApplied, RedoRequested, StockCycled, UndoRequested};
+use crate::protocol::ClientMessage;
+use log::warn;
+
+/// サーバーに送る予定のメッセージの置き場 (リソース)。置かれてない World では何も積まれない。
+#[derive(Debug, Default)]
+pub struct Outbox(pub Vec<ClientMessage>);
+
+impl Resource for Outbox {}
+
+/// `MoveApplied` → `MakeMove`、`StockCycled` → `DrawFromStock` / `ResetWasteToStock`、
+/// `UndoRequested` / `RedoRequested` → `Undo` / `Redo` に変換する。
+/// (undo / redo はこっちで戻せたかどうかに関係なく送る。やっていいかどうか決めるのはサーバー)
+#[derive(Default)]
+pub struct NetworkOutboxSystem {
+    moves: EventCursor<MoveApplied>,
+    stock: EventCursor<StockCycled>,
+    undos: EventCursor<UndoRequested>,
+    redos: EventCursor<RedoRequested>,
+}
+
+impl NetworkOutboxSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for NetworkOutboxSystem {
+    fn run(&mut self, world: &mut World) {
+        // こっちのエンティティ → サーバーのエンティティ (対応表が無い World ではそのまま)
+        let entity_map = world.resource::<EntityMap>();
+        let to_remote = |local: Entity| match entity_map {
+            Some(map) => map.remote(local),
+            None => Some(local),
+        };
+
+        let mut messages = Vec::new();
+        if let Some(events) = world.resource::<Events<MoveApplied>>() {
+            for applied in self.moves.read(events) {
+                match to_remote(applied.entity) {
+                    Some(moved_entity) => messages.push(ClientMessage::MakeMove { moved_entity, target_stack: applied.to }),
+                    None => warn!("[NetworkOutbox] {:?} is not known to the server yet; move not sent.", applied.entity),
+                }
+            }
+        }
+        if let Some(events) = world.resource::<Events<StockCycled>>() {
+            messages.extend(self.stock.read(events).map(|cycled| match cycled {
+                StockCycled::Drew(_) => ClientMessage::DrawFromStock,
+                StockCycled::Reset => ClientMessage::ResetWasteToStock,
+            }));
+        }
+        if let Some(events) = world.resource::<Events<UndoRequested>>() {
+            messages.extend(self.undos.read(events).map(|_| ClientMessage::Undo));
+        }
+        if let Some(events) = world.resource::<Events<RedoRequested>>() {
+            messages.extend(self.redos.read(events).map(|_| ClientMessage::Redo));
+        }
+
+        if let Some(outbox) = world.resource_mut::<Outbox>() {
+            outbox.0.extend(messages);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::StackType;
+    use crate::events::register_game_events;
+
+    #[test]
+    fn moves_are_sent_with_server_entity_ids() {
+        let mut world = World::new();
+        register_game_events(&mut world);
+        world.insert_resource(Outbox::default());
+        let mut map = EntityMap::default();
+        let synced = world.create_entity();
+        let local_only = world.create_entity();
+        map.insert(Entity::new(30, 2), synced);
+        world.insert_resource(map);
+
+        for entity in [synced, local_only] {
+            world.send_event(MoveApplied { entity, from: StackType::Waste, to: StackType::Tableau(0), count: 1 });
+        }
+        NetworkOutboxSystem::new().run(&mut world);
+
+        let outbox = &world.resource::<Outbox>().unwrap().0;
+        assert_eq!(outbox.len(), 1, "サーバーが知らないカードの移動は送らない");
+        assert!(matches!(
+            outbox[0],
+            ClientMessage::MakeMove { moved_entity, target_stack: StackType::Tableau(0) } if moved_entity == Entity::new(30, 2)
+        ));
+        println!("送信時の ID 変換テスト、成功！📮");
+    }
+}
@@ -0,0 +1,221 @@
+// src/systems/replay_system.rs
+//! 棋譜 (`GameRecord`) を1手ずつ再生するシステムだよ！📼
+//!
+//! 再生用の World は遊んでる World とは別に用意してね (`ReplaySystem::rebuild` が中身を作り直す)。
+//! 棋譜のシードで `DealInitialCardsSystem` から配り直して、記録された手をプレイヤーの入力と同じイベントにして
+//! サーバーに何も送らないスケジュール (`systems::build_replay_schedule`) で1手ずつ処理する。
+//!
+//! - 1手進める / 戻る (`step_forward` / `step_back`)、好きな手まで飛ぶ (`seek`)
+//! - `play(speed)` で記録された時間どおりに (speed 倍速で) 自動再生。`run` (か `advance`) を毎フレーム呼んでね
+//!
+//! 戻る時は最初から配り直して、手前までの手を全部入れ直す (undo の記録も棋譜に入ってるので、これが一番確実)。
+//! マルチプレイで揉めた時の確認や、ルールの回帰テストに使うよ🔍
+
+use crate::components::score::Score;
+use crate::components::{Card, DraggingInfo, GameState, Player, Position, StackInfo};
+use crate::ecs::schedule::Schedule;
+use crate::ecs::system::System;
+use crate::ecs::time::Time;
+use crate::ecs::world::World;
+use crate::events::register_game_events;
+use crate::logic::game_record::{self, GameRecord};
+use crate::logic::history::History;
+use crate::systems::{self, deal_system::DealInitialCardsSystem};
+use log::warn;
+
+pub struct ReplaySystem {
+    record: GameRecord,
+    /// 記録された手を処理するスケジュール (サーバーには何も送らない)。
+    schedule: Schedule,
+    /// World に入れ終わった手の数 (0 なら配った直後)。
+    position: usize,
+    /// 自動再生中なら何倍速か。
+    speed: Option<f64>,
+    /// 再生位置の時間 (棋譜の `at` と同じ、最初の1手からの秒数)。
+    clock: f64,
+}
+
+impl ReplaySystem {
+    pub fn new(record: GameRecord) -> Self {
+        Self { record, schedule: systems::build_replay_schedule(), position: 0, speed: None, clock: 0.0 }
+    }
+
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// 今何手目まで入れたか。
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 棋譜の手の数。
+    pub fn len(&self) -> usize {
+        self.record.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.record.moves.is_empty()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.speed.is_some()
+    }
+
+    /// `world` を棋譜の最初 (配った直後) の盤面に作り直す。
+    pub fn rebuild(&mut self, world: &mut World) {
+        *world = World::new();
+        world.register_serializable_component::<Card>();
+        world.register_serializable_component::<Position>();
+        world.register_serializable_component::<StackInfo>();
+        world.register_component::<Player>();
+        world.register_component::<DraggingInfo>();
+        world.insert_resource(GameState::playing());
+        world.insert_resource(History::default());
+        world.insert_resource(self.record.variant);
+        world.insert_resource(self.record.rules);
+        world.insert_resource(Score::new(self.record.scoring));
+        register_game_events(world);
+        DealInitialCardsSystem.execute(world, self.record.seed);
+
+        self.schedule = systems::build_replay_schedule();
+        self.position = 0;
+        self.clock = 0.0;
+    }
+
+    /// 1手進める。もう最後なら `Ok(false)`。手が盤面と合わなければ (却下されたら) 再生を止めてエラー。
+    pub fn step_forward(&mut self, world: &mut World) -> Result<bool, String> {
+        let Some(&recorded) = self.record.moves.get(self.position) else {
+            self.speed = None;
+            return Ok(false);
+        };
+        if let Err(e) = game_record::replay(world, &mut self.schedule, std::slice::from_ref(&recorded)) {
+            self.speed = None;
+            return Err(format!("Move {}: {}", self.position + 1, e));
+        }
+        self.position += 1;
+        self.clock = self.clock.max(recorded.at);
+        Ok(true)
+    }
+
+    /// 1手戻る。もう最初なら `Ok(false)`。
+    pub fn step_back(&mut self, world: &mut World) -> Result<bool, String> {
+        if self.position == 0 {
+            return Ok(false);
+        }
+        self.seek(world, self.position - 1)?;
+        Ok(true)
+    }
+
+    /// `index` 手目まで入れた盤面にする (棋譜の長さより先なら最後まで)。
+    pub fn seek(&mut self, world: &mut World, index: usize) -> Result<(), String> {
+        let index = index.min(self.len());
+        if index < self.position {
+            self.rebuild(world);
+        }
+        while self.position < index {
+            self.step_forward(world)?;
+        }
+        self.clock = index.checked_sub(1).map_or(0.0, |last| self.record.moves[last].at);
+        Ok(())
+    }
+
+    /// 記録された時間どおりに、`speed` 倍速で自動再生する。
+    pub fn play(&mut self, speed: f64) {
+        self.speed = (speed > 0.0).then_some(speed);
+    }
+
+    pub fn pause(&mut self) {
+        self.speed = None;
+    }
+
+    /// 自動再生中なら `dt` 秒 (× 速さ) 進めて、その時間までに指された手を入れる。最後まで行ったら止まるよ。
+    pub fn advance(&mut self, world: &mut World, dt: f64) -> Result<(), String> {
+        let Some(speed) = self.speed else { return Ok(()) };
+        self.clock += dt * speed;
+        while self.record.moves.get(self.position).is_some_and(|recorded| recorded.at <= self.clock) {
+            self.step_forward(world)?;
+        }
+        if self.position >= self.len() {
+            self.speed = None;
+        }
+        Ok(())
+    }
+}
+
+impl System for ReplaySystem {
+    /// `Time` の dt で `advance` する (スケジュールに入れて使う時用)。
+    fn run(&mut self, world: &mut World) {
+        let dt = world.resource::<Time>().map_or(0.0, |time| time.delta_seconds);
+        if let Err(e) = self.advance(world, dt) {
+            warn!("[ReplaySystem] Replay stopped: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::StackType;
+    use crate::config::GameRules;
+    use crate::logic::notation::Move;
+    use crate::components::score::ScoringMode;
+    use crate::logic::variants::Variant;
+
+    /// シード 7 のクロンダイク (1枚めくり) に `moves` を 1 秒おきに指した棋譜。
+    fn record_of(moves: &[&str]) -> GameRecord {
+        let mut record = GameRecord::new(7, Variant::Klondike, GameRules::draw_one(), ScoringMode::Standard);
+        for (second, play) in moves.iter().enumerate() {
+            record.push(second as f64, play.parse::<Move>().unwrap());
+        }
+        record
+    }
+
+    fn waste_top(world: &World) -> Option<Card> {
+        world.stacks().top(StackType::Waste).and_then(|entity| world.get_component::<Card>(entity).cloned())
+    }
+
+    #[test]
+    fn steps_seeks_and_plays_through_a_record() {
+        let mut world = World::new();
+        let mut replay = ReplaySystem::new(record_of(&["S", "S", "S", "undo"]));
+        replay.rebuild(&mut world);
+        assert_eq!(world.stacks().len(StackType::Stock), 24);
+
+        assert_eq!(replay.step_forward(&mut world), Ok(true));
+        let first = waste_top(&world);
+        assert!(first.is_some());
+        replay.seek(&mut world, 3).unwrap();
+        assert_eq!(world.stacks().len(StackType::Waste), 3);
+
+        // 戻ると配り直して入れ直すので、1手目の後と同じ捨て札になる
+        replay.seek(&mut world, 1).unwrap();
+        assert_eq!((replay.position(), waste_top(&world)), (1, first));
+        assert_eq!(replay.step_back(&mut world), Ok(true));
+        assert!(world.stacks().is_empty(StackType::Waste));
+        assert_eq!(replay.step_back(&mut world), Ok(false));
+
+        // 2倍速で 1 秒 = 記録の 2 秒分 (0秒・1秒・2秒の3手) 進む。最後まで行ったら止まる
+        replay.play(2.0);
+        replay.advance(&mut world, 1.0).unwrap();
+        assert_eq!(replay.position(), 3);
+        replay.advance(&mut world, 1.0).unwrap();
+        assert_eq!(replay.position(), 4);
+        assert_eq!(world.stacks().len(StackType::Waste), 2, "最後の undo まで入ってる");
+        assert!(!replay.is_playing());
+        println!("ReplaySystem のコマ送りと再生テスト、成功！📼");
+    }
+
+    #[test]
+    fn a_move_that_does_not_fit_the_board_stops_the_replay() {
+        let mut world = World::new();
+        let mut replay = ReplaySystem::new(record_of(&["S", "F1->T1"]));
+        replay.rebuild(&mut world);
+        replay.play(1.0);
+        assert!(replay.advance(&mut world, 5.0).is_err());
+        assert_eq!(replay.position(), 1);
+        assert!(!replay.is_playing());
+        assert_eq!(world.stacks().len(StackType::Waste), 1, "1手目までは入ってる");
+        println!("ReplaySystem の食い違い検出テスト、成功！🚨");
+    }
+}
@@ -0,0 +1,58 @@
+// src/systems/scoring_system.rs
+//! 勝った時のタイムボーナスをつけるシステムだよ！💯
+//!
+//! 移動・めくり・捨て札を戻した点数は、その手を履歴に記録する時 (`MoveCardSystem::process_move` / `stock_handler`) につける。
+//! 1手ごとにその1回分として記録されるので、同じフレームに何手あっても undo でちゃんと1手分ずつ取り消せるよ。
+//! ここは `GameWon` を見て、`logic::scoring` のルールでタイムボーナスを足すだけ。
+
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::system::System;
+use crate::ecs::world::World;
+use crate::events::GameWon;
+use crate::logic::scoring;
+
+#[derive(Default)]
+pub struct ScoringSystem {
+    wins: EventCursor<GameWon>,
+}
+
+impl ScoringSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for ScoringSystem {
+    fn run(&mut self, world: &mut World) {
+        let won = world.resource::<Events<GameWon>>().is_some_and(|events| self.wins.read(events).count() > 0);
+        if won {
+            scoring::record_win(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::score::{Score, ScoringMode};
+    use crate::components::GameClock;
+    use crate::events::register_game_events;
+
+    #[test]
+    fn winning_adds_the_time_bonus_once() {
+        let mut world = World::new();
+        register_game_events(&mut world);
+        world.insert_resource(Score::new(ScoringMode::Standard).new_game());
+        world.insert_resource(GameClock { elapsed_seconds: 100.0, started: true, ..GameClock::default() });
+
+        // 勝ったら 100 秒分 (GameClock) のタイムボーナス。同じイベントは2回数えない
+        world.send_event(GameWon);
+        world.update_events();
+        let mut system = ScoringSystem::new();
+        system.run(&mut world);
+        assert_eq!(Score::from_world(&world).points, 7000);
+        system.run(&mut world);
+        assert_eq!(Score::from_world(&world).points, 7000);
+        println!("ScoringSystem のテスト、成功！💯");
+    }
+}
@@ -0,0 +1,71 @@
+// src/systems/stock_system.rs
+//! 山札クリック (`StockClicked`) を処理するシステムだよ！🃏🔄
+
+use crate::ecs::event::{EventCursor, Events};
+use crate::ecs::system::System;
+use crate::ecs::world::World;
+use crate::events::{StockClicked, StockCycled};
+use crate::components::StackType;
+use crate::logic::stock_handler;
+use log::debug;
+
+/// 山札がクリックされたら、今の遊び方のやり方で配る (クロンダイクなら捨て札にめくる)。
+/// 配れなければ捨て札を山札に戻す。やったことは `StockCycled` イベントで知らせるよ。
+#[derive(Default)]
+pub struct StockSystem {
+    clicks: EventCursor<StockClicked>,
+}
+
+impl StockSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl System for StockSystem {
+    fn run(&mut self, world: &mut World) {
+        let clicks = match world.resource::<Events<StockClicked>>() {
+            Some(events) => self.clicks.read(events).count(),
+            None => return,
+        };
+
+        for _ in 0..clicks {
+            let stock_top = world.stacks().top(StackType::Stock);
+            if stock_handler::deal_from_stock(world) {
+                // 捨て札が無い遊び方 (スパイダー) なら、山札の一番上だったカード
+                if let Some(drawn) = world.stacks().top(StackType::Waste).or(stock_top) {
+                    world.send_event(StockCycled::Drew(drawn));
+                }
+            } else if stock_handler::reset_waste_to_stock(world) {
+                world.send_event(StockCycled::Reset);
+            } else {
+                debug!("[StockSystem] Stock and Waste are both empty. Nothing to do.");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Rank, Suit};
+    use crate::events::register_game_events;
+    use crate::harness::{card_world, push_card};
+
+    #[test]
+    fn clicks_draw_until_empty_then_reset() {
+        let mut world = card_world();
+        register_game_events(&mut world);
+        let card = push_card(&mut world, Suit::Diamond, Rank::Nine, StackType::Stock, false);
+
+        world.send_event(StockClicked);
+        world.send_event(StockClicked);
+        world.update_events();
+        StockSystem::new().run(&mut world);
+
+        let cycled: Vec<_> = world.resource::<Events<StockCycled>>().unwrap().iter().copied().collect();
+        assert_eq!(cycled, vec![StockCycled::Drew(card), StockCycled::Reset]);
+        assert_eq!(world.stacks().location(card), Some((StackType::Stock, 0)));
+        println!("StockSystem のテスト、成功！🔄");
+    }
+}
@@ -4,7 +4,7 @@
 // `init` 関数: WASM モジュールを非同期で初期化する関数。
 // `GameApp` クラス: Rust 側で #[wasm_bindgen] を付けた構造体が JS ではクラスみたいに見える！
 // パスはプロジェクトの構成に合わせてね (http-server がルートを配信するので、ルートからの絶対パス /pkg/ になる)
-import init, { GameApp } from '/pkg/ecs_wasm_game5.js';
+import init, { GameApp, set_log_level } from '/pkg/ecs_wasm_game5.js';
 
 // グローバルスコープ (どこからでもアクセスできる場所) に gameApp インスタンスを保持する変数を用意するよ。
 // 最初は null (まだ無い状態) にしておく。
@@ -19,12 +19,21 @@ let draggedEntityId = null;
 
 // --- ★追加: requestAnimationFrame のループID --- ★
 let animationFrameId = null;
+// 前のフレームのタイムスタンプ (ミリ秒)。gameApp.tick(dt) に渡す経過時間の計算用
+let lastFrameTime = null;
 
 // --- DOM 要素を取得 --- (後でイベントリスナーを設定するために先に取っておく！)
 const connectButton = document.getElementById('connect-button');
 const joinButton = document.getElementById('join-button');
 const dealButton = document.getElementById('deal-button');
+const variantSelect = document.getElementById('variant-select'); // 遊び方 (次に配る時から)
+const drawSelect = document.getElementById('draw-select'); // 山札を何枚ずつめくるか
+const passesSelect = document.getElementById('passes-select'); // 山札を何周までできるか (空なら無制限)
+const foundationBackCheckbox = document.getElementById('foundation-back-checkbox'); // 組札から場札に戻せるか
 const getStateButton = document.getElementById('get-state-button');
+const exportButton = document.getElementById('export-button'); // 棋譜 (JSON) をクリップボードへ
+const importButton = document.getElementById('import-button'); // 貼り付けた棋譜を再生
+const replayButton = document.getElementById('replay-button'); // 棋譜を見るだけ (コマ送りできる)
 const connectionStatusSpan = document.getElementById('connection-status');
 const playerIdSpan = document.getElementById('player-id');
 const gameAreaDiv = document.getElementById('game-area'); // ゲーム描画用の div を取得！
@@ -39,10 +48,31 @@ async function main() {
         await init();
         console.log("✅ WASM モジュール初期化完了！");
 
+        // ?log=debug みたいに URL で指定すると、Rust 側のログをそこまで出す (普段は info まで)
+        const logLevel = new URLSearchParams(window.location.search).get('log');
+        if (logLevel) {
+            try {
+                set_log_level(logLevel);
+            } catch (e) {
+                console.warn("ログレベルを変えられませんでした:", e);
+            }
+        }
+
         // GameApp のインスタンスを作成！ Rust 側の GameApp::new() が呼ばれるよ。
         gameApp = new GameApp();
         console.log("🎮 GameApp インスタンス作成完了！", gameApp);
 
+        // 前回のゲームがセーブしてあれば続きから (手が進むたびに localStorage に自動セーブされてる)
+        try {
+            if (gameApp.resume_saved_game()) {
+                syncGameSettings();
+                console.log("💾 セーブしてあったゲームを続きから遊びます。");
+            }
+        } catch (e) {
+            console.warn("💾 セーブデータを読み込めなかったので消します:", e);
+            gameApp.clear_saved_game();
+        }
+
         // --- 初期状態のボタン制御 ---
         // 最初はサーバーに接続しないとゲームに参加したりできないようにする
         connectButton.disabled = false; // 接続ボタンは有効
@@ -78,16 +108,38 @@ async function main() {
 }
 
 // --- ★新しい関数: ゲームループ --- ★
-function gameLoop() {
+function gameLoop(timestamp) {
     // まず、次のフレームで再度 gameLoop を呼び出すように予約！
     // これでループが継続するよ。
     animationFrameId = requestAnimationFrame(gameLoop);
 
+    // 前のフレームからの経過時間 (秒)。最初のフレーム (timestamp 無し) は 0 秒
+    const now = timestamp ?? performance.now();
+    const dt = lastFrameTime === null ? 0 : (now - lastFrameTime) / 1000;
+    lastFrameTime = now;
+
     // --- ループ内で行う処理 --- //
     // 1. 接続状態などの表示を更新 (これは頻繁じゃなくていいかもだけど、一旦入れる)
     updateStatusDisplay();
 
-    // 2. Rust 側のゲーム状態に基づいて Canvas を再描画！
+    // 2. Rust 側で1フレーム進める (受信メッセージの反映 + システムの実行)
+    if (gameApp) {
+        try {
+            // 戻り値は Option<usize> 型。usize はサーバーに拒否されたカードのIDだよ。
+            //   (JSでは number | undefined として扱われる)
+            const rejected_card_id = gameApp.tick(dt);
+            if (rejected_card_id !== undefined) {
+                // どのカードの移動がダメだったか ID も表示するよ。
+                console.warn(`⚠️ サーバーから移動が拒否されました！ (カードID: ${rejected_card_id}) ルールを確認してね！`);
+                // TODO: ここに、もっとリッチなフィードバック処理を追加できるよ！
+                //   例: アラートを表示する (alert(...)), カードを元の位置に戻すアニメーションを開始する、など
+            }
+        } catch (e) {
+            console.error("💥 gameApp.tick 中にエラー:", e);
+        }
+    }
+
+    // 3. Rust 側のゲーム状態に基づいて Canvas を再描画！
     //    update_dragged_position で Position が更新されていれば、
     //    ここでドラッグ中のカードが新しい位置に描画される！✨
     if (gameApp) {
@@ -134,6 +186,11 @@ function setupEventListeners() {
     dealButton.addEventListener('click', () => {
         console.log("🖱️ 配るボタンクリック");
         try {
+            gameApp.set_variant(variantSelect.value); // 選んだ遊び方で配る
+            // 山札のルールも配る前に決める (捨て札はめくった枚数だけずらして見せる)
+            const drawCount = Number(drawSelect.value);
+            const maxPasses = passesSelect.value === '' ? undefined : Number(passesSelect.value);
+            gameApp.set_rules(drawCount, maxPasses, drawCount, foundationBackCheckbox.checked);
             gameApp.deal_initial_cards(); // Rust 側の deal_initial_cards() を呼び出す！
             console.log("🃏 Rust 側でカード配布完了。");
             gameApp.render_game_rust();
@@ -156,6 +213,98 @@ function setupEventListeners() {
         }
     });
 
+    // 「棋譜をコピー」ボタン (バグ報告にはスクショの代わりにこれを貼ってもらう)
+    exportButton.addEventListener('click', async () => {
+        try {
+            const record = gameApp.export_game();
+            await navigator.clipboard.writeText(record);
+            console.log("📜 棋譜をクリップボードにコピーしました:", record);
+        } catch (e) {
+            console.error("棋譜のコピーに失敗:", e);
+        }
+    });
+
+    // 「棋譜を読み込む」ボタン (同じシードで配り直して、手を全部再生する)
+    importButton.addEventListener('click', () => {
+        const record = window.prompt("棋譜 (JSON) を貼り付けてください");
+        if (!record) { return; }
+        try {
+            gameApp.import_game(record);
+            syncGameSettings();
+            gameApp.render_game_rust();
+            console.log("📜 棋譜を再生しました。");
+        } catch (e) {
+            console.error("棋譜の読み込みに失敗:", e);
+        }
+    });
+
+    // 「棋譜を再生」ボタン (遊んでる盤面はそのままで、再生用の盤面を見る)
+    // ← / → で1手ずつ、Space で再生 / 一時停止、Esc で終わり
+    replayButton.addEventListener('click', () => {
+        const record = window.prompt("再生する棋譜 (JSON) を貼り付けてください");
+        if (!record) { return; }
+        try {
+            gameApp.start_replay(record);
+            console.log(`📼 棋譜を再生します (${gameApp.replay_length()} 手)。← / → でコマ送り、Space で再生、Esc で終了`);
+        } catch (e) {
+            console.error("棋譜の再生に失敗:", e);
+        }
+    });
+    document.addEventListener('keydown', (event) => {
+        if (gameApp.replay_position() === undefined) { return; }
+        try {
+            if (event.key === 'ArrowRight') {
+                gameApp.replay_step_forward();
+            } else if (event.key === 'ArrowLeft') {
+                gameApp.replay_step_back();
+            } else if (event.key === ' ') {
+                event.preventDefault();
+                gameApp.replay_is_playing() ? gameApp.replay_pause() : gameApp.replay_play(4.0);
+            } else if (event.key === 'Escape') {
+                gameApp.stop_replay();
+                console.log("📼 再生を終わりました。");
+                return;
+            } else {
+                return;
+            }
+            console.log(`📼 ${gameApp.replay_position()} / ${gameApp.replay_length()} 手目`);
+        } catch (e) {
+            console.error("棋譜の再生中にエラー:", e);
+        }
+    });
+
+    // Ctrl+Z で元に戻す、Ctrl+Y / Ctrl+Shift+Z でやり直す (Mac は Cmd)
+    // H キーでヒント (移動元と移動先が Canvas 上で光る)
+    // A キーでオートコンプリート (山札・捨て札が空で、場札が全部表向きの時だけ)
+    document.addEventListener('keydown', (event) => {
+        const key = event.key.toLowerCase();
+        const plainKey = !(event.ctrlKey || event.metaKey || event.altKey);
+        if (key === 'h' && plainKey) {
+            const hint = gameApp.get_hint();
+            console.log(hint !== undefined ? `💡 ヒント: ${hint}` : "💡 指せる手がありません");
+            return;
+        }
+        if (key === 'a' && plainKey) {
+            if (!gameApp.auto_complete()) {
+                console.log("🪄 まだオートコンプリートできません (山札・捨て札・裏向きのカードが残ってる)");
+            }
+            return;
+        }
+        if (!(event.ctrlKey || event.metaKey)) { return; }
+        if (key === 'z' && !event.shiftKey) {
+            event.preventDefault();
+            gameApp.undo();
+        } else if (key === 'y' || (key === 'z' && event.shiftKey)) {
+            event.preventDefault();
+            gameApp.redo();
+        }
+    });
+
+    // タブが隠れてる間は経過時間の時計を止める
+    document.addEventListener('visibilitychange', () => {
+        gameApp.set_page_visible(!document.hidden);
+    });
+
     // --- Canvas のリスナー --- ★★★ Rust側で設定するため、以下のリスナーは削除 ★★★
     const canvas = document.getElementById('game-canvas');
     if (!canvas) {
@@ -228,6 +377,15 @@ function getCanvasCoordinates(event) {
     return { x, y };
 }
 
+// 遊び方と山札のルールの選択欄を、今のゲームに合わせる (セーブや棋譜から続けた時用)
+function syncGameSettings() {
+    variantSelect.value = gameApp.current_variant();
+    const rules = JSON.parse(gameApp.current_rules_json());
+    drawSelect.value = String(rules.draw_count);
+    passesSelect.value = rules.max_passes === null ? '' : String(rules.max_passes);
+    foundationBackCheckbox.checked = rules.foundation_to_tableau;
+}
+
 // --- 接続状態などを表示する関数 ---
 function updateStatusDisplay() {
     if (!gameApp) return;
@@ -277,28 +435,7 @@ function updateStatusDisplay() {
     //     console.error("メッセージ処理またはRustレンダリング呼び出し中にエラー:", e);
     // }
 
-    // ★追加: メッセージ処理はここで行う (描画とは別タイミング) ★
-    //     描画は毎フレームやるけど、メッセージ処理はここ (1秒ごと) でいいかも？
-    //     もっと頻繁にしたいなら gameLoop に移してもOK
-    try {
-        // ★修正: Rust側のメッセージ処理を呼び出し、戻り値を受け取る！★
-        //   戻り値は Option<usize> 型。usize は拒否されたカードのIDだよ。
-        //   (JSでは number | undefined として扱われる)
-        const rejected_card_id = gameApp.process_received_messages();
-
-        // ★追加: 戻り値をチェックして、拒否されたカードがあれば警告を出す！★
-        //   rejected_card_id が undefined じゃなければ (つまり Some(id) だったら)
-        if (rejected_card_id !== undefined) {
-            // 警告メッセージをコンソールに出力！⚠️
-            // どのカードの移動がダメだったか ID も表示するよ。
-            console.warn(`⚠️ サーバーから移動が拒否されました！ (カードID: ${rejected_card_id}) ルールを確認してね！`);
-            // TODO: ここに、もっとリッチなフィードバック処理を追加できるよ！
-            //   例: アラートを表示する (alert(...)), カードを元の位置に戻すアニメーションを開始する、など
-        }
-
-    } catch (e) {
-        console.error("メッセージ処理中にエラー:", e);
-    }
+    // メッセージ処理は gameLoop の gameApp.tick(dt) の中でやるようになったよ
 }
 
 // --- ★ Window 用の MouseMove イベントハンドラー ★ ---